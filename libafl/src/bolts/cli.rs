@@ -221,6 +221,11 @@ pub struct FuzzerOptions {
     )]
     pub max_total_allocation: usize,
 
+    /// The maximum amount of live (allocated, but not yet freed) bytes, reporting an error if exceeded
+    #[cfg(feature = "frida_cli")]
+    #[arg(long, help_heading = "ASan Options")]
+    pub max_live_bytes: Option<usize>,
+
     /// Instruct `ASan` to panic if the max `ASan` allocation size is exceeded
     #[cfg(feature = "frida_cli")]
     #[arg(long, help_heading = "ASan Options")]
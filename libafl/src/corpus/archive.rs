@@ -0,0 +1,261 @@
+//! Corpus archives, packing all [`Testcase`]s of a [`Corpus`] (inputs and metadata) into a single file.
+//!
+//! The archive starts with [`ARCHIVE_MAGIC`] and the little-endian `u32` format version, followed by
+//! length-prefixed frames (little-endian `u64` length, then the `postcard`-serialized payload):
+//! first an [`ArchiveHeader`], then one frame per [`Testcase`].
+
+use alloc::vec::Vec;
+use std::io::{Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corpus::{Corpus, CorpusId, Testcase},
+    Error,
+};
+
+/// The magic bytes at the start of each corpus archive
+pub const ARCHIVE_MAGIC: &[u8; 8] = b"LAFLCORP";
+
+/// The current version of the archive format
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// The tag marking a [`Testcase`] as objective for [`export_objectives`], for corpora whose
+/// [`Testcase`]s were not flagged by [`Testcase::set_objective`]
+pub const OBJECTIVE_TAG: &str = "objective";
+
+/// The corpus-level data stored at the start of an archive
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ArchiveHeader {
+    /// The number of [`Testcase`]s in the archive
+    pub count: usize,
+    /// The position (as in [`Corpus::nth`]) of the current [`Testcase`], if any
+    pub current: Option<usize>,
+}
+
+fn write_frame<W>(writer: &mut W, payload: &[u8]) -> Result<(), Error>
+where
+    W: Write,
+{
+    writer.write_all(&(payload.len() as u64).to_le_bytes())?;
+    writer.write_all(payload)?;
+    Ok(())
+}
+
+fn read_frame<R>(reader: &mut R) -> Result<Vec<u8>, Error>
+where
+    R: Read,
+{
+    let mut len = [0; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    // the length is untrusted, so only allocate for the bytes actually read
+    let mut payload = Vec::new();
+    reader.by_ref().take(len).read_to_end(&mut payload)?;
+    if payload.len() as u64 != len {
+        return Err(Error::illegal_argument("Truncated corpus archive frame"));
+    }
+    Ok(payload)
+}
+
+/// Writes all [`Testcase`]s of the `corpus` to the `writer`, loading the inputs not in memory
+pub fn save_archive<C, W>(corpus: &C, writer: W) -> Result<(), Error>
+where
+    C: Corpus,
+    W: Write,
+{
+    save_archive_filtered(corpus, writer, |_| true)
+}
+
+/// Writes the objectives of the `corpus` to the `writer`, i.e. the [`Testcase`]s flagged by
+/// [`Testcase::set_objective`] or tagged with [`OBJECTIVE_TAG`]. The result is a regular archive.
+pub fn export_objectives<C, W>(corpus: &C, writer: W) -> Result<(), Error>
+where
+    C: Corpus,
+    W: Write,
+{
+    save_archive_filtered(corpus, writer, |testcase| {
+        testcase.is_objective() || testcase.has_tag(OBJECTIVE_TAG)
+    })
+}
+
+/// Writes the [`Testcase`]s of the `corpus` matching the `filter` to the `writer`
+fn save_archive_filtered<C, W, F>(corpus: &C, mut writer: W, filter: F) -> Result<(), Error>
+where
+    C: Corpus,
+    W: Write,
+    F: Fn(&Testcase<C::Input>) -> bool,
+{
+    let mut ids = Vec::new();
+    for idx in corpus.ids() {
+        if filter(&corpus.get(idx)?.borrow()) {
+            ids.push(idx);
+        }
+    }
+
+    writer.write_all(ARCHIVE_MAGIC)?;
+    writer.write_all(&ARCHIVE_VERSION.to_le_bytes())?;
+
+    let header = ArchiveHeader {
+        count: ids.len(),
+        current: corpus
+            .current()
+            .and_then(|current| ids.iter().position(|idx| *idx == current)),
+    };
+    write_frame(&mut writer, &postcard::to_allocvec(&header)?)?;
+
+    for idx in ids {
+        let mut testcase = corpus.get(idx)?.borrow_mut();
+        corpus.load_input_into(&mut testcase)?;
+        write_frame(&mut writer, &postcard::to_allocvec(&*testcase)?)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Adds all [`Testcase`]s in the archive read from `reader` to the `corpus`, returning their new ids
+pub fn load_archive<C, R>(corpus: &mut C, mut reader: R) -> Result<Vec<CorpusId>, Error>
+where
+    C: Corpus,
+    R: Read,
+{
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != ARCHIVE_MAGIC {
+        return Err(Error::illegal_argument("Not a corpus archive"));
+    }
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version > ARCHIVE_VERSION {
+        return Err(Error::illegal_argument(format!(
+            "Unsupported corpus archive version {version} (supported up to {ARCHIVE_VERSION})"
+        )));
+    }
+
+    let header: ArchiveHeader = postcard::from_bytes(&read_frame(&mut reader)?)?;
+    let mut ids = Vec::new();
+    for _ in 0..header.count {
+        let testcase: Testcase<C::Input> = postcard::from_bytes(&read_frame(&mut reader)?)?;
+        ids.push(corpus.add(testcase)?);
+    }
+    if let Some(current) = header.current {
+        *corpus.current_mut() = ids.get(current).copied();
+    }
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        corpus::{
+            archive::{ArchiveHeader, ARCHIVE_MAGIC, ARCHIVE_VERSION, OBJECTIVE_TAG},
+            Corpus, InMemoryCorpus, Testcase,
+        },
+        inputs::BytesInput,
+        schedulers::minimizer::IsFavoredMetadata,
+        state::HasMetadata,
+    };
+
+    #[test]
+    fn test_corpus_archive_roundtrip() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        for i in 0..5 {
+            let mut testcase = Testcase::new(BytesInput::new(vec![i; usize::from(i) + 1]));
+            if i % 2 == 0 {
+                testcase.add_metadata(IsFavoredMetadata {});
+            }
+            testcase.add_tag("seed");
+            corpus.add(testcase).unwrap();
+        }
+        *corpus.current_mut() = Some(corpus.nth(3));
+
+        let mut archive = Vec::new();
+        corpus.save_archive(&mut archive).unwrap();
+
+        let mut loaded = InMemoryCorpus::<BytesInput>::new();
+        let ids = loaded.load_archive(archive.as_slice()).unwrap();
+        assert_eq!(ids.len(), 5);
+        assert_eq!(loaded.count(), 5);
+        assert_eq!(*loaded.current(), Some(ids[3]));
+
+        for (original, loaded_idx) in corpus.ids().zip(ids) {
+            let original = corpus.get(original).unwrap().borrow();
+            let loaded = loaded.get(loaded_idx).unwrap().borrow();
+            assert_eq!(original.input(), loaded.input());
+            assert_eq!(
+                original.has_metadata::<IsFavoredMetadata>(),
+                loaded.has_metadata::<IsFavoredMetadata>()
+            );
+            assert!(loaded.has_tag("seed"));
+        }
+
+        // a wrong magic or a newer version is refused
+        let mut corrupted = archive.clone();
+        corrupted[0] = b'X';
+        assert!(loaded.load_archive(corrupted.as_slice()).is_err());
+        let mut newer = archive;
+        newer[8] = 0xff;
+        assert!(loaded.load_archive(newer.as_slice()).is_err());
+        assert_eq!(loaded.count(), 5);
+    }
+
+    #[test]
+    fn test_malformed_archive() {
+        let mut prefix = ARCHIVE_MAGIC.to_vec();
+        prefix.extend_from_slice(&ARCHIVE_VERSION.to_le_bytes());
+        let mut loaded = InMemoryCorpus::<BytesInput>::new();
+
+        // a huge frame length, with only a few bytes following
+        let mut huge_frame = prefix.clone();
+        huge_frame.extend_from_slice(&u64::MAX.to_le_bytes());
+        huge_frame.extend_from_slice(&[0; 4]);
+        assert!(loaded.load_archive(huge_frame.as_slice()).is_err());
+
+        // a huge testcase count, without the testcases
+        let header = postcard::to_allocvec(&ArchiveHeader {
+            count: usize::MAX,
+            current: None,
+        })
+        .unwrap();
+        let mut huge_count = prefix;
+        huge_count.extend_from_slice(&(header.len() as u64).to_le_bytes());
+        huge_count.extend_from_slice(&header);
+        assert!(loaded.load_archive(huge_count.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_export_objectives() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        for i in 0..6 {
+            let mut testcase = Testcase::new(BytesInput::new(vec![i]));
+            match i % 3 {
+                0 => testcase.set_objective(true),
+                1 => {
+                    testcase.add_tag(OBJECTIVE_TAG);
+                }
+                _ => {}
+            }
+            corpus.add(testcase).unwrap();
+        }
+        *corpus.current_mut() = Some(corpus.nth(3));
+
+        let mut archive = Vec::new();
+        corpus.export_objectives(&mut archive).unwrap();
+
+        let mut loaded = InMemoryCorpus::<BytesInput>::new();
+        let ids = loaded.load_archive(archive.as_slice()).unwrap();
+        let inputs: Vec<_> = ids
+            .iter()
+            .map(|idx| loaded.get(*idx).unwrap().borrow().input().clone().unwrap())
+            .collect();
+        assert_eq!(
+            inputs,
+            [0, 1, 3, 4].map(|i| BytesInput::new(vec![i])).to_vec()
+        );
+        assert!(loaded.get(ids[0]).unwrap().borrow().is_objective());
+        assert_eq!(*loaded.current(), Some(ids[2]));
+    }
+}
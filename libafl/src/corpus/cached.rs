@@ -1,12 +1,13 @@
 //! The [`CachedOnDiskCorpus`] stores [`Testcase`]s to disk, keeping a subset of them in memory/cache, evicting in a FIFO manner.
 
-use alloc::collections::vec_deque::VecDeque;
+use alloc::{collections::vec_deque::VecDeque, vec::Vec};
 use core::cell::RefCell;
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    bolts::HasLen,
     corpus::{
         inmemory_ondisk::InMemoryOnDiskCorpus, ondisk::OnDiskMetadataFormat, Corpus, CorpusId,
         HasTestcase, Testcase,
@@ -39,7 +40,7 @@ where
 
 impl<I> Corpus for CachedOnDiskCorpus<I>
 where
-    I: Input,
+    I: Input + HasLen,
 {
     /// Returns the number of elements
     #[inline]
@@ -129,6 +130,26 @@ where
         self.inner.nth(nth)
     }
 
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+
+    fn reindex(&mut self) -> Result<Vec<(CorpusId, CorpusId)>, Error> {
+        let mapping = self.inner.reindex()?;
+        for cached in self.cached_indexes.get_mut() {
+            if let Some((_, new_id)) = mapping.iter().find(|(old_id, _)| old_id == cached) {
+                *cached = *new_id;
+            }
+        }
+        Ok(mapping)
+    }
+
     #[inline]
     fn load_input_into(&self, testcase: &mut Testcase<Self::Input>) -> Result<(), Error> {
         self.inner.load_input_into(testcase)
@@ -142,7 +163,7 @@ where
 
 impl<I> HasTestcase for CachedOnDiskCorpus<I>
 where
-    I: Input,
+    I: Input + HasLen,
 {
     fn testcase(&self, id: CorpusId) -> Result<core::cell::Ref<Testcase<Self::Input>>, Error> {
         Ok(self.get(id)?.borrow())
@@ -1,13 +1,18 @@
 //! In-memory corpus, keeps all test cases in memory at all times
 
-use alloc::vec::Vec;
-use core::cell::RefCell;
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    cell::RefCell,
+    fmt::{self, Debug, Formatter},
+    mem::size_of,
+};
 
 use serde::{Deserialize, Serialize};
 
 use super::HasTestcase;
 use crate::{
-    corpus::{Corpus, CorpusId, Testcase},
+    bolts::{current_time, HasLen},
+    corpus::{input_hash, Corpus, CorpusId, Testcase},
     inputs::{Input, UsesInput},
     Error,
 };
@@ -180,6 +185,26 @@ where
         self.map.get(&idx)
     }
 
+    /// Iterate mutably over all testcases, in insertion order
+    #[cfg(not(feature = "corpus_btreemap"))]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (CorpusId, &mut Testcase<I>)> {
+        let mut entries: Vec<_> = self
+            .map
+            .iter_mut()
+            .map(|(idx, item)| (*idx, item.testcase.get_mut()))
+            .collect();
+        entries.sort_unstable_by_key(|(idx, _)| *idx);
+        entries.into_iter()
+    }
+
+    /// Iterate mutably over all testcases, in insertion order
+    #[cfg(feature = "corpus_btreemap")]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (CorpusId, &mut Testcase<I>)> {
+        self.map
+            .iter_mut()
+            .map(|(idx, testcase)| (*idx, testcase.get_mut()))
+    }
+
     /// Get the next id given a `CorpusId` (creation order)
     #[cfg(not(feature = "corpus_btreemap"))]
     #[must_use]
@@ -270,12 +295,95 @@ where
         self.map.iter().next_back().map(|x| *x.0)
     }
 
+    /// Remove all testcases after the first `len` ones (in insertion order)
+    #[cfg(not(feature = "corpus_btreemap"))]
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.keys.len() {
+            return;
+        }
+        for idx in self.keys.drain(len..) {
+            self.map.remove(&idx);
+        }
+        self.last_idx = self.keys.last().copied();
+        if let Some(last_idx) = self.last_idx {
+            self.map.get_mut(&last_idx).unwrap().next = None;
+        } else {
+            self.first_idx = None;
+        }
+    }
+
+    /// Remove all testcases after the first `len` ones (in insertion order)
+    #[cfg(feature = "corpus_btreemap")]
+    pub fn truncate(&mut self, len: usize) {
+        if let Some(first_removed) = self.keys.get(len).copied() {
+            self.keys.truncate(len);
+            self.map.split_off(&first_removed);
+        }
+    }
+
+    /// Remove all testcases, returning them in insertion order
+    #[cfg(not(feature = "corpus_btreemap"))]
+    pub fn drain(&mut self) -> Vec<Testcase<I>> {
+        self.first_idx = None;
+        self.last_idx = None;
+        core::mem::take(&mut self.keys)
+            .into_iter()
+            .map(|idx| self.map.remove(&idx).unwrap().testcase.into_inner())
+            .collect()
+    }
+
+    /// Remove all testcases, returning them in insertion order
+    #[cfg(feature = "corpus_btreemap")]
+    pub fn drain(&mut self) -> Vec<Testcase<I>> {
+        self.keys.clear();
+        core::mem::take(&mut self.map)
+            .into_values()
+            .map(RefCell::into_inner)
+            .collect()
+    }
+
+    /// Re-inserts all testcases in insertion order, assigning them the ids `0..n`.
+    /// Returns the old and the new id of each testcase.
+    pub fn reindex(&mut self) -> Vec<(CorpusId, CorpusId)> {
+        let old_ids = self.keys.clone();
+        let testcases = self.drain();
+        self.progressive_idx = 0;
+        old_ids
+            .into_iter()
+            .zip(testcases)
+            .map(|(old_id, testcase)| (old_id, self.insert(RefCell::new(testcase))))
+            .collect()
+    }
+
+    /// Reserve capacity for at least `additional` more testcases
+    pub fn reserve(&mut self, additional: usize) {
+        #[cfg(not(feature = "corpus_btreemap"))]
+        self.map.reserve(additional);
+        self.keys.reserve(additional);
+    }
+
+    /// Release the unused capacity of the storage
+    pub fn shrink_to_fit(&mut self) {
+        #[cfg(not(feature = "corpus_btreemap"))]
+        self.map.shrink_to_fit();
+        self.keys.shrink_to_fit();
+    }
+
     /// Create new `TestcaseStorage`
     #[must_use]
     pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create new `TestcaseStorage`, preallocated to hold at least `capacity` testcases
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
         Self {
+            #[cfg(not(feature = "corpus_btreemap"))]
+            map: TestcaseStorageMap::with_capacity(capacity),
+            #[cfg(feature = "corpus_btreemap")]
             map: TestcaseStorageMap::default(),
-            keys: vec![],
+            keys: Vec::with_capacity(capacity),
             progressive_idx: 0,
             #[cfg(not(feature = "corpus_btreemap"))]
             first_idx: None,
@@ -285,6 +393,34 @@ where
     }
 }
 
+/// A callback invoked with the id and the [`Testcase`] of each [`Testcase`] added to an
+/// [`InMemoryCorpus`], see [`InMemoryCorpus::set_on_add_hook`]
+pub type OnAddHook<I> = Box<dyn FnMut(CorpusId, &Testcase<I>) + Send>;
+
+/// Holds the optional [`OnAddHook`] of an [`InMemoryCorpus`].
+/// The hook is a runtime callback: it is neither cloned nor serialized.
+struct OnAddHookSlot<I>(Option<OnAddHook<I>>);
+
+impl<I> Default for OnAddHookSlot<I> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<I> Clone for OnAddHookSlot<I> {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+impl<I> Debug for OnAddHookSlot<I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OnAddHookSlot")
+            .field(&self.0.as_ref().map(|_| "<hook>"))
+            .finish()
+    }
+}
+
 /// A corpus handling all in memory.
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
 #[serde(bound = "I: serde::de::DeserializeOwned")]
@@ -294,6 +430,13 @@ where
 {
     storage: TestcaseStorage<I>,
     current: Option<CorpusId>,
+    /// The number of stored inputs per [`input_hash`], if enabled
+    hash_index: Option<hashbrown::HashMap<u64, usize>>,
+    /// Invoked on each added [`Testcase`]
+    #[serde(skip)]
+    on_add: OnAddHookSlot<I>,
+    /// If empty inputs are rejected, see [`InMemoryCorpus::set_reject_empty`]
+    reject_empty: bool,
 }
 
 impl<I> UsesInput for InMemoryCorpus<I>
@@ -305,7 +448,7 @@ where
 
 impl<I> Corpus for InMemoryCorpus<I>
 where
-    I: Input,
+    I: Input + HasLen,
 {
     /// Returns the number of elements
     #[inline]
@@ -315,25 +458,57 @@ where
 
     /// Add an entry to the corpus and return its index
     #[inline]
-    fn add(&mut self, testcase: Testcase<I>) -> Result<CorpusId, Error> {
-        Ok(self.storage.insert(RefCell::new(testcase)))
+    fn add(&mut self, mut testcase: Testcase<I>) -> Result<CorpusId, Error> {
+        self.check_rejected(&testcase)?;
+        if testcase.created_at().is_none() {
+            testcase.set_created_at(current_time());
+        }
+        self.index_insert(&testcase)?;
+        let idx = self.storage.insert(RefCell::new(testcase));
+        if let (Some(hook), Some(testcase)) = (self.on_add.0.as_mut(), self.storage.get(idx)) {
+            hook(idx, &testcase.borrow());
+        }
+        Ok(idx)
+    }
+
+    /// Add all entries to the corpus, reserving the space for all of them upfront.
+    /// If any entry is rejected, none is added.
+    fn add_all(&mut self, testcases: Vec<Testcase<I>>) -> Result<Vec<CorpusId>, Error> {
+        for testcase in &testcases {
+            self.check_rejected(testcase)?;
+        }
+        self.reserve(testcases.len());
+        testcases
+            .into_iter()
+            .map(|testcase| self.add(testcase))
+            .collect()
     }
 
     /// Replaces the testcase at the given idx
     #[inline]
     fn replace(&mut self, idx: CorpusId, testcase: Testcase<I>) -> Result<Testcase<I>, Error> {
-        self.storage
+        let new_hash = self.testcase_hash(&testcase)?;
+        let entry = self
+            .storage
             .replace(idx, testcase)
-            .ok_or_else(|| Error::key_not_found(format!("Index {idx} not found")))
+            .ok_or_else(|| Error::key_not_found(format!("Index {idx} not found")))?;
+        self.index_remove(&entry)?;
+        if let (Some(index), Some(hash)) = (self.hash_index.as_mut(), new_hash) {
+            *index.entry(hash).or_default() += 1;
+        }
+        Ok(entry)
     }
 
     /// Removes an entry from the corpus, returning it if it was present.
     #[inline]
     fn remove(&mut self, idx: CorpusId) -> Result<Testcase<I>, Error> {
-        self.storage
+        let entry = self
+            .storage
             .remove(idx)
             .map(|x| x.take())
-            .ok_or_else(|| Error::key_not_found(format!("Index {idx} not found")))
+            .ok_or_else(|| Error::key_not_found(format!("Index {idx} not found")))?;
+        self.index_remove(&entry)?;
+        Ok(entry)
     }
 
     /// Get by id
@@ -381,6 +556,60 @@ where
         self.storage.keys[nth]
     }
 
+    #[inline]
+    fn truncate(&mut self, len: usize) -> Result<(), Error> {
+        self.storage.truncate(len);
+        if let Some(current) = self.current {
+            if self.storage.get(current).is_none() {
+                self.current = None;
+            }
+        }
+        if self.hash_index.is_some() {
+            self.enable_hash_index()?;
+        }
+        Ok(())
+    }
+
+    /// Assigns the ids `0..n` to the stored testcases, in insertion order, and remaps the current id.
+    /// The hash index only counts inputs, so it stays valid.
+    fn reindex(&mut self) -> Result<Vec<(CorpusId, CorpusId)>, Error> {
+        let mapping = self.storage.reindex();
+        if let Some(current) = self.current {
+            self.current = mapping
+                .iter()
+                .find(|(old_id, _)| *old_id == current)
+                .map(|(_, new_id)| *new_id);
+        }
+        Ok(mapping)
+    }
+
+    /// Checks if an input with the same [`input_hash`] as the given one is stored in this corpus,
+    /// using the hash index if enabled, see [`InMemoryCorpus::enable_hash_index`].
+    fn contains(&self, input: &I) -> bool {
+        input_hash(input).map_or(false, |hash| match &self.hash_index {
+            Some(index) => index.contains_key(&hash),
+            None => self.storage.keys.iter().any(|idx| {
+                self.storage.get(*idx).map_or(false, |testcase| {
+                    testcase
+                        .borrow()
+                        .input()
+                        .as_ref()
+                        .map_or(false, |other| input_hash(other).ok() == Some(hash))
+                })
+            }),
+        })
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.storage.reserve(additional);
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.storage.shrink_to_fit();
+    }
+
     #[inline]
     fn load_input_into(&self, _: &mut Testcase<Self::Input>) -> Result<(), Error> {
         // Inputs never get evicted, nothing to load here.
@@ -395,7 +624,7 @@ where
 
 impl<I> HasTestcase for InMemoryCorpus<I>
 where
-    I: Input,
+    I: Input + HasLen,
 {
     fn testcase(
         &self,
@@ -423,7 +652,542 @@ where
         Self {
             storage: TestcaseStorage::new(),
             current: None,
+            hash_index: None,
+            on_add: OnAddHookSlot::default(),
+            reject_empty: false,
+        }
+    }
+
+    /// Creates a new [`InMemoryCorpus`], preallocated to hold at least `capacity` [`Testcase`]`s`.
+    /// Useful to avoid reallocations when importing a large amount of seeds.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            storage: TestcaseStorage::with_capacity(capacity),
+            current: None,
+            hash_index: None,
+            on_add: OnAddHookSlot::default(),
+            reject_empty: false,
+        }
+    }
+
+    /// Sets the [`OnAddHook`], invoked with the id and the [`Testcase`] of each [`Testcase`] added
+    /// to this corpus, replacing the previous hook, if any
+    #[must_use]
+    pub fn with_on_add_hook<F>(mut self, hook: F) -> Self
+    where
+        F: FnMut(CorpusId, &Testcase<I>) + Send + 'static,
+    {
+        self.set_on_add_hook(hook);
+        self
+    }
+
+    /// Sets the [`OnAddHook`], invoked with the id and the [`Testcase`] of each [`Testcase`] added
+    /// to this corpus, replacing the previous hook, if any.
+    /// The hook is not serialized, and not cloned along with the corpus.
+    pub fn set_on_add_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut(CorpusId, &Testcase<I>) + Send + 'static,
+    {
+        self.on_add = OnAddHookSlot(Some(Box::new(hook)));
+    }
+
+    /// Removes the [`OnAddHook`], if any
+    pub fn clear_on_add_hook(&mut self) {
+        self.on_add = OnAddHookSlot::default();
+    }
+
+    /// Sets if [`Corpus::add`] rejects [`Testcase`]`s` with an empty input, with an error, leaving
+    /// the corpus unchanged
+    #[must_use]
+    pub fn with_reject_empty(mut self, reject_empty: bool) -> Self {
+        self.set_reject_empty(reject_empty);
+        self
+    }
+
+    /// Sets if [`Corpus::add`] rejects [`Testcase`]`s` with an empty input, with an error, leaving
+    /// the corpus unchanged. Disabled by default.
+    /// Unlike the [`OnAddHook`], this setting is serialized along with the corpus.
+    pub fn set_reject_empty(&mut self, reject_empty: bool) {
+        self.reject_empty = reject_empty;
+    }
+
+    /// Returns `true` if [`Corpus::add`] rejects empty inputs, see
+    /// [`InMemoryCorpus::set_reject_empty`]
+    #[must_use]
+    pub fn reject_empty(&self) -> bool {
+        self.reject_empty
+    }
+
+    /// Enables an index of the [`input_hash`] of all stored inputs, built from the current
+    /// [`Testcase`]`s`, so that [`Corpus::contains`] does not need to hash every input.
+    /// Keeping the index up to date costs a hash on every add, replace, and remove.
+    pub fn enable_hash_index(&mut self) -> Result<(), Error> {
+        let mut index = hashbrown::HashMap::with_capacity(self.storage.keys.len());
+        for idx in &self.storage.keys {
+            if let Some(testcase) = self.storage.get(*idx) {
+                if let Some(input) = testcase.borrow().input() {
+                    *index.entry(input_hash(input)?).or_default() += 1;
+                }
+            }
+        }
+        self.hash_index = Some(index);
+        Ok(())
+    }
+
+    /// Disables the index of input hashes, see [`InMemoryCorpus::enable_hash_index`].
+    pub fn disable_hash_index(&mut self) {
+        self.hash_index = None;
+    }
+
+    /// Fails if the given [`Testcase`] is rejected, see [`InMemoryCorpus::set_reject_empty`]
+    fn check_rejected(&self, testcase: &Testcase<I>) -> Result<(), Error>
+    where
+        I: HasLen,
+    {
+        if self.reject_empty && testcase.input().as_ref().map_or(false, HasLen::is_empty) {
+            return Err(Error::illegal_argument(
+                "Empty inputs are rejected by this corpus",
+            ));
+        }
+        Ok(())
+    }
+
+    /// The [`input_hash`] of the input of the given [`Testcase`], if the hash index is enabled
+    fn testcase_hash(&self, testcase: &Testcase<I>) -> Result<Option<u64>, Error> {
+        match (&self.hash_index, testcase.input()) {
+            (Some(_), Some(input)) => Ok(Some(input_hash(input)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn index_insert(&mut self, testcase: &Testcase<I>) -> Result<(), Error> {
+        if let Some(hash) = self.testcase_hash(testcase)? {
+            *self.hash_index.as_mut().unwrap().entry(hash).or_default() += 1;
+        }
+        Ok(())
+    }
+
+    fn index_remove(&mut self, testcase: &Testcase<I>) -> Result<(), Error> {
+        if let Some(hash) = self.testcase_hash(testcase)? {
+            let index = self.hash_index.as_mut().unwrap();
+            if let Some(count) = index.get_mut(&hash) {
+                *count -= 1;
+                if *count == 0 {
+                    index.remove(&hash);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Iterates mutably over all [`Testcase`]`s` of this corpus, in insertion order, e.g. to rewrite
+    /// all inputs at once.
+    /// This requires exclusive access to the corpus: the [`Testcase`]`s` are reached through
+    /// [`RefCell::get_mut`], so no [`Testcase`] may be borrowed (see [`Corpus::get`]) meanwhile.
+    /// If the hash index is enabled, rebuild it with [`InMemoryCorpus::enable_hash_index`] after
+    /// changing inputs.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (CorpusId, &mut Testcase<I>)> {
+        self.storage.iter_mut()
+    }
+
+    /// Removes all [`Testcase`]`s` from this corpus, returning them (in insertion order) without cloning.
+    /// The current [`CorpusId`] is reset to `None`.
+    pub fn drain(&mut self) -> Vec<Testcase<I>> {
+        self.current = None;
+        if let Some(index) = self.hash_index.as_mut() {
+            index.clear();
+        }
+        self.storage.drain()
+    }
+
+    /// Estimates the memory used by this [`InMemoryCorpus`], in bytes.
+    /// This sums the byte size of all inputs and the allocated capacity of the storage.
+    #[must_use]
+    pub fn memory_footprint(&self) -> usize
+    where
+        I: HasLen,
+    {
+        #[cfg(not(feature = "corpus_btreemap"))]
+        let entries = self.storage.map.capacity() * size_of::<(CorpusId, TestcaseStorageItem<I>)>();
+        #[cfg(feature = "corpus_btreemap")]
+        let entries = self.storage.map.len() * size_of::<(CorpusId, RefCell<Testcase<I>>)>();
+
+        let inputs: usize = self
+            .storage
+            .keys
+            .iter()
+            .filter_map(|idx| self.storage.get(*idx))
+            .map(|testcase| testcase.borrow().input().as_ref().map_or(0, HasLen::len))
+            .sum();
+
+        size_of::<Self>() + entries + self.storage.keys.capacity() * size_of::<CorpusId>() + inputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::time::Duration;
+    use std::{
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use crate::{
+        corpus::{Corpus, CorpusId, InMemoryCorpus, Testcase},
+        inputs::{BytesInput, HasBytesVec},
+    };
+
+    #[test]
+    fn test_inmemory_corpus_with_capacity() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::with_capacity(1024);
+        assert!(corpus.storage.keys.capacity() >= 1024);
+        #[cfg(not(feature = "corpus_btreemap"))]
+        assert!(corpus.storage.map.capacity() >= 1024);
+
+        corpus.add(Testcase::new(BytesInput::new(vec![0]))).unwrap();
+        corpus.reserve(4096);
+        assert!(corpus.storage.keys.capacity() >= 4097);
+    }
+
+    #[test]
+    fn test_inmemory_corpus_shrink_to_fit() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let ids: Vec<_> = (0..1024)
+            .map(|i| {
+                corpus
+                    .add(Testcase::new(BytesInput::new(vec![0; i % 16])))
+                    .unwrap()
+            })
+            .collect();
+        for idx in &ids[8..] {
+            corpus.remove(*idx).unwrap();
+        }
+
+        let keys_capacity = corpus.storage.keys.capacity();
+        #[cfg(not(feature = "corpus_btreemap"))]
+        let map_capacity = corpus.storage.map.capacity();
+        let footprint = corpus.memory_footprint();
+
+        corpus.shrink_to_fit();
+        assert_eq!(corpus.count(), 8);
+        assert!(corpus.storage.keys.capacity() < keys_capacity);
+        #[cfg(not(feature = "corpus_btreemap"))]
+        assert!(corpus.storage.map.capacity() < map_capacity);
+        assert!(corpus.memory_footprint() < footprint);
+    }
+
+    #[test]
+    fn test_inmemory_corpus_drain() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        for i in 0..4 {
+            corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap();
+        }
+        *corpus.current_mut() = corpus.first();
+
+        let drained = corpus.drain();
+        let inputs: Vec<_> = drained
+            .iter()
+            .map(|testcase| testcase.input().clone().unwrap())
+            .collect();
+        assert_eq!(
+            inputs,
+            (0..4).map(|i| BytesInput::new(vec![i])).collect::<Vec<_>>()
+        );
+
+        assert_eq!(corpus.count(), 0);
+        assert_eq!(*corpus.current(), None);
+        assert_eq!(corpus.first(), None);
+        assert_eq!(corpus.last(), None);
+        assert_eq!(corpus.ids().count(), 0);
+
+        // the corpus stays usable
+        let idx = corpus.add(Testcase::new(BytesInput::new(vec![4]))).unwrap();
+        assert_eq!(corpus.first(), Some(idx));
+        assert_eq!(corpus.count(), 1);
+    }
+
+    #[test]
+    fn test_inmemory_corpus_contains() {
+        for indexed in [false, true] {
+            let mut corpus = InMemoryCorpus::<BytesInput>::new();
+            if indexed {
+                corpus.enable_hash_index().unwrap();
+            }
+            let idx = corpus
+                .add(Testcase::new(BytesInput::new(vec![1, 2, 3])))
+                .unwrap();
+            assert!(corpus.contains(&BytesInput::new(vec![1, 2, 3])));
+            assert!(!corpus.contains(&BytesInput::new(vec![1, 2, 4])));
+
+            corpus
+                .replace(idx, Testcase::new(BytesInput::new(vec![1, 2, 4])))
+                .unwrap();
+            assert!(!corpus.contains(&BytesInput::new(vec![1, 2, 3])));
+            assert!(corpus.contains(&BytesInput::new(vec![1, 2, 4])));
+
+            corpus.remove(idx).unwrap();
+            assert!(!corpus.contains(&BytesInput::new(vec![1, 2, 4])));
+        }
+    }
+
+    #[test]
+    fn test_inmemory_corpus_reindex() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let ids: Vec<_> = (0..6)
+            .map(|i| corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap())
+            .collect();
+        for idx in ids.iter().step_by(2) {
+            corpus.remove(*idx).unwrap();
+        }
+        *corpus.current_mut() = Some(ids[3]);
+
+        let mapping = corpus.reindex().unwrap();
+        assert_eq!(
+            mapping,
+            vec![
+                (ids[1], CorpusId::from(0_usize)),
+                (ids[3], CorpusId::from(1_usize)),
+                (ids[5], CorpusId::from(2_usize)),
+            ]
+        );
+        assert_eq!(
+            corpus.ids().collect::<Vec<_>>(),
+            (0..3_usize).map(CorpusId::from).collect::<Vec<_>>()
+        );
+        assert_eq!(*corpus.current(), Some(CorpusId::from(1_usize)));
+        for (old_id, new_id) in mapping {
+            assert_eq!(
+                corpus.get(new_id).unwrap().borrow().input(),
+                &Some(BytesInput::new(vec![u8::try_from(old_id.0).unwrap()]))
+            );
+        }
+
+        // new testcases continue after the dense range
+        let idx = corpus.add(Testcase::new(BytesInput::new(vec![6]))).unwrap();
+        assert_eq!(idx, CorpusId::from(3_usize));
+        assert_eq!(corpus.last(), Some(idx));
+    }
+
+    #[test]
+    fn test_inmemory_corpus_truncate() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let ids: Vec<_> = (0..5)
+            .map(|i| corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap())
+            .collect();
+
+        // current stays, if it is not cut
+        *corpus.current_mut() = Some(ids[1]);
+        corpus.truncate(4).unwrap();
+        assert_eq!(corpus.count(), 4);
+        assert_eq!(*corpus.current(), Some(ids[1]));
+
+        // current is reset, if it pointed past the cut
+        *corpus.current_mut() = Some(ids[3]);
+        corpus.truncate(2).unwrap();
+        assert_eq!(corpus.count(), 2);
+        assert_eq!(*corpus.current(), None);
+        assert_eq!(corpus.ids().collect::<Vec<_>>(), ids[..2]);
+        assert_eq!(corpus.last(), Some(ids[1]));
+        assert!(corpus.get(ids[2]).is_err());
+
+        // the corpus stays usable
+        let idx = corpus.add(Testcase::new(BytesInput::new(vec![5]))).unwrap();
+        assert_eq!(corpus.next(ids[1]), Some(idx));
+        assert_eq!(corpus.count(), 3);
+
+        corpus.truncate(0).unwrap();
+        assert_eq!(corpus.count(), 0);
+        assert_eq!(corpus.first(), None);
+        assert_eq!(corpus.last(), None);
+    }
+
+    #[test]
+    fn test_inmemory_corpus_on_add_hook() {
+        let added = Arc::new(Mutex::new(Vec::new()));
+        let recorded = added.clone();
+        let mut corpus =
+            InMemoryCorpus::<BytesInput>::new().with_on_add_hook(move |idx, testcase| {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((idx, testcase.input().clone().unwrap()));
+            });
+
+        let ids: Vec<_> = (0..3)
+            .map(|i| corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap())
+            .collect();
+        assert_eq!(
+            *added.lock().unwrap(),
+            [
+                (ids[0], BytesInput::new(vec![0])),
+                (ids[1], BytesInput::new(vec![1])),
+                (ids[2], BytesInput::new(vec![2]))
+            ]
+        );
+
+        // the hook is skipped by serde
+        let serialized = postcard::to_allocvec(&corpus).unwrap();
+        let mut loaded: InMemoryCorpus<BytesInput> = postcard::from_bytes(&serialized).unwrap();
+        assert_eq!(loaded.count(), 3);
+        loaded.add(Testcase::new(BytesInput::new(vec![3]))).unwrap();
+        assert_eq!(added.lock().unwrap().len(), 3);
+
+        corpus.clear_on_add_hook();
+        corpus.add(Testcase::new(BytesInput::new(vec![4]))).unwrap();
+        assert_eq!(added.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_inmemory_corpus_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<InMemoryCorpus<BytesInput>>();
+
+        // a corpus with a hook can still be moved to another thread
+        let mut corpus = InMemoryCorpus::<BytesInput>::new().with_on_add_hook(|_, _| {});
+        corpus.add(Testcase::new(BytesInput::new(vec![0]))).unwrap();
+        let count = thread::spawn(move || corpus.count()).join().unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_inmemory_corpus_reject_empty() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        assert!(!corpus.reject_empty());
+        corpus.add(Testcase::new(BytesInput::new(vec![]))).unwrap();
+        assert_eq!(corpus.count(), 1);
+
+        let mut corpus = InMemoryCorpus::<BytesInput>::new().with_reject_empty(true);
+        assert!(corpus.reject_empty());
+        corpus.add(Testcase::new(BytesInput::new(vec![1]))).unwrap();
+        assert!(corpus.add(Testcase::new(BytesInput::new(vec![]))).is_err());
+        assert_eq!(corpus.count(), 1);
+        assert_eq!(corpus.ids().count(), 1);
+
+        // the setting survives a restart, like the rest of the corpus
+        let serialized = postcard::to_allocvec(&corpus).unwrap();
+        let mut restored: InMemoryCorpus<BytesInput> = postcard::from_bytes(&serialized).unwrap();
+        assert!(restored.reject_empty());
+        assert!(restored
+            .add(Testcase::new(BytesInput::new(vec![])))
+            .is_err());
+
+        corpus.set_reject_empty(false);
+        corpus.add(Testcase::new(BytesInput::new(vec![]))).unwrap();
+        assert_eq!(corpus.count(), 2);
+    }
+
+    #[test]
+    fn test_inmemory_corpus_add_all() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        corpus.add(Testcase::new(BytesInput::new(vec![0]))).unwrap();
+        let ids = corpus
+            .add_all(
+                (1..=5)
+                    .map(|i| Testcase::new(BytesInput::new(vec![i])))
+                    .collect(),
+            )
+            .unwrap();
+        assert_eq!(ids, (1..=5).map(CorpusId::from).collect::<Vec<_>>());
+        assert_eq!(corpus.count(), 6);
+        for (id, i) in ids.iter().zip(1..=5) {
+            assert_eq!(
+                corpus.get(*id).unwrap().borrow().input(),
+                &Some(BytesInput::new(vec![i]))
+            );
+        }
+
+        // a rejected entry rejects the whole batch
+        corpus.set_reject_empty(true);
+        assert!(corpus
+            .add_all(vec![
+                Testcase::new(BytesInput::new(vec![6])),
+                Testcase::new(BytesInput::new(vec![])),
+            ])
+            .is_err());
+        assert_eq!(corpus.count(), 6);
+        assert_eq!(corpus.add_all(Vec::new()).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_inmemory_corpus_replace() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let idx = corpus.add(Testcase::new(BytesInput::new(vec![1]))).unwrap();
+        let mut replacement = Testcase::new(BytesInput::new(vec![2]));
+        replacement.add_tag("replacement");
+
+        let old = corpus.replace(idx, replacement).unwrap();
+        assert_eq!(old.input(), &Some(BytesInput::new(vec![1])));
+        assert!(!old.has_tag("replacement"));
+        let current = corpus.get(idx).unwrap().borrow();
+        assert_eq!(current.input(), &Some(BytesInput::new(vec![2])));
+        assert!(current.has_tag("replacement"));
+        drop(current);
+
+        corpus.remove(idx).unwrap();
+        assert!(corpus
+            .replace(idx, Testcase::new(BytesInput::new(vec![3])))
+            .is_err());
+    }
+
+    #[test]
+    fn test_inmemory_corpus_created_at() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let ids: Vec<_> = (0..3)
+            .map(|i| {
+                thread::sleep(Duration::from_millis(2));
+                corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap()
+            })
+            .collect();
+        let created_at: Vec<_> = ids
+            .iter()
+            .map(|id| corpus.get(*id).unwrap().borrow().created_at().unwrap())
+            .collect();
+        assert!(created_at.windows(2).all(|w| w[0] <= w[1]));
+
+        // the timestamps survive serde, and re-adding a testcase keeps its timestamp
+        let serialized = postcard::to_allocvec(&corpus).unwrap();
+        let mut loaded: InMemoryCorpus<BytesInput> = postcard::from_bytes(&serialized).unwrap();
+        for (id, created_at) in ids.iter().zip(&created_at) {
+            assert_eq!(
+                loaded.get(*id).unwrap().borrow().created_at(),
+                Some(*created_at)
+            );
+        }
+        let testcase = loaded.remove(ids[0]).unwrap();
+        let id = loaded.add(testcase).unwrap();
+        assert_eq!(
+            loaded.get(id).unwrap().borrow().created_at(),
+            Some(created_at[0])
+        );
+    }
+
+    #[test]
+    fn test_inmemory_corpus_iter_mut() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let ids: Vec<_> = (1..=4)
+            .map(|i| corpus.add(Testcase::new(BytesInput::new(vec![i; i as usize]))))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        corpus.remove(ids[1]).unwrap();
+
+        // double the length of every input
+        let mut visited = Vec::new();
+        for (id, testcase) in corpus.iter_mut() {
+            visited.push(id);
+            let bytes = testcase.input_mut().as_mut().unwrap().bytes_mut();
+            bytes.extend_from_within(..);
+        }
+        assert_eq!(visited, vec![ids[0], ids[2], ids[3]]);
+
+        for (id, i) in visited.iter().zip([1_u8, 3, 4]) {
+            assert_eq!(
+                corpus.get(*id).unwrap().borrow().input(),
+                &Some(BytesInput::new(vec![i; 2 * i as usize]))
+            );
         }
+        assert_eq!(corpus.count(), 3);
     }
 }
 
@@ -3,7 +3,7 @@
 //! For a lower memory footprint, consider using [`crate::corpus::CachedOnDiskCorpus`]
 //! which only stores a certain number of [`Testcase`]s and removes additional ones in a FIFO manner.
 
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
 use core::{cell::RefCell, time::Duration};
 #[cfg(feature = "std")]
 use std::{fs, fs::File, io::Write};
@@ -21,7 +21,7 @@ use super::{
 #[cfg(feature = "gzip")]
 use crate::bolts::compress::GzipCompressor;
 use crate::{
-    bolts::serdeany::SerdeAnyMap,
+    bolts::{serdeany::SerdeAnyMap, HasLen},
     corpus::{Corpus, CorpusId, InMemoryCorpus, Testcase},
     inputs::{Input, UsesInput},
     state::HasMetadata,
@@ -61,7 +61,7 @@ where
 
 impl<I> Corpus for InMemoryOnDiskCorpus<I>
 where
-    I: Input,
+    I: Input + HasLen,
 {
     /// Returns the number of elements
     #[inline]
@@ -141,6 +141,21 @@ where
         self.inner.nth(nth)
     }
 
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+
+    #[inline]
+    fn reindex(&mut self) -> Result<Vec<(CorpusId, CorpusId)>, Error> {
+        self.inner.reindex()
+    }
+
     fn load_input_into(&self, testcase: &mut Testcase<Self::Input>) -> Result<(), Error> {
         if testcase.input_mut().is_none() {
             let Some(file_path) = testcase.file_path().as_ref() else {
@@ -166,7 +181,7 @@ where
 
 impl<I> HasTestcase for InMemoryOnDiskCorpus<I>
 where
-    I: Input,
+    I: Input + HasLen,
 {
     fn testcase(
         &self,
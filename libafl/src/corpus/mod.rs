@@ -21,15 +21,32 @@ pub mod cached;
 #[cfg(feature = "std")]
 pub use cached::CachedOnDiskCorpus;
 
+#[cfg(feature = "std")]
+pub mod archive;
+
 #[cfg(feature = "cmin")]
 pub mod minimizer;
-use core::{cell::RefCell, fmt};
+use alloc::vec::Vec;
+use core::{
+    cell::RefCell,
+    fmt,
+    hash::{BuildHasher, Hasher},
+};
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
 
+use ahash::RandomState;
 #[cfg(feature = "cmin")]
 pub use minimizer::*;
 use serde::{Deserialize, Serialize};
 
-use crate::{inputs::UsesInput, Error};
+use crate::{
+    bolts::{rands::Rand, serdeany::SerdeAnyMap, HasLen},
+    inputs::{HasBytesVec, Input, UsesInput},
+    schedulers::minimizer::IsFavoredMetadata,
+    state::HasMetadata,
+    Error,
+};
 
 /// An abstraction for the index that identify a testcase in the corpus
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
@@ -61,6 +78,23 @@ impl From<CorpusId> for usize {
     }
 }
 
+/// A summary of the [`Testcase`]s in a [`Corpus`], as returned by [`Corpus::stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CorpusStats {
+    /// The number of [`Testcase`]s
+    pub count: usize,
+    /// The sum of the lengths of all inputs, in bytes
+    pub total_bytes: usize,
+    /// The average input length
+    pub average_len: f64,
+    /// The median input length (the upper median, for an even amount of [`Testcase`]s)
+    pub median_len: usize,
+    /// The number of [`Testcase`]s marked as favored by a minimizer scheduler
+    pub favored: usize,
+    /// The number of disabled [`Testcase`]s
+    pub disabled: usize,
+}
+
 /// Utility macro to call `Corpus::random_id`
 #[macro_export]
 macro_rules! random_corpus_id {
@@ -71,6 +105,16 @@ macro_rules! random_corpus_id {
     }};
 }
 
+/// Computes the stable hash of an input, as used by [`Corpus::contains`]
+pub fn input_hash<I>(input: &I) -> Result<u64, Error>
+where
+    I: Input,
+{
+    let mut hasher = RandomState::with_seeds(0, 0, 0, 0).build_hasher();
+    hasher.write(&postcard::to_allocvec(input)?);
+    Ok(hasher.finish())
+}
+
 /// Corpus with all current [`Testcase`]s, or solutions
 pub trait Corpus: UsesInput + Serialize + for<'de> Deserialize<'de> {
     /// Returns the number of elements
@@ -84,6 +128,16 @@ pub trait Corpus: UsesInput + Serialize + for<'de> Deserialize<'de> {
     /// Add an entry to the corpus and return its index
     fn add(&mut self, testcase: Testcase<Self::Input>) -> Result<CorpusId, Error>;
 
+    /// Add all the given entries to the corpus, in order, and return their indices.
+    /// By default, this reserves the space for all entries, and adds them one by one.
+    fn add_all(&mut self, testcases: Vec<Testcase<Self::Input>>) -> Result<Vec<CorpusId>, Error> {
+        self.reserve(testcases.len());
+        testcases
+            .into_iter()
+            .map(|testcase| self.add(testcase))
+            .collect()
+    }
+
     /// Replaces the [`Testcase`] at the given idx, returning the existing.
     fn replace(
         &mut self,
@@ -131,6 +185,225 @@ pub trait Corpus: UsesInput + Serialize + for<'de> Deserialize<'de> {
             .expect("Failed to get the {nth} CorpusId")
     }
 
+    /// Get the id of the nth enabled [`Testcase`], skipping the ones with a
+    /// [`Testcase::disabled_reason`], e.g. to schedule fairly over the enabled [`Testcase`]s.
+    /// Returns `None` if fewer than `nth + 1` [`Testcase`]s are enabled.
+    fn nth_enabled(&self, nth: usize) -> Result<Option<CorpusId>, Error> {
+        let mut enabled = 0;
+        for id in self.ids() {
+            if self.get(id)?.borrow().disabled_reason().is_none() {
+                if enabled == nth {
+                    return Ok(Some(id));
+                }
+                enabled += 1;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the position (as in [`Corpus::nth`]) of the given [`Testcase`], comparing by reference identity.
+    /// Returns `None` if the [`Testcase`] is not stored in this corpus.
+    fn position_of(&self, testcase: &RefCell<Testcase<Self::Input>>) -> Option<usize> {
+        self.ids().position(|id| {
+            self.get(id)
+                .map_or(false, |other| core::ptr::eq(other, testcase))
+        })
+    }
+
+    /// Get the ids of all [`Testcase`]s with the given tag, see [`Testcase::add_tag`]
+    fn find_by_tag(&self, tag: &str) -> Result<Vec<CorpusId>, Error> {
+        let mut ids = Vec::new();
+        for id in self.ids() {
+            if self.get(id)?.borrow().has_tag(tag) {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Checks if an input with the same [`input_hash`] as the given one is stored in this corpus.
+    /// By default, this hashes every stored input, loading it from the storage if needed.
+    fn contains(&self, input: &Self::Input) -> bool {
+        input_hash(input).map_or(false, |hash| {
+            self.ids().any(|id| {
+                self.cloned_input_for_id(id)
+                    .and_then(|other| input_hash(&other))
+                    .map_or(false, |other| other == hash)
+            })
+        })
+    }
+
+    /// Replaces the whole metadata map of the [`Testcase`] with the given id, dropping all
+    /// previous metadata. Fails if no [`Testcase`] with the given id is stored in this corpus.
+    fn replace_metadata(&mut self, id: CorpusId, metadata: SerdeAnyMap) -> Result<(), Error> {
+        *self.get(id)?.borrow_mut().metadata_map_mut() = metadata;
+        Ok(())
+    }
+
+    /// Calls `f` with the id and the metadata map of each [`Testcase`], in order, for example to
+    /// migrate the metadata of the whole corpus in a single pass.
+    fn for_each_metadata_mut<F>(&mut self, mut f: F) -> Result<(), Error>
+    where
+        F: FnMut(CorpusId, &mut SerdeAnyMap),
+    {
+        for id in self.ids() {
+            f(id, self.get(id)?.borrow_mut().metadata_map_mut());
+        }
+        Ok(())
+    }
+
+    /// Increments the [`Testcase::scheduled`] counter of the [`Testcase`] with the given id.
+    /// Call this whenever a [`Testcase`] is selected for fuzzing, e.g. from a custom scheduler; the
+    /// built-in schedulers do not.
+    fn on_scheduled(&self, id: CorpusId) -> Result<(), Error> {
+        let mut testcase = self.get(id)?.borrow_mut();
+        let scheduled = testcase.scheduled();
+        testcase.set_scheduled(scheduled + 1);
+        Ok(())
+    }
+
+    /// Get the id of the [`Testcase`] with the lowest [`Testcase::scheduled`] counter, useful for
+    /// fair scheduling. On ties, the first inserted [`Testcase`] is returned.
+    fn least_scheduled(&self) -> Option<CorpusId> {
+        self.ids().min_by_key(|id| {
+            self.get(*id)
+                .map_or(u64::MAX, |testcase| testcase.borrow().scheduled())
+        })
+    }
+
+    /// Draws the id of a [`Testcase`] with a probability proportional to its
+    /// [`Testcase::energy`], with a single pass over the corpus (roulette-wheel selection).
+    /// A [`Testcase`] without energy weighs 1, so that the draw is uniform if no energy is assigned,
+    /// as it is if all energies are 0. Returns `None` if the corpus is empty.
+    fn sample_weighted<R>(&self, rand: &mut R) -> Result<Option<CorpusId>, Error>
+    where
+        R: Rand,
+    {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let mut total: u64 = 0;
+        for id in self.ids() {
+            total = total.saturating_add(self.get(id)?.borrow().energy().unwrap_or(1));
+        }
+        if total == 0 {
+            return Ok(Some(random_corpus_id!(self, rand)));
+        }
+
+        let mut pick = rand.below(total);
+        for id in self.ids() {
+            let energy = self.get(id)?.borrow().energy().unwrap_or(1);
+            if pick < energy {
+                return Ok(Some(id));
+            }
+            pick -= energy;
+        }
+        // only reached if the total saturated
+        Ok(self.last())
+    }
+
+    /// Remove all [`Testcase`]s at positions (as in [`Corpus::nth`]) greater or equal to `len`.
+    /// If the current [`Testcase`] is removed, the current [`CorpusId`] is reset to `None`.
+    fn truncate(&mut self, len: usize) -> Result<(), Error> {
+        let removed: Vec<CorpusId> = self.ids().skip(len).collect();
+        for idx in removed {
+            self.remove(idx)?;
+            if *self.current() == Some(idx) {
+                *self.current_mut() = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Compacts the ids of the stored [`Testcase`]s into the dense range `0..n`, keeping their order,
+    /// and remaps the current [`CorpusId`]. Returns the old and the new id of each [`Testcase`],
+    /// so that components holding ids, such as schedulers, can update them.
+    /// By default, this is not supported.
+    fn reindex(&mut self) -> Result<Vec<(CorpusId, CorpusId)>, Error> {
+        Err(Error::not_implemented(
+            "This corpus does not support reindexing",
+        ))
+    }
+
+    /// Checks the invariants of this corpus, for debugging: the current [`CorpusId`] (if any) and
+    /// all ids reachable from [`Corpus::first`] must refer to stored [`Testcase`]s, their number must
+    /// match [`Corpus::count`], and the files backing the [`Testcase`]s must exist.
+    /// Returns an error describing the first violation.
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(current) = *self.current() {
+            if self.get(current).is_err() {
+                return Err(Error::illegal_state(format!(
+                    "The current id {current} is not in the corpus"
+                )));
+            }
+        }
+        let mut reachable = 0;
+        for id in self.ids() {
+            reachable += 1;
+            if reachable > self.count() {
+                // also stops on cycles
+                return Err(Error::illegal_state(format!(
+                    "More ids are reachable than the {} testcases in the corpus",
+                    self.count()
+                )));
+            }
+            let testcase = self.get(id).map_err(|err| {
+                Error::illegal_state(format!("The reachable id {id} is not accessible: {err}"))
+            })?;
+            #[cfg(feature = "std")]
+            if let Some(path) = testcase.borrow().file_path() {
+                if !path.exists() {
+                    return Err(Error::illegal_state(format!(
+                        "The file {} backing the testcase {id} does not exist",
+                        path.display()
+                    )));
+                }
+            }
+        }
+        if reachable != self.count() {
+            return Err(Error::illegal_state(format!(
+                "Only {reachable} of the {} testcases in the corpus are reachable",
+                self.count()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Packs all [`Testcase`]s (inputs and metadata) into a single, versioned archive, see [`archive`].
+    #[cfg(feature = "std")]
+    fn save_archive<W>(&self, writer: W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        archive::save_archive(self, writer)
+    }
+
+    /// Packs only the objectives (such as crashes) into an archive, see [`archive::export_objectives`].
+    #[cfg(feature = "std")]
+    fn export_objectives<W>(&self, writer: W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        archive::export_objectives(self, writer)
+    }
+
+    /// Adds all [`Testcase`]s from an archive written by [`Corpus::save_archive`], returning their ids.
+    #[cfg(feature = "std")]
+    fn load_archive<R>(&mut self, reader: R) -> Result<Vec<CorpusId>, Error>
+    where
+        R: Read,
+    {
+        archive::load_archive(self, reader)
+    }
+
+    /// Reserve capacity for at least `additional` more [`Testcase`]s, if the storage supports it.
+    /// By default, this does nothing.
+    fn reserve(&mut self, _additional: usize) {}
+
+    /// Release the unused capacity of the storage, if the storage supports it.
+    /// By default, this does nothing.
+    fn shrink_to_fit(&mut self) {}
+
     /// Method to load the input for this [`Testcase`] from persistent storage,
     /// if necessary, and if was not already loaded (`== Some(input)`).
     /// After this call, `testcase.input()` must always return `Some(input)`.
@@ -144,6 +417,54 @@ pub trait Corpus: UsesInput + Serialize + for<'de> Deserialize<'de> {
         let mut testcase = self.get(idx)?.borrow_mut();
         Ok(testcase.load_input(self)?.clone())
     }
+
+    /// Loads the `Input` for a given [`CorpusId`] from the [`Corpus`], if needed, and returns a copy
+    /// of its bytes.
+    fn input_bytes(&self, id: CorpusId) -> Result<Vec<u8>, Error>
+    where
+        Self::Input: HasBytesVec,
+    {
+        let mut testcase = self.get(id)?.borrow_mut();
+        self.load_input_into(&mut testcase)?;
+        testcase
+            .input()
+            .as_ref()
+            .map(|input| input.bytes().to_vec())
+            .ok_or_else(|| Error::empty(format!("The testcase {id} has no input")))
+    }
+
+    /// Computes a [`CorpusStats`] summary, iterating over all [`Testcase`]s.
+    /// The length of inputs not yet in memory is loaded from the storage, if not cached.
+    fn stats(&self) -> Result<CorpusStats, Error>
+    where
+        Self::Input: HasLen,
+    {
+        let mut stats = CorpusStats::default();
+        let mut lens = Vec::with_capacity(self.count());
+        for id in self.ids() {
+            let mut testcase = self.get(id)?.borrow_mut();
+            let len = testcase.load_len(self)?;
+            stats.total_bytes += len;
+            lens.push(len);
+            if testcase.has_metadata::<IsFavoredMetadata>() {
+                stats.favored += 1;
+            }
+            if testcase.disabled_reason().is_some() {
+                stats.disabled += 1;
+            }
+        }
+
+        stats.count = lens.len();
+        if !lens.is_empty() {
+            #[allow(clippy::cast_precision_loss)]
+            {
+                stats.average_len = stats.total_bytes as f64 / lens.len() as f64;
+            }
+            let mid = lens.len() / 2;
+            stats.median_len = *lens.select_nth_unstable(mid).1;
+        }
+        Ok(stats)
+    }
 }
 
 /// [`Iterator`] over the ids of a [`Corpus`]
@@ -187,10 +508,289 @@ where
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    use crate::{
+        bolts::{rands::StdRand, serdeany::SerdeAnyMap},
+        corpus::{Corpus, CorpusId, CorpusStats, InMemoryCorpus, Testcase},
+        feedbacks::MapIndexesMetadata,
+        inputs::BytesInput,
+        schedulers::minimizer::IsFavoredMetadata,
+        state::HasMetadata,
+    };
+
+    #[test]
+    fn test_corpus_stats() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        assert_eq!(corpus.stats().unwrap(), CorpusStats::default());
+
+        for (i, len) in [1, 8, 2, 4, 100].into_iter().enumerate() {
+            let mut testcase = Testcase::new(BytesInput::new(vec![0; len]));
+            if i % 2 == 0 {
+                testcase.add_metadata(IsFavoredMetadata {});
+            }
+            if len == 100 {
+                testcase.set_disabled_reason("too big".into());
+            }
+            corpus.add(testcase).unwrap();
+        }
+
+        let stats = corpus.stats().unwrap();
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.total_bytes, 115);
+        assert!((stats.average_len - 23.0).abs() < f64::EPSILON);
+        assert_eq!(stats.median_len, 4);
+        assert_eq!(stats.favored, 3);
+        assert_eq!(stats.disabled, 1);
+    }
+
+    #[test]
+    fn test_corpus_nth_enabled() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        assert_eq!(corpus.nth_enabled(0).unwrap(), None);
+
+        let ids: Vec<_> = (0..6)
+            .map(|i| {
+                let mut testcase = Testcase::new(BytesInput::new(vec![i]));
+                if [0, 2, 3].contains(&i) {
+                    testcase.set_disabled_reason("flaky".into());
+                }
+                corpus.add(testcase).unwrap()
+            })
+            .collect();
+
+        let enabled: Vec<_> = (0..4).map(|nth| corpus.nth_enabled(nth).unwrap()).collect();
+        assert_eq!(
+            enabled,
+            vec![Some(ids[1]), Some(ids[4]), Some(ids[5]), None]
+        );
+
+        // re-enabling an entry shifts the later positions
+        *corpus
+            .get(ids[2])
+            .unwrap()
+            .borrow_mut()
+            .disabled_reason_mut() = None;
+        assert_eq!(corpus.nth_enabled(1).unwrap(), Some(ids[2]));
+        assert_eq!(corpus.nth_enabled(3).unwrap(), Some(ids[5]));
+    }
+
+    #[test]
+    fn test_corpus_position_of() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        for i in 0..4 {
+            corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap();
+        }
+
+        for nth in 0..4 {
+            let testcase = corpus.get(corpus.nth(nth)).unwrap();
+            assert_eq!(corpus.position_of(testcase), Some(nth));
+        }
+
+        let first = corpus.get(corpus.first().unwrap()).unwrap();
+        assert_eq!(corpus.position_of(first), Some(0));
+
+        let other = RefCell::new(Testcase::new(BytesInput::new(vec![0])));
+        assert_eq!(corpus.position_of(&other), None);
+    }
+
+    #[test]
+    fn test_corpus_least_scheduled() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        assert_eq!(corpus.least_scheduled(), None);
+
+        let ids: Vec<_> = (0..3)
+            .map(|i| corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap())
+            .collect();
+        for (id, times) in ids.iter().zip([3, 1, 2]) {
+            for _ in 0..times {
+                corpus.on_scheduled(*id).unwrap();
+            }
+        }
+        assert_eq!(corpus.least_scheduled(), Some(ids[1]));
+
+        corpus.on_scheduled(ids[1]).unwrap();
+        corpus.on_scheduled(ids[1]).unwrap();
+        assert_eq!(corpus.get(ids[1]).unwrap().borrow().scheduled(), 3);
+        // the counter is separate from the scheduled count of the mutational stages
+        assert_eq!(corpus.get(ids[1]).unwrap().borrow().scheduled_count(), 0);
+        // and serialized along with the testcase
+        let serialized = postcard::to_allocvec(&*corpus.get(ids[1]).unwrap().borrow()).unwrap();
+        let loaded: Testcase<BytesInput> = postcard::from_bytes(&serialized).unwrap();
+        assert_eq!(loaded.scheduled(), 3);
+        // on ties, the first inserted testcase wins
+        assert_eq!(corpus.least_scheduled(), Some(ids[2]));
+        corpus.on_scheduled(ids[2]).unwrap();
+        assert_eq!(corpus.least_scheduled(), Some(ids[0]));
+    }
+
+    #[test]
+    fn test_corpus_replace_metadata() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let mut testcase = Testcase::new(BytesInput::new(vec![0]));
+        testcase.add_metadata(IsFavoredMetadata {});
+        let id = corpus.add(testcase).unwrap();
+
+        let mut metadata = SerdeAnyMap::new();
+        metadata.insert(MapIndexesMetadata::new(vec![1, 2]));
+        corpus.replace_metadata(id, metadata).unwrap();
+
+        let testcase = corpus.get(id).unwrap().borrow();
+        assert!(!testcase.has_metadata::<IsFavoredMetadata>());
+        assert_eq!(
+            testcase.metadata::<MapIndexesMetadata>().unwrap().list,
+            vec![1, 2]
+        );
+        drop(testcase);
+
+        assert!(corpus
+            .replace_metadata(CorpusId::from(1_usize), SerdeAnyMap::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_corpus_for_each_metadata_mut() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let ids: Vec<_> = (0..3)
+            .map(|i| corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap())
+            .collect();
+        corpus
+            .get(ids[1])
+            .unwrap()
+            .borrow_mut()
+            .add_metadata(IsFavoredMetadata {});
+
+        let mut visited = vec![];
+        corpus
+            .for_each_metadata_mut(|id, metadata| {
+                visited.push(id);
+                metadata.insert(MapIndexesMetadata::new(vec![id.into()]));
+            })
+            .unwrap();
+        assert_eq!(visited, ids);
+
+        for id in ids.iter().copied() {
+            let testcase = corpus.get(id).unwrap().borrow();
+            assert_eq!(
+                testcase.metadata::<MapIndexesMetadata>().unwrap().list,
+                vec![usize::from(id)]
+            );
+            // the other metadata is kept
+            assert_eq!(testcase.has_metadata::<IsFavoredMetadata>(), id == ids[1]);
+        }
+    }
+
+    #[test]
+    fn test_corpus_sample_weighted() {
+        let mut rand = StdRand::with_seed(1337);
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        assert_eq!(corpus.sample_weighted(&mut rand).unwrap(), None);
+
+        let ids: Vec<_> = (0..4)
+            .map(|i| corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap())
+            .collect();
+        let draw = |corpus: &InMemoryCorpus<BytesInput>, rand: &mut StdRand| {
+            let mut hits = [0_usize; 4];
+            for _ in 0..10_000 {
+                let id = corpus.sample_weighted(rand).unwrap().unwrap();
+                hits[ids.iter().position(|i| *i == id).unwrap()] += 1;
+            }
+            hits
+        };
+
+        // without energies, the draw is uniform
+        let hits = draw(&corpus, &mut rand);
+        assert!(hits.iter().all(|h| (2000..3000).contains(h)));
+
+        // the high-energy entry takes about 97 draws out of 100, the zero-energy one none
+        for (id, energy) in ids.iter().zip([1, 97, 2, 0]) {
+            corpus
+                .get(*id)
+                .unwrap()
+                .borrow_mut()
+                .set_energy(Some(energy));
+        }
+        let hits = draw(&corpus, &mut rand);
+        assert!(hits[1] > 9000);
+        assert!(hits[0] < 300 && hits[2] < 500);
+        assert_eq!(hits[3], 0);
+    }
+
+    #[test]
+    fn test_corpus_validate() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        corpus.validate().unwrap();
+        let ids: Vec<_> = (0..3)
+            .map(|i| corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap())
+            .collect();
+        *corpus.current_mut() = Some(ids[2]);
+        corpus.validate().unwrap();
+
+        *corpus.current_mut() = Some(CorpusId::from(10_usize));
+        assert!(corpus.validate().is_err());
+
+        *corpus.current_mut() = Some(ids[0]);
+        corpus.remove(ids[0]).unwrap();
+        assert!(corpus.validate().is_err());
+        *corpus.current_mut() = None;
+        corpus.validate().unwrap();
+    }
+
+    #[test]
+    fn test_corpus_find_by_tag() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let mut ids = vec![];
+        for i in 0..4 {
+            let mut testcase = Testcase::new(BytesInput::new(vec![i]));
+            if i % 2 == 0 {
+                testcase.add_tag("seed");
+            }
+            if i == 3 {
+                testcase.add_tag("from-crash");
+            }
+            ids.push(corpus.add(testcase).unwrap());
+        }
+        {
+            let mut testcase = corpus.get(ids[2]).unwrap().borrow_mut();
+            assert!(testcase.add_tag("minimized"));
+            assert!(!testcase.add_tag("minimized"));
+            assert!(testcase.remove_tag("seed"));
+            assert!(!testcase.remove_tag("seed"));
+        }
+
+        let serialized = postcard::to_allocvec(&corpus).unwrap();
+        let corpus: InMemoryCorpus<BytesInput> = postcard::from_bytes(&serialized).unwrap();
+
+        assert_eq!(corpus.find_by_tag("seed").unwrap(), vec![ids[0]]);
+        assert_eq!(corpus.find_by_tag("minimized").unwrap(), vec![ids[2]]);
+        assert_eq!(corpus.find_by_tag("from-crash").unwrap(), vec![ids[3]]);
+        assert!(corpus.find_by_tag("unknown").unwrap().is_empty());
+        assert_eq!(corpus.get(ids[2]).unwrap().borrow().tags().len(), 1);
+    }
+
+    #[test]
+    fn test_corpus_input_bytes() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        let id = corpus
+            .add(Testcase::new(BytesInput::new(b"known".to_vec())))
+            .unwrap();
+        assert_eq!(corpus.input_bytes(id).unwrap(), b"known");
+
+        // a testcase without input, that the corpus cannot load
+        let empty = corpus.add(Testcase::default()).unwrap();
+        assert!(corpus.input_bytes(empty).is_err());
+        assert!(corpus.input_bytes(CorpusId::from(42_usize)).is_err());
+    }
+}
+
 /// `Corpus` Python bindings
 #[cfg(feature = "python")]
 #[allow(missing_docs)]
 pub mod pybind {
+    use alloc::vec::Vec;
     use std::cell::RefCell;
 
     use pyo3::prelude::*;
@@ -382,6 +982,22 @@ pub mod pybind {
             unwrap_me!(self.wrapper, c, { c.last() })
         }
 
+        fn reserve(&mut self, additional: usize) {
+            unwrap_me_mut!(self.wrapper, c, { c.reserve(additional) });
+        }
+
+        fn truncate(&mut self, len: usize) -> Result<(), Error> {
+            unwrap_me_mut!(self.wrapper, c, { c.truncate(len) })
+        }
+
+        fn shrink_to_fit(&mut self) {
+            unwrap_me_mut!(self.wrapper, c, { c.shrink_to_fit() });
+        }
+
+        fn reindex(&mut self) -> Result<Vec<(CorpusId, CorpusId)>, Error> {
+            unwrap_me_mut!(self.wrapper, c, { c.reindex() })
+        }
+
         fn load_input_into(&self, testcase: &mut Testcase<BytesInput>) -> Result<(), Error> {
             unwrap_me!(self.wrapper, c, { c.load_input_into(testcase) })
         }
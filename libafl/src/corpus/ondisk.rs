@@ -4,6 +4,7 @@
 //! For any other occasions, consider using [`crate::corpus::CachedOnDiskCorpus`]
 //! which stores a certain number of testcases in memory and removes additional ones in a FIFO manner.
 
+use alloc::vec::Vec;
 use core::{cell::RefCell, time::Duration};
 use std::path::{Path, PathBuf};
 
@@ -11,7 +12,7 @@ use serde::{Deserialize, Serialize};
 
 use super::{CachedOnDiskCorpus, HasTestcase};
 use crate::{
-    bolts::serdeany::SerdeAnyMap,
+    bolts::{serdeany::SerdeAnyMap, HasLen},
     corpus::{Corpus, CorpusId, Testcase},
     inputs::{Input, UsesInput},
     Error,
@@ -70,7 +71,7 @@ where
 
 impl<I> Corpus for OnDiskCorpus<I>
 where
-    I: Input,
+    I: Input + HasLen,
 {
     /// Returns the number of elements
     #[inline]
@@ -139,6 +140,21 @@ where
         self.inner.nth(nth)
     }
 
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+
+    #[inline]
+    fn reindex(&mut self) -> Result<Vec<(CorpusId, CorpusId)>, Error> {
+        self.inner.reindex()
+    }
+
     #[inline]
     fn load_input_into(&self, testcase: &mut Testcase<Self::Input>) -> Result<(), Error> {
         self.inner.load_input_into(testcase)
@@ -152,7 +168,7 @@ where
 
 impl<I> HasTestcase for OnDiskCorpus<I>
 where
-    I: Input,
+    I: Input + HasLen,
 {
     fn testcase(
         &self,
@@ -1,7 +1,7 @@
 //! The testcase is a struct embedded in each corpus.
 //! It will contain a respective input, and metadata.
 
-use alloc::string::String;
+use alloc::string::{String, ToString};
 use core::{
     cell::{Ref, RefMut},
     default::Default,
@@ -11,6 +11,7 @@ use core::{
 #[cfg(feature = "std")]
 use std::path::PathBuf;
 
+use hashbrown::HashSet;
 use serde::{Deserialize, Serialize};
 
 use super::Corpus;
@@ -58,14 +59,26 @@ where
     metadata_path: Option<PathBuf>,
     /// Time needed to execute the input
     exec_time: Option<Duration>,
+    /// When this [`Testcase`] was added to a corpus, as the time since the UNIX epoch
+    created_at: Option<Duration>,
     /// Cached len of the input, if any
     cached_len: Option<usize>,
     /// Number of executions done at discovery time
     executions: usize,
     /// Number of fuzzing iterations of this particular input updated in perform_mutational
     scheduled_count: usize,
+    /// Number of times this [`Testcase`] was selected, see [`crate::corpus::Corpus::on_scheduled`]
+    scheduled: u64,
+    /// The energy of this [`Testcase`], its weight in [`crate::corpus::Corpus::sample_weighted`], if assigned
+    energy: Option<u64>,
     /// Parent [`CorpusId`], if known
     parent_id: Option<CorpusId>,
+    /// The reason why this [`Testcase`] was disabled or skipped, if any (e.g., "flaky")
+    disabled_reason: Option<String>,
+    /// Free-form tags (labels) of this [`Testcase`], such as `"seed"` or `"minimized"`
+    tags: HashSet<String>,
+    /// If this [`Testcase`] was saved as an objective (a solution, such as a crash), rather than as a corpus entry
+    objective: bool,
 }
 
 impl<I> HasMetadata for Testcase<I>
@@ -174,6 +187,20 @@ where
         self.exec_time = Some(time);
     }
 
+    /// Get when this testcase was added to a corpus, as the time since the UNIX epoch, if known.
+    /// This is set by [`crate::corpus::Corpus::add`], if not set before.
+    #[inline]
+    #[must_use]
+    pub fn created_at(&self) -> Option<Duration> {
+        self.created_at
+    }
+
+    /// Sets when this testcase was added to a corpus, as the time since the UNIX epoch
+    #[inline]
+    pub fn set_created_at(&mut self, created_at: Duration) {
+        self.created_at = Some(created_at);
+    }
+
     /// Get the executions
     #[inline]
     pub fn executions(&self) -> &usize {
@@ -198,6 +225,33 @@ where
         self.scheduled_count = scheduled_count;
     }
 
+    /// Get the number of times this [`Testcase`] was selected, see
+    /// [`crate::corpus::Corpus::on_scheduled`]
+    #[inline]
+    #[must_use]
+    pub fn scheduled(&self) -> u64 {
+        self.scheduled
+    }
+
+    /// Set the number of times this [`Testcase`] was selected
+    #[inline]
+    pub fn set_scheduled(&mut self, scheduled: u64) {
+        self.scheduled = scheduled;
+    }
+
+    /// Get the energy, if assigned
+    #[inline]
+    #[must_use]
+    pub fn energy(&self) -> Option<u64> {
+        self.energy
+    }
+
+    /// Set the energy, the weight of this testcase in [`crate::corpus::Corpus::sample_weighted`]
+    #[inline]
+    pub fn set_energy(&mut self, energy: Option<u64>) {
+        self.energy = energy;
+    }
+
     /// Create a new Testcase instance given an input
     #[inline]
     pub fn new(mut input: I) -> Self {
@@ -256,6 +310,62 @@ where
     pub fn set_parent_id_optional(&mut self, parent_id: Option<CorpusId>) {
         self.parent_id = parent_id;
     }
+
+    /// Get the reason why this testcase was disabled, if any
+    #[inline]
+    pub fn disabled_reason(&self) -> &Option<String> {
+        &self.disabled_reason
+    }
+
+    /// Get the reason why this testcase was disabled, if any (mutable)
+    #[inline]
+    pub fn disabled_reason_mut(&mut self) -> &mut Option<String> {
+        &mut self.disabled_reason
+    }
+
+    /// Sets the reason why this testcase was disabled, as a triage annotation
+    #[inline]
+    pub fn set_disabled_reason(&mut self, reason: String) {
+        self.disabled_reason = Some(reason);
+    }
+
+    /// Get the tags of this testcase
+    #[inline]
+    pub fn tags(&self) -> &HashSet<String> {
+        &self.tags
+    }
+
+    /// Adds a tag to this testcase, returns `false` if it was already present
+    #[inline]
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        self.tags.insert(tag.to_string())
+    }
+
+    /// Removes a tag from this testcase, returns `false` if it was not present
+    #[inline]
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        self.tags.remove(tag)
+    }
+
+    /// Returns `true` if this testcase was saved as an objective (a solution, such as a crash)
+    #[inline]
+    #[must_use]
+    pub fn is_objective(&self) -> bool {
+        self.objective
+    }
+
+    /// Sets if this testcase was saved as an objective (a solution, such as a crash)
+    #[inline]
+    pub fn set_objective(&mut self, objective: bool) {
+        self.objective = objective;
+    }
+
+    /// Returns `true` if this testcase has the given tag
+    #[inline]
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
 }
 
 impl<I> Default for Testcase<I>
@@ -270,10 +380,16 @@ where
             filename: None,
             metadata: SerdeAnyMap::new(),
             exec_time: None,
+            created_at: None,
             cached_len: None,
             scheduled_count: 0,
+            scheduled: 0,
+            energy: None,
             executions: 0,
             parent_id: None,
+            disabled_reason: None,
+            tags: HashSet::new(),
+            objective: false,
             #[cfg(feature = "std")]
             file_path: None,
             #[cfg(feature = "std")]
@@ -432,9 +548,38 @@ impl SchedulerTestcaseMetadata {
 
 crate::impl_serdeany!(SchedulerTestcaseMetadata);
 
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{corpus::Testcase, inputs::BytesInput};
+
+    #[test]
+    fn test_testcase_disabled_reason() {
+        let mut testcase = Testcase::new(BytesInput::new(vec![1, 2, 3]));
+        assert!(testcase.disabled_reason().is_none());
+
+        testcase.set_disabled_reason("flaky".to_string());
+        let serialized = postcard::to_allocvec(&testcase).unwrap();
+        let deserialized: Testcase<BytesInput> = postcard::from_bytes(&serialized).unwrap();
+        assert_eq!(deserialized.disabled_reason().as_deref(), Some("flaky"));
+    }
+
+    #[test]
+    fn test_testcase_objective() {
+        let mut testcase = Testcase::new(BytesInput::new(vec![1, 2, 3]));
+        assert!(!testcase.is_objective());
+
+        testcase.set_objective(true);
+        let serialized = postcard::to_allocvec(&testcase).unwrap();
+        let deserialized: Testcase<BytesInput> = postcard::from_bytes(&serialized).unwrap();
+        assert!(deserialized.is_objective());
+    }
+}
+
+/// `Testcase` Python bindings
 #[cfg(feature = "python")]
 #[allow(missing_docs)]
-/// `Testcase` Python bindings
 pub mod pybind {
     use alloc::{boxed::Box, vec::Vec};
 
@@ -589,6 +589,7 @@ pub fn run_observers_and_save_state<E, EM, OF, Z>(
         let mut new_testcase = Testcase::new(input.clone());
         new_testcase.add_metadata(exitkind);
         new_testcase.set_parent_id_optional(*state.corpus().current());
+        new_testcase.set_objective(true);
         fuzzer
             .objective_mut()
             .append_metadata(state, observers, &mut new_testcase)
@@ -0,0 +1,262 @@
+//! The [`AssertionFeedback`] detects soft failures, such as assertion messages printed by a target
+//! that doesn't crash, by scanning the output captured by an observer.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{HasCapturedOutput, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// Common markers of failed assertions
+pub const DEFAULT_ASSERTION_PATTERNS: [&str; 3] =
+    ["assertion failed", "Assertion `", "AssertionError"];
+
+/// A testcase metadata holding the assertion message matched by an [`AssertionFeedback`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertionMetadata {
+    /// The (trimmed) output line containing the match
+    pub message: String,
+}
+
+crate::impl_serdeany!(AssertionMetadata);
+
+/// An [`AssertionFeedback`] scans the output captured by an observer, such as a
+/// [`crate::observers::StdErrObserver`], and reports as interesting the runs printing a line matching
+/// any of the configured substrings (or regexes, with the `regex` feature).
+/// The matching line is added to the testcase as [`AssertionMetadata`].
+#[derive(Clone, Debug)]
+pub struct AssertionFeedback<O, S> {
+    name: String,
+    observer_name: String,
+    /// The substrings indicating a soft failure
+    substrings: Vec<String>,
+    /// The regexes indicating a soft failure
+    #[cfg(feature = "regex")]
+    regexes: Vec<Regex>,
+    /// The message matched in the last run, if any
+    last_message: Option<String>,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<O, S> AssertionFeedback<O, S>
+where
+    O: HasCapturedOutput + Named,
+{
+    /// Creates a new [`AssertionFeedback`], matching any of the given `substrings` in the observer output.
+    #[must_use]
+    pub fn new(observer: &O, substrings: &[&str]) -> Self {
+        Self {
+            name: "assertionfeedback_".to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            substrings: substrings.iter().map(|s| (*s).to_string()).collect(),
+            #[cfg(feature = "regex")]
+            regexes: Vec::new(),
+            last_message: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Adds a substring indicating a soft failure
+    pub fn add_substring(&mut self, substring: &str) {
+        self.substrings.push(substring.to_string());
+    }
+
+    /// Adds a regex indicating a soft failure
+    #[cfg(feature = "regex")]
+    pub fn add_regex(&mut self, regex: &str) -> Result<(), Error> {
+        let regex = Regex::new(regex)
+            .map_err(|e| Error::illegal_argument(format!("Invalid regex {regex}: {e}")))?;
+        self.regexes.push(regex);
+        Ok(())
+    }
+
+    /// Returns `true` if the line matches any of the patterns
+    fn matches(&self, line: &str) -> bool {
+        if self.substrings.iter().any(|s| line.contains(s.as_str())) {
+            return true;
+        }
+        #[cfg(feature = "regex")]
+        if self.regexes.iter().any(|r| r.is_match(line)) {
+            return true;
+        }
+        false
+    }
+}
+
+impl<O, S> Feedback<S> for AssertionFeedback<O, S>
+where
+    O: HasCapturedOutput + Named + Debug,
+    S: UsesInput + HasClientPerfMonitor + Debug,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "AssertionFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+
+        self.last_message = observer.captured_output().and_then(|output| {
+            String::from_utf8_lossy(output)
+                .lines()
+                .find(|line| self.matches(line))
+                .map(|line| line.trim().to_string())
+        });
+        Ok(self.last_message.is_some())
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(message) = self.last_message.take() {
+            testcase.add_metadata(AssertionMetadata { message });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_message = None;
+        Ok(())
+    }
+}
+
+impl<O, S> Named for AssertionFeedback<O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, S> HasObserverName for AssertionFeedback<O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{AssertionFeedback, AssertionMetadata, Feedback, DEFAULT_ASSERTION_PATTERNS},
+        inputs::BytesInput,
+        observers::StdErrObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_assertion_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback = AssertionFeedback::new(
+            &StdErrObserver::new("stderr".into()),
+            &DEFAULT_ASSERTION_PATTERNS,
+        );
+        feedback.add_substring("SOFT FAILURE");
+
+        let mut run = |feedback: &mut AssertionFeedback<_, _>, output: &str| {
+            let mut observer = StdErrObserver::new("stderr".into());
+            observer.stderr = Some(output.as_bytes().to_vec());
+            feedback
+                .is_interesting(
+                    &mut state,
+                    &mut mgr,
+                    &input,
+                    &tuple_list![observer],
+                    &ExitKind::Ok,
+                )
+                .unwrap()
+        };
+
+        assert!(!run(&mut feedback, "all good\nnothing to see here\n"));
+        assert!(!run(&mut feedback, ""));
+        assert!(run(&mut feedback, "SOFT FAILURE in parser\n"));
+        assert!(run(
+            &mut feedback,
+            "starting\n  thread 'main' panicked: assertion failed: x < 10  \ndone\n"
+        ));
+
+        let mut testcase = Testcase::new(BytesInput::new(vec![0]));
+        feedback
+            .append_metadata(&mut NopState::new(), &(), &mut testcase)
+            .unwrap();
+        assert_eq!(
+            testcase
+                .metadata()
+                .get::<AssertionMetadata>()
+                .unwrap()
+                .message,
+            "thread 'main' panicked: assertion failed: x < 10"
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_assertion_feedback_regex() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback = AssertionFeedback::new(&StdErrObserver::new("stderr".into()), &[]);
+        feedback.add_regex(r"check \d+ failed").unwrap();
+        assert!(feedback.add_regex("(").is_err());
+
+        let mut run = |output: &str| {
+            let mut observer = StdErrObserver::new("stderr".into());
+            observer.stderr = Some(output.as_bytes().to_vec());
+            feedback
+                .is_interesting(
+                    &mut state,
+                    &mut mgr,
+                    &input,
+                    &tuple_list![observer],
+                    &ExitKind::Ok,
+                )
+                .unwrap()
+        };
+
+        assert!(!run("check failed"));
+        assert!(run("check 42 failed"));
+    }
+}
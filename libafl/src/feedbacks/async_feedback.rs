@@ -0,0 +1,313 @@
+//! Async feedbacks, for harnesses where the observer data needs to be awaited (for example, fetched over a channel).
+//!
+//! Every [`Feedback`] is also an [`AsyncFeedback`], so existing feedbacks can be awaited.
+//! The other way around, a [`BlockOnFeedback`] turns an [`AsyncFeedback`] into a regular [`Feedback`],
+//! blocking the current thread until the future completes.
+
+use alloc::{boxed::Box, string::String};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use futures::{executor::block_on, future::LocalBoxFuture};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// An [`AsyncFeedback`] is a [`Feedback`] whose `is_interesting` returns a future.
+pub trait AsyncFeedback<S>: Named + Debug
+where
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Initializes the feedback state.
+    /// This method is called after that the `State` is created.
+    fn init_state_async(&mut self, _state: &mut S) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// `is_interesting_async` returns a future resolving to whether an input is worth the addition to the corpus
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting_async<'a, EM, OT>(
+        &'a mut self,
+        state: &'a mut S,
+        manager: &'a mut EM,
+        input: &'a S::Input,
+        observers: &'a OT,
+        exit_kind: &'a ExitKind,
+    ) -> LocalBoxFuture<'a, Result<bool, Error>>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>;
+
+    /// Append to the testcase the generated metadata in case of a new corpus item
+    #[inline]
+    #[allow(unused_variables)]
+    fn append_metadata_async<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        Ok(())
+    }
+
+    /// Discard the stored metadata in case that the testcase is not added to the corpus
+    #[inline]
+    fn discard_metadata_async(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<F, S> AsyncFeedback<S> for F
+where
+    F: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state_async(&mut self, state: &mut S) -> Result<(), Error> {
+        self.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting_async<'a, EM, OT>(
+        &'a mut self,
+        state: &'a mut S,
+        manager: &'a mut EM,
+        input: &'a S::Input,
+        observers: &'a OT,
+        exit_kind: &'a ExitKind,
+    ) -> LocalBoxFuture<'a, Result<bool, Error>>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        Box::pin(async move { self.is_interesting(state, manager, input, observers, exit_kind) })
+    }
+
+    #[inline]
+    fn append_metadata_async<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        self.append_metadata(state, observers, testcase)
+    }
+
+    #[inline]
+    fn discard_metadata_async(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.discard_metadata(state, input)
+    }
+}
+
+/// A [`BlockOnFeedback`] wraps an [`AsyncFeedback`] to use it as a regular [`Feedback`],
+/// blocking on the returned future.
+pub struct BlockOnFeedback<A, S>
+where
+    A: AsyncFeedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped async feedback
+    pub inner: A,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S> BlockOnFeedback<A, S>
+where
+    A: AsyncFeedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`BlockOnFeedback`], wrapping the given [`AsyncFeedback`]
+    pub fn new(inner: A) -> Self {
+        let name = format!("BlockOn({})", inner.name());
+        Self {
+            inner,
+            name,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<A, S> Debug for BlockOnFeedback<A, S>
+where
+    A: AsyncFeedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockOnFeedback")
+            .field("name", &self.name)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<A, S> Named for BlockOnFeedback<A, S>
+where
+    A: AsyncFeedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S> Feedback<S> for BlockOnFeedback<A, S>
+where
+    A: AsyncFeedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state_async(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        block_on(
+            self.inner
+                .is_interesting_async(state, manager, input, observers, exit_kind),
+        )
+    }
+
+    #[inline]
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        self.inner.append_metadata_async(state, observers, testcase)
+    }
+
+    #[inline]
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata_async(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+    use core::marker::PhantomData;
+
+    use futures::{executor::block_on, future::LocalBoxFuture};
+
+    use crate::{
+        bolts::tuples::Named,
+        events::{EventFirer, NopEventManager},
+        executors::ExitKind,
+        feedbacks::{AsyncFeedback, BlockOnFeedback, ConstFeedback, Feedback},
+        inputs::{BytesInput, HasBytesVec, UsesInput},
+        observers::ObserversTuple,
+        state::{HasClientPerfMonitor, NopState},
+        Error,
+    };
+
+    /// Interesting if the input is not empty, after yielding to the executor once
+    #[derive(Debug)]
+    struct NonEmptyAsyncFeedback<S> {
+        phantom: PhantomData<S>,
+    }
+
+    impl<S> Named for NonEmptyAsyncFeedback<S> {
+        fn name(&self) -> &str {
+            "NonEmptyAsyncFeedback"
+        }
+    }
+
+    impl<S> AsyncFeedback<S> for NonEmptyAsyncFeedback<S>
+    where
+        S: UsesInput<Input = BytesInput> + HasClientPerfMonitor,
+    {
+        fn is_interesting_async<'a, EM, OT>(
+            &'a mut self,
+            _state: &'a mut S,
+            _manager: &'a mut EM,
+            input: &'a S::Input,
+            _observers: &'a OT,
+            _exit_kind: &'a ExitKind,
+        ) -> LocalBoxFuture<'a, Result<bool, Error>>
+        where
+            EM: EventFirer<State = S>,
+            OT: ObserversTuple<S>,
+        {
+            Box::pin(async move {
+                futures::future::ready(()).await;
+                Ok(!input.bytes().is_empty())
+            })
+        }
+    }
+
+    #[test]
+    fn test_async_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![1, 2, 3]);
+
+        let mut feedback = NonEmptyAsyncFeedback {
+            phantom: PhantomData,
+        };
+        assert!(block_on(feedback.is_interesting_async(
+            &mut state,
+            &mut mgr,
+            &input,
+            &(),
+            &ExitKind::Ok
+        ))
+        .unwrap());
+
+        // a regular feedback can be awaited
+        let mut sync_feedback = ConstFeedback::new(false);
+        assert!(!block_on(sync_feedback.is_interesting_async(
+            &mut state,
+            &mut mgr,
+            &input,
+            &(),
+            &ExitKind::Ok
+        ))
+        .unwrap());
+
+        // an async feedback can be used as a regular feedback
+        let mut blocking = BlockOnFeedback::new(feedback);
+        assert_eq!(blocking.name(), "BlockOn(NonEmptyAsyncFeedback)");
+        assert!(!blocking
+            .is_interesting(
+                &mut state,
+                &mut mgr,
+                &BytesInput::new(vec![]),
+                &(),
+                &ExitKind::Ok
+            )
+            .unwrap());
+    }
+}
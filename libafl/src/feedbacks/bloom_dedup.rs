@@ -0,0 +1,366 @@
+//! The [`BloomDedupFeedback`] keeps the hashes of all seen inputs in a bloom filter, a compact
+//! probabilistic set that can be saved to disk, to only keep novel inputs across restarts.
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::input_hash,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasNamedMetadata},
+    Error,
+};
+
+/// The state of [`BloomDedupFeedback`]: a bloom filter of the hashes of all seen inputs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BloomFilterMetadata {
+    /// The bits of the filter
+    bits: Vec<u64>,
+    /// The number of bits of the filter
+    num_bits: u64,
+    /// The number of bits set per inserted hash
+    num_hashes: u32,
+    /// The number of inserted hashes
+    count: u64,
+}
+
+crate::impl_serdeany!(BloomFilterMetadata);
+
+impl BloomFilterMetadata {
+    /// Creates a new, empty, bloom filter sized to hold `expected_items` hashes with a false
+    /// positive rate of `false_positive_rate`
+    ///
+    /// # Panics
+    /// Panics if `false_positive_rate` is not strictly between `0.0` and `1.0`.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss,
+        clippy::cast_possible_truncation
+    )]
+    pub fn with_rate(expected_items: usize, false_positive_rate: f64) -> Self {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "The false positive rate must be between 0 and 1"
+        );
+        let n = expected_items.max(1) as f64;
+        let ln2 = core::f64::consts::LN_2;
+        let num_bits = ((-n * false_positive_rate.ln() / (ln2 * ln2)).ceil() as u64).max(64);
+        let num_hashes = ((num_bits as f64 / n * ln2).round() as u32).max(1);
+        Self {
+            bits: vec![0; ((num_bits + 63) / 64) as usize],
+            num_bits,
+            num_hashes,
+            count: 0,
+        }
+    }
+
+    /// The number of bits of the filter
+    #[must_use]
+    pub fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    /// The number of bits set per inserted hash
+    #[must_use]
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// The number of inserted hashes
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The bit indices of the given hash, derived by double hashing
+    fn indices(&self, hash: u64) -> impl Iterator<Item = u64> {
+        let h2 = hash.rotate_left(32).wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+        let num_bits = self.num_bits;
+        (0..u64::from(self.num_hashes))
+            .map(move |i| hash.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+
+    /// Returns `true` if the hash was probably inserted before, `false` if it certainly was not
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn contains(&self, hash: u64) -> bool {
+        self.indices(hash)
+            .all(|idx| self.bits[(idx / 64) as usize] & (1 << (idx % 64)) != 0)
+    }
+
+    /// Inserts the hash, returning `true` if it was certainly not inserted before
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn insert(&mut self, hash: u64) -> bool {
+        let mut new = false;
+        for idx in self.indices(hash) {
+            let word = &mut self.bits[(idx / 64) as usize];
+            new |= *word & (1 << (idx % 64)) == 0;
+            *word |= 1 << (idx % 64);
+        }
+        if new {
+            self.count += 1;
+        }
+        new
+    }
+
+    /// The estimated false positive rate for the hashes inserted so far
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn false_positive_rate(&self) -> f64 {
+        let k = f64::from(self.num_hashes);
+        (1.0 - (-k * self.count as f64 / self.num_bits as f64).exp()).powf(k)
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.bits.fill(0);
+        self.count = 0;
+        Ok(())
+    }
+}
+
+/// A [`BloomDedupFeedback`] considers interesting the inputs whose [`input_hash`] was probably not
+/// seen before, keeping all seen hashes in a [`BloomFilterMetadata`] in the state. Unlike the
+/// hash set of a [`crate::feedbacks::NewHashFeedback`], the filter has a fixed size, and can be
+/// saved to and loaded from a file with [`BloomDedupFeedback::save`] and
+/// [`BloomDedupFeedback::load`], to resume the deduplication after a restart.
+///
+/// The price of the compact filter are false positives: a novel input colliding with the bits of
+/// the seen inputs is considered seen, and silently dropped. The rate grows with the number of
+/// seen inputs, and matches the configured rate once the expected number of inputs is reached, see
+/// [`BloomFilterMetadata::false_positive_rate`]. Seen inputs are never considered novel.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BloomDedupFeedback<S> {
+    name: String,
+    /// The number of inputs the filter is sized for
+    expected_items: usize,
+    /// The false positive rate once `expected_items` inputs are seen
+    false_positive_rate: f64,
+    phantom: PhantomData<S>,
+}
+
+impl<S> BloomDedupFeedback<S>
+where
+    S: HasNamedMetadata,
+{
+    /// Creates a new [`BloomDedupFeedback`], with a filter sized to hold `expected_items` input
+    /// hashes with a false positive rate of `false_positive_rate`, between `0.0` and `1.0`
+    #[must_use]
+    pub fn new(name: &str, expected_items: usize, false_positive_rate: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            expected_items,
+            false_positive_rate,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The filter of the seen input hashes
+    pub fn filter<'a>(&self, state: &'a S) -> Result<&'a BloomFilterMetadata, Error> {
+        state
+            .named_metadata_map()
+            .get::<BloomFilterMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "BloomDedupFeedback: metadata {} not found",
+                    self.name
+                ))
+            })
+    }
+
+    /// Saves the filter of the seen input hashes to the given file
+    pub fn save<P>(&self, state: &S, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, postcard::to_allocvec(self.filter(state)?)?)?;
+        Ok(())
+    }
+
+    /// Replaces the filter of the seen input hashes with the one saved to the given file, see
+    /// [`BloomDedupFeedback::save`]. The loaded filter keeps the size it was saved with.
+    /// Fails, leaving the current filter in place, if the saved filter is inconsistent.
+    pub fn load<P>(&self, state: &mut S, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let filter: BloomFilterMetadata = postcard::from_bytes(&fs::read(path)?)?;
+        if filter.num_bits == 0
+            || filter.num_hashes == 0
+            || filter.bits.len() as u64 != (filter.num_bits + 63) / 64
+        {
+            return Err(Error::illegal_argument(format!(
+                "BloomDedupFeedback: inconsistent filter of {} bits in {} words, with {} hashes",
+                filter.num_bits,
+                filter.bits.len(),
+                filter.num_hashes
+            )));
+        }
+        state.add_named_metadata(filter, &self.name);
+        Ok(())
+    }
+}
+
+impl<S> Feedback<S> for BloomDedupFeedback<S>
+where
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(
+            BloomFilterMetadata::with_rate(self.expected_items, self.false_positive_rate),
+            &self.name,
+        );
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        input: &S::Input,
+        _observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let hash = input_hash(input)?;
+        let filter = state
+            .named_metadata_map_mut()
+            .get_mut::<BloomFilterMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "BloomDedupFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+        Ok(filter.insert(hash))
+    }
+}
+
+impl<S> Named for BloomDedupFeedback<S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{BloomDedupFeedback, BloomFilterMetadata, Feedback},
+        inputs::BytesInput,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut filter = BloomFilterMetadata::with_rate(1000, 0.01);
+        assert!(filter.num_bits() >= 9585);
+        assert_eq!(filter.num_hashes(), 7);
+
+        for hash in 0..1000_u64 {
+            filter.insert(hash.wrapping_mul(0x2545_f491_4f6c_dd1d));
+        }
+        assert!((0..1000_u64).all(|hash| filter.contains(hash.wrapping_mul(0x2545_f491_4f6c_dd1d))));
+        assert!((filter.false_positive_rate() - 0.01).abs() < 0.002);
+
+        // no more false positives than expected
+        let false_positives = (1000..11000_u64)
+            .filter(|hash| filter.contains(hash.wrapping_mul(0x2545_f491_4f6c_dd1d)))
+            .count();
+        assert!(false_positives < 200);
+    }
+
+    #[test]
+    fn test_bloom_dedup_feedback() {
+        let path = std::env::temp_dir().join(format!("bloom_dedup_{}.bin", std::process::id()));
+        let mut mgr = NopEventManager::new();
+        let inputs: Vec<_> = (0..100_u8).map(|i| BytesInput::new(vec![i; 4])).collect();
+
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut feedback: BloomDedupFeedback<NopState<BytesInput>> =
+            BloomDedupFeedback::new("bloom", 1000, 0.001);
+        feedback.init_state(&mut state).unwrap();
+        let mut run = |feedback: &mut BloomDedupFeedback<_>,
+                       state: &mut NopState<BytesInput>,
+                       input: &BytesInput| {
+            feedback
+                .is_interesting(state, &mut mgr, input, &(), &ExitKind::Ok)
+                .unwrap()
+        };
+
+        for input in &inputs {
+            assert!(run(&mut feedback, &mut state, input));
+        }
+        for input in &inputs {
+            assert!(!run(&mut feedback, &mut state, input));
+        }
+        feedback.save(&state, &path).unwrap();
+
+        // a fresh campaign forgets the seen inputs, until the filter is loaded
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut feedback: BloomDedupFeedback<NopState<BytesInput>> =
+            BloomDedupFeedback::new("bloom", 1000, 0.001);
+        feedback.init_state(&mut state).unwrap();
+        assert_eq!(feedback.filter(&state).unwrap().count(), 0);
+        feedback.load(&mut state, &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(feedback.filter(&state).unwrap().count(), 100);
+
+        for input in &inputs {
+            assert!(!run(&mut feedback, &mut state, input));
+        }
+        assert!(run(
+            &mut feedback,
+            &mut state,
+            &BytesInput::new(vec![0xff; 8])
+        ));
+
+        // a corrupt filter is refused
+        for corrupt in [
+            BloomFilterMetadata {
+                bits: vec![0; 1],
+                num_bits: 1000,
+                num_hashes: 7,
+                count: 0,
+            },
+            BloomFilterMetadata {
+                bits: Vec::new(),
+                num_bits: 0,
+                num_hashes: 7,
+                count: 0,
+            },
+            BloomFilterMetadata {
+                bits: vec![0; 16],
+                num_bits: 1000,
+                num_hashes: 0,
+                count: 0,
+            },
+        ] {
+            std::fs::write(&path, postcard::to_allocvec(&corrupt).unwrap()).unwrap();
+            assert!(feedback.load(&mut state, &path).is_err());
+        }
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(feedback.filter(&state).unwrap().count(), 101);
+    }
+}
@@ -0,0 +1,142 @@
+//! The [`FeedbackBuilder`] composes [`Feedback`] trees with method calls, as an alternative to
+//! the [`crate::feedback_or`] family of macros.
+
+use core::marker::PhantomData;
+
+use crate::{
+    feedbacks::{
+        EagerAndFeedback, EagerOrFeedback, FastAndFeedback, FastOrFeedback, Feedback, NotFeedback,
+    },
+    inputs::UsesInput,
+    state::HasClientPerfMonitor,
+};
+
+/// Builds a tree of [`Feedback`]s fluently, for example
+/// `FeedbackBuilder::new(a).or(FeedbackBuilder::new(b).and(c).build()).build()` is equivalent to
+/// `feedback_or!(a, feedback_and!(b, c))`.
+///
+/// The composed feedback keeps concrete types, so all branches have to be known at compile time.
+/// To leave out a branch based on the configuration, it can be replaced by a neutral
+/// [`crate::feedbacks::ConstFeedback`], i.e. `false` for `or` and `true` for `and`.
+#[derive(Debug)]
+pub struct FeedbackBuilder<F, S>
+where
+    F: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    feedback: F,
+    phantom: PhantomData<S>,
+}
+
+impl<F, S> FeedbackBuilder<F, S>
+where
+    F: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Starts building from the given [`Feedback`]
+    pub fn new(feedback: F) -> Self {
+        Self {
+            feedback,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Combines the feedback built so far with `other`, using an [`EagerOrFeedback`]
+    pub fn or<B>(self, other: B) -> FeedbackBuilder<EagerOrFeedback<F, B, S>, S>
+    where
+        B: Feedback<S>,
+    {
+        FeedbackBuilder::new(EagerOrFeedback::new(self.feedback, other))
+    }
+
+    /// Combines the feedback built so far with `other`, using a [`FastOrFeedback`]
+    pub fn fast_or<B>(self, other: B) -> FeedbackBuilder<FastOrFeedback<F, B, S>, S>
+    where
+        B: Feedback<S>,
+    {
+        FeedbackBuilder::new(FastOrFeedback::new(self.feedback, other))
+    }
+
+    /// Combines the feedback built so far with `other`, using an [`EagerAndFeedback`]
+    pub fn and<B>(self, other: B) -> FeedbackBuilder<EagerAndFeedback<F, B, S>, S>
+    where
+        B: Feedback<S>,
+    {
+        FeedbackBuilder::new(EagerAndFeedback::new(self.feedback, other))
+    }
+
+    /// Combines the feedback built so far with `other`, using a [`FastAndFeedback`]
+    pub fn fast_and<B>(self, other: B) -> FeedbackBuilder<FastAndFeedback<F, B, S>, S>
+    where
+        B: Feedback<S>,
+    {
+        FeedbackBuilder::new(FastAndFeedback::new(self.feedback, other))
+    }
+
+    /// Inverts the feedback built so far, using a [`NotFeedback`]
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> FeedbackBuilder<NotFeedback<F, S>, S> {
+        FeedbackBuilder::new(NotFeedback::new(self.feedback))
+    }
+
+    /// Returns the composed [`Feedback`]
+    pub fn build(self) -> F {
+        self.feedback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::Named,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedback_and, feedback_not, feedback_or,
+        feedbacks::{ConstFeedback, Feedback, FeedbackBuilder},
+        inputs::BytesInput,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_feedback_builder() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        for bits in 0..8 {
+            let (a, b, c) = (bits & 1 != 0, bits & 2 != 0, bits & 4 != 0);
+
+            let mut expected = feedback_or!(
+                ConstFeedback::new(a),
+                feedback_and!(ConstFeedback::new(b), ConstFeedback::new(c))
+            );
+            let mut built = FeedbackBuilder::<_, NopState<BytesInput>>::new(ConstFeedback::new(a))
+                .or(FeedbackBuilder::new(ConstFeedback::new(b))
+                    .and(ConstFeedback::new(c))
+                    .build())
+                .build();
+            assert_eq!(built.name(), expected.name());
+            assert_eq!(
+                built
+                    .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                    .unwrap(),
+                expected
+                    .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                    .unwrap()
+            );
+
+            let mut expected = feedback_not!(ConstFeedback::new(a));
+            let mut built = FeedbackBuilder::<_, NopState<BytesInput>>::new(ConstFeedback::new(a))
+                .not()
+                .build();
+            assert_eq!(
+                built
+                    .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                    .unwrap(),
+                expected
+                    .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                    .unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! The [`BurstSampleFeedback`] keeps only the first interesting input of a burst of interesting
+//! inputs, such as the near-identical inputs some mutational stages produce in a row.
+
+use alloc::string::String;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    time::Duration,
+};
+
+use crate::{
+    bolts::{current_time, tuples::Named},
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// How long a burst of a [`BurstSampleFeedback`] lasts after the wrapped feedback last fired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BurstCooldown {
+    /// The burst ends after the given number of evaluations the wrapped feedback did not fire
+    Evaluations(usize),
+    /// The burst ends once the wrapped feedback did not fire for the given time
+    Duration(Duration),
+}
+
+/// A [`BurstSampleFeedback`] wraps another [`Feedback`] and samples one input per burst.
+/// Once the wrapped feedback fires, a cooldown starts, during which this feedback reports every
+/// input as not interesting. Each time the wrapped feedback fires during the cooldown, the cooldown
+/// starts over, so a burst only ends after the wrapped feedback stayed quiet for the whole cooldown.
+///
+/// Unlike rate limiting, there is no fixed window: a steady stream of interesting inputs is
+/// reduced to its first input.
+pub struct BurstSampleFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    cooldown: BurstCooldown,
+    /// The time the wrapped feedback last fired, if it fired at all
+    last_fire: Option<Duration>,
+    /// The number of evaluations since the wrapped feedback last fired
+    evaluations_since_fire: usize,
+    /// The number of inputs suppressed so far
+    suppressed: usize,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S> BurstSampleFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`BurstSampleFeedback`], wrapping the given feedback, with the given cooldown
+    pub fn new(inner: A, cooldown: BurstCooldown) -> Self {
+        let name = format!("BurstSample({})", inner.name());
+        Self {
+            inner,
+            cooldown,
+            last_fire: None,
+            evaluations_since_fire: 0,
+            suppressed: 0,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The cooldown after each firing of the wrapped feedback
+    #[must_use]
+    pub fn cooldown(&self) -> BurstCooldown {
+        self.cooldown
+    }
+
+    /// The number of interesting inputs suppressed so far
+    #[must_use]
+    pub fn suppressed(&self) -> usize {
+        self.suppressed
+    }
+
+    /// Returns `true` if the feedback is currently within a burst
+    #[must_use]
+    pub fn in_burst(&self) -> bool {
+        match (self.last_fire, self.cooldown) {
+            (None, _) => false,
+            (Some(_), BurstCooldown::Evaluations(evaluations)) => {
+                self.evaluations_since_fire < evaluations
+            }
+            (Some(last_fire), BurstCooldown::Duration(duration)) => {
+                current_time().saturating_sub(last_fire) < duration
+            }
+        }
+    }
+}
+
+impl<A, S> Debug for BurstSampleFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BurstSampleFeedback")
+            .field("name", &self.name)
+            .field("cooldown", &self.cooldown)
+            .field("suppressed", &self.suppressed)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, S> Named for BurstSampleFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S> Feedback<S> for BurstSampleFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let fired = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        if !fired {
+            self.evaluations_since_fire = self.evaluations_since_fire.saturating_add(1);
+            return Ok(false);
+        }
+
+        let in_burst = self.in_burst();
+        self.last_fire = Some(current_time());
+        self.evaluations_since_fire = 0;
+        if in_burst {
+            self.suppressed += 1;
+        }
+        Ok(!in_burst)
+    }
+
+    #[inline]
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    #[inline]
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::time::Duration;
+
+    use crate::{
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{BurstCooldown, BurstSampleFeedback, ConstFeedback, Feedback},
+        inputs::BytesInput,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_burst_sample_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+        let mut feedback =
+            BurstSampleFeedback::new(ConstFeedback::new(false), BurstCooldown::Evaluations(2));
+
+        // two bursts, the second one starting after two quiet evaluations
+        let fires = [
+            true, true, false, true, false, false, true, true, false, true,
+        ];
+        let passed: Vec<bool> = fires
+            .iter()
+            .map(|fire| {
+                feedback.inner = ConstFeedback::new(*fire);
+                feedback
+                    .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            passed,
+            [true, false, false, false, false, false, true, false, false, false]
+        );
+        assert_eq!(feedback.suppressed(), 4);
+
+        let mut feedback = BurstSampleFeedback::new(
+            ConstFeedback::new(true),
+            BurstCooldown::Duration(Duration::from_secs(3600)),
+        );
+        for expected in [true, false, false] {
+            assert_eq!(
+                feedback
+                    .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                    .unwrap(),
+                expected
+            );
+        }
+        assert!(feedback.in_burst());
+    }
+}
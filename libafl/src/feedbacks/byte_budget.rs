@@ -0,0 +1,225 @@
+//! The [`ByteBudgetFeedback`] bounds the growth of the corpus by a total byte budget.
+
+use alloc::string::String;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::{
+    bolts::{tuples::Named, HasLen},
+    corpus::{Corpus, Testcase},
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasCorpus},
+    Error,
+};
+
+/// A [`ByteBudgetFeedback`] wraps another [`Feedback`]. Once the total bytes of all inputs in the
+/// corpus, as reported by [`Corpus::stats`], reach the budget, it reports every input as not
+/// interesting, without asking the wrapped feedback.
+///
+/// By default, the feedback is re-enabled as soon as the corpus shrinks below the budget again,
+/// e.g., after evictions. See [`ByteBudgetFeedback::set_reenable`] to stay exhausted instead.
+/// Computing the stats iterates over the whole corpus, so this is best used with smaller corpora.
+pub struct ByteBudgetFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    /// The maximum total bytes of the corpus
+    budget: usize,
+    /// If the feedback is re-enabled once the corpus shrinks below the budget
+    reenable: bool,
+    /// If the budget was reached
+    exhausted: bool,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S> ByteBudgetFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`ByteBudgetFeedback`], wrapping the given feedback, for a corpus of at most
+    /// `budget` total bytes
+    pub fn new(inner: A, budget: usize) -> Self {
+        let name = format!("ByteBudget({})", inner.name());
+        Self {
+            inner,
+            budget,
+            reenable: true,
+            exhausted: false,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The maximum total bytes of the corpus
+    #[must_use]
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Sets if the feedback is re-enabled once the corpus shrinks below the budget again
+    pub fn set_reenable(&mut self, reenable: bool) {
+        self.reenable = reenable;
+    }
+
+    /// Returns `true` if the budget was reached in the last run
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
+
+impl<A, S> Debug for ByteBudgetFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ByteBudgetFeedback")
+            .field("name", &self.name)
+            .field("budget", &self.budget)
+            .field("reenable", &self.reenable)
+            .field("exhausted", &self.exhausted)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, S> Named for ByteBudgetFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S> Feedback<S> for ByteBudgetFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor + HasCorpus,
+    S::Input: HasLen,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        if !self.exhausted || self.reenable {
+            self.exhausted = state.corpus().stats()?.total_bytes >= self.budget;
+        }
+        if self.exhausted {
+            return Ok(false);
+        }
+        self.inner
+            .is_interesting(state, manager, input, observers, exit_kind)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{ByteBudgetFeedback, ConstFeedback, Feedback},
+        inputs::BytesInput,
+        state::{HasCorpus, StdState},
+    };
+
+    #[test]
+    fn test_byte_budget_feedback() {
+        let mut feedback = ByteBudgetFeedback::new(ConstFeedback::new(true), 10);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut mgr = NopEventManager::new();
+
+        // add 4-byte inputs until the budget is reached
+        let mut added = vec![];
+        loop {
+            let input = BytesInput::new(vec![0; 4]);
+            if !feedback
+                .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                .unwrap()
+            {
+                break;
+            }
+            added.push(state.corpus_mut().add(Testcase::new(input)).unwrap());
+        }
+        assert_eq!(added.len(), 3);
+        assert!(feedback.is_exhausted());
+
+        let input = BytesInput::new(vec![0]);
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap());
+
+        // evictions re-enable the feedback
+        state.corpus_mut().remove(added[0]).unwrap();
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap());
+
+        // unless configured to stay exhausted
+        state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![0; 4])))
+            .unwrap();
+        feedback.set_reenable(false);
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap());
+        state.corpus_mut().remove(added[1]).unwrap();
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap());
+    }
+}
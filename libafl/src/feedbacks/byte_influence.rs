@@ -0,0 +1,223 @@
+//! The [`ByteInfluenceFeedback`] records which input bytes likely caused new coverage,
+//! comparing a new testcase with its parent (a cheap, taint-lite heuristic).
+
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::{Corpus, Testcase},
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::{HasBytesVec, UsesInput},
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasCorpus, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata holding the byte offsets that likely influenced the new coverage,
+/// i.e., the offsets at which the input differs from its parent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ByteInfluenceMetadata {
+    /// The influencing byte offsets, in ascending order
+    pub offsets: Vec<usize>,
+}
+
+crate::impl_serdeany!(ByteInfluenceMetadata);
+
+/// Returns the offsets at which `input` differs from `parent`, including all offsets past the end of `parent`
+fn differing_offsets(parent: &[u8], input: &[u8]) -> Vec<usize> {
+    input
+        .iter()
+        .enumerate()
+        .filter(|(i, byte)| parent.get(*i) != Some(byte))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// A [`ByteInfluenceFeedback`] wraps another [`Feedback`], usually a map feedback. If the wrapped
+/// feedback finds the input interesting, the offsets at which the input differs from the current
+/// (scheduled) testcase, its parent, are added to the testcase as [`ByteInfluenceMetadata`].
+/// Without a current testcase, no metadata is added.
+///
+/// The result of the wrapped feedback is forwarded unchanged.
+pub struct ByteInfluenceFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    /// The offsets computed in the last run, if the wrapped feedback was interesting
+    last_offsets: Option<Vec<usize>>,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S> ByteInfluenceFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`ByteInfluenceFeedback`], wrapping the given feedback
+    pub fn new(inner: A) -> Self {
+        let name = format!("ByteInfluence({})", inner.name());
+        Self {
+            inner,
+            last_offsets: None,
+            name,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<A, S> Debug for ByteInfluenceFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ByteInfluenceFeedback")
+            .field("name", &self.name)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, S> Named for ByteInfluenceFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S> Feedback<S> for ByteInfluenceFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor + HasCorpus,
+    S::Input: HasBytesVec,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_offsets = None;
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+
+        if interesting {
+            if let Some(parent_idx) = *state.corpus().current() {
+                let mut parent = state.corpus().get(parent_idx)?.borrow_mut();
+                let parent_input = parent.load_input(state.corpus())?;
+                self.last_offsets = Some(differing_offsets(parent_input.bytes(), input.bytes()));
+            }
+        }
+        Ok(interesting)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(offsets) = self.last_offsets.take() {
+            testcase.add_metadata(ByteInfluenceMetadata { offsets });
+        }
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.last_offsets = None;
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{ByteInfluenceFeedback, ByteInfluenceMetadata, ConstFeedback, Feedback},
+        inputs::BytesInput,
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_byte_influence_feedback() {
+        let mut corpus = InMemoryCorpus::new();
+        let parent_idx = corpus
+            .add(Testcase::new(BytesInput::new(vec![1, 2, 3, 4])))
+            .unwrap();
+
+        let mut feedback = ByteInfluenceFeedback::new(ConstFeedback::new(true));
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut mgr = NopEventManager::new();
+
+        let cases = [
+            // without a parent, nothing is recorded
+            (None, vec![1, 2], None),
+            (
+                Some(parent_idx),
+                vec![1, 9, 3, 7, 5, 6],
+                Some(vec![1, 3, 4, 5]),
+            ),
+            (Some(parent_idx), vec![1, 2], Some(vec![])),
+        ];
+        for (current, bytes, expected) in cases {
+            *state.corpus_mut().current_mut() = current;
+            let input = BytesInput::new(bytes);
+            assert!(feedback
+                .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                .unwrap());
+            let mut testcase = Testcase::new(input);
+            feedback
+                .append_metadata(&mut state, &(), &mut testcase)
+                .unwrap();
+            assert_eq!(
+                testcase
+                    .metadata_map()
+                    .get::<ByteInfluenceMetadata>()
+                    .map(|meta| meta.offsets.clone()),
+                expected
+            );
+        }
+    }
+}
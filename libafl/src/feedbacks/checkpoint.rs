@@ -0,0 +1,247 @@
+//! The [`PostCheckpointMapFeedback`] ignores the coverage of runs that did not get past the
+//! initialization of a staged target, as marked by a checkpoint entry of a map.
+
+use alloc::string::{String, ToString};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// A [`PostCheckpointMapFeedback`] wraps another [`Feedback`], usually a
+/// [`crate::feedbacks::MapFeedback`], and only evaluates it for runs that hit the checkpoint entry
+/// of a [`MapObserver`], such as the edge at the end of the initialization of the target.
+/// Runs not reaching the checkpoint are not interesting, and their coverage is never added to the
+/// history of the wrapped feedback, so novelty is only measured on runs past the checkpoint.
+///
+/// A map does not record the order of its entries, so the setup coverage of runs reaching the
+/// checkpoint is still evaluated; it is usually not novel anymore after the first of these runs.
+pub struct PostCheckpointMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    observer_name: String,
+    /// The index of the checkpoint entry
+    checkpoint: usize,
+    /// If the last run reached the checkpoint
+    reached: bool,
+    /// The number of runs not reaching the checkpoint
+    unreached: usize,
+    name: String,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<A, O, S> PostCheckpointMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`PostCheckpointMapFeedback`], wrapping the given feedback, only evaluated for
+    /// runs hitting the `checkpoint` entry of the given map observer
+    pub fn new(inner: A, observer: &O, checkpoint: usize) -> Self {
+        let name = format!("PostCheckpoint({})", inner.name());
+        Self {
+            inner,
+            observer_name: observer.name().to_string(),
+            checkpoint,
+            reached: false,
+            unreached: 0,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The index of the checkpoint entry
+    #[must_use]
+    pub fn checkpoint(&self) -> usize {
+        self.checkpoint
+    }
+
+    /// The number of runs not reaching the checkpoint so far
+    #[must_use]
+    pub fn unreached(&self) -> usize {
+        self.unreached
+    }
+}
+
+impl<A, O, S> Debug for PostCheckpointMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PostCheckpointMapFeedback")
+            .field("name", &self.name)
+            .field("observer_name", &self.observer_name)
+            .field("checkpoint", &self.checkpoint)
+            .field("unreached", &self.unreached)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, O, S> Named for PostCheckpointMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, O, S> HasObserverName for PostCheckpointMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+impl<A, O, S> Feedback<S> for PostCheckpointMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "PostCheckpointMapFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        if self.checkpoint >= observer.usable_count() {
+            return Err(Error::illegal_argument(format!(
+                "PostCheckpointMapFeedback: checkpoint {} out of the bounds of observer {}",
+                self.checkpoint, self.observer_name
+            )));
+        }
+
+        self.reached = *observer.get(self.checkpoint) != observer.initial();
+        if !self.reached {
+            self.unreached += 1;
+            return Ok(false);
+        }
+        self.inner
+            .is_interesting(state, manager, input, observers, exit_kind)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        // The wrapped feedback did not evaluate this run, so it must not record its coverage
+        if self.reached {
+            self.inner.append_metadata(state, observers, testcase)?;
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        if self.reached {
+            self.inner.discard_metadata(state, input)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{ConstFeedback, Feedback, MaxMapFeedback, PostCheckpointMapFeedback},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_post_checkpoint_map_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let initial = StdMapObserver::owned("map", vec![0_u8; 4]);
+        let mut feedback = PostCheckpointMapFeedback::new(
+            MaxMapFeedback::<_, NopState<BytesInput>, u8>::new(&initial),
+            &initial,
+            0,
+        );
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |map: [u8; 4]| {
+            let observers = tuple_list![StdMapObserver::owned("map", map.to_vec())];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            // keep the testcase anyway, as if another feedback considered it interesting
+            feedback
+                .append_metadata(&mut state, &observers, &mut Testcase::new(input.clone()))
+                .unwrap();
+            interesting
+        };
+
+        // the checkpoint is not reached, the coverage is ignored
+        assert!(!run([0, 1, 1, 0]));
+        assert!(!run([0, 0, 0, 1]));
+        // past the checkpoint, the same coverage is novel
+        assert!(run([1, 1, 1, 0]));
+        assert!(!run([1, 1, 0, 0]));
+        assert!(!run([0, 2, 0, 1]));
+        assert!(run([1, 0, 0, 1]));
+        assert_eq!(feedback.unreached(), 3);
+
+        // a checkpoint outside of the map is an error
+        let observers = tuple_list![StdMapObserver::owned("map", vec![1_u8; 4])];
+        let mut feedback = PostCheckpointMapFeedback::new(ConstFeedback::new(true), &initial, 4);
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .is_err());
+    }
+}
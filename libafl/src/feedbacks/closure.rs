@@ -0,0 +1,265 @@
+//! Closure Feedback, turning a closure into a [`Feedback`] to quickly prototype feedback logic.
+//!
+
+use alloc::string::{String, ToString};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::{
+    bolts::tuples::Named,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// A [`FnFeedback`] calls the given closure to decide if an input is interesting, from the input
+/// and the [`ExitKind`] of the run. To also inspect an observer, see [`ObserverFnFeedback`].
+pub struct FnFeedback<F, S>
+where
+    F: FnMut(&mut S, &S::Input, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput,
+{
+    name: String,
+    func: F,
+    phantom: PhantomData<S>,
+}
+
+impl<F, S> FnFeedback<F, S>
+where
+    F: FnMut(&mut S, &S::Input, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput,
+{
+    /// Creates a new [`FnFeedback`] with the given name, deciding interestingness with `func`
+    pub fn new(name: &str, func: F) -> Self {
+        Self {
+            name: name.to_string(),
+            func,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, S> Debug for FnFeedback<F, S>
+where
+    F: FnMut(&mut S, &S::Input, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnFeedback")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, S> Named for FnFeedback<F, S>
+where
+    F: FnMut(&mut S, &S::Input, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<F, S> Feedback<S> for FnFeedback<F, S>
+where
+    F: FnMut(&mut S, &S::Input, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        input: &S::Input,
+        _observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        (self.func)(state, input, exit_kind)
+    }
+}
+
+/// An [`ObserverFnFeedback`] calls the given closure to decide if an input is interesting, like a
+/// [`FnFeedback`], also passing it the observer of type `O` with the given name, looked up in the
+/// observers of the run. If there is no such observer, an error is returned.
+pub struct ObserverFnFeedback<F, O, S>
+where
+    F: FnMut(&mut S, &S::Input, &O, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput,
+{
+    name: String,
+    observer_name: String,
+    func: F,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<F, O, S> ObserverFnFeedback<F, O, S>
+where
+    F: FnMut(&mut S, &S::Input, &O, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput,
+{
+    /// Creates a new [`ObserverFnFeedback`] with the given name, deciding interestingness with
+    /// `func` from the observer named `observer_name`.
+    pub fn new(name: &str, observer_name: &str, func: F) -> Self {
+        Self {
+            name: name.to_string(),
+            observer_name: observer_name.to_string(),
+            func,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, O, S> Debug for ObserverFnFeedback<F, O, S>
+where
+    F: FnMut(&mut S, &S::Input, &O, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObserverFnFeedback")
+            .field("name", &self.name)
+            .field("observer_name", &self.observer_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, O, S> Named for ObserverFnFeedback<F, O, S>
+where
+    F: FnMut(&mut S, &S::Input, &O, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<F, O, S> HasObserverName for ObserverFnFeedback<F, O, S>
+where
+    F: FnMut(&mut S, &S::Input, &O, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+impl<F, O, S> Feedback<S> for ObserverFnFeedback<F, O, S>
+where
+    F: FnMut(&mut S, &S::Input, &O, &ExitKind) -> Result<bool, Error>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "ObserverFnFeedback {}: observer {} not found",
+                    self.name, self.observer_name
+                ))
+            })?;
+        (self.func)(state, input, observer, exit_kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::{tuple_list, Named},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, FnFeedback, ObserverFnFeedback},
+        inputs::{BytesInput, HasBytesVec},
+        observers::{MapObserver, StdMapObserver},
+        state::NopState,
+    };
+
+    #[test]
+    fn test_fn_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let mut feedback = FnFeedback::new(
+            "longer_than_4",
+            |_state: &mut NopState<BytesInput>, input: &BytesInput, _exit_kind| {
+                Ok(input.bytes().len() > 4)
+            },
+        );
+        assert_eq!(feedback.name(), "longer_than_4");
+
+        for (bytes, expected) in [
+            (vec![], false),
+            (vec![1, 2, 3, 4], false),
+            (vec![1, 2, 3, 4, 5], true),
+        ] {
+            let input = BytesInput::new(bytes);
+            assert_eq!(
+                feedback
+                    .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                    .unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_observer_fn_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let mut feedback = ObserverFnFeedback::new(
+            "longer_than_4_and_covering",
+            "map",
+            |_state: &mut NopState<BytesInput>,
+             input: &BytesInput,
+             observer: &StdMapObserver<u8, false>,
+             _exit_kind| { Ok(input.bytes().len() > 4 && observer.count_bytes() > 0) },
+        );
+        assert_eq!(feedback.name(), "longer_than_4_and_covering");
+
+        for (bytes, covered, expected) in [
+            (vec![], 1_u8, false),
+            (vec![1, 2, 3, 4], 1, false),
+            (vec![1, 2, 3, 4, 5], 0, false),
+            (vec![1, 2, 3, 4, 5], 1, true),
+        ] {
+            let input = BytesInput::new(bytes);
+            let observers = tuple_list![StdMapObserver::owned("map", vec![covered, 0])];
+            assert_eq!(
+                feedback
+                    .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                    .unwrap(),
+                expected
+            );
+        }
+
+        // a missing observer is an error
+        let input = BytesInput::new(vec![1, 2, 3, 4, 5]);
+        let observers = tuple_list![StdMapObserver::owned("other", vec![1_u8, 0])];
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .is_err());
+    }
+}
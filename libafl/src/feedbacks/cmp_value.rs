@@ -0,0 +1,333 @@
+//! The [`NewCmpValueFeedback`] keeps inputs producing operand values not seen before at a
+//! comparison site, as logged by a [`CmpObserver`], for comparison-guided fuzzing.
+
+use alloc::{
+    collections::VecDeque,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::Debug,
+    hash::{BuildHasher, Hasher},
+    marker::PhantomData,
+};
+
+use ahash::RandomState;
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{CmpMap, CmpObserver, CmpValues, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const NEW_CMP_VALUE_FEEDBACK_PREFIX: &str = "newcmpvaluefeedback_metadata_";
+
+/// Default maximum number of `(site, value)` pairs remembered by a [`NewCmpValueFeedback`]
+pub const DEFAULT_MAX_CMP_VALUES: usize = 1 << 20;
+
+/// The state of [`NewCmpValueFeedback`]: the seen `(site, value hash)` pairs, oldest first
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct NewCmpValueFeedbackMetadata {
+    /// The seen pairs
+    pub seen: HashSet<(usize, u64)>,
+    /// The seen pairs, in the order they were first seen
+    pub order: VecDeque<(usize, u64)>,
+}
+
+crate::impl_serdeany!(NewCmpValueFeedbackMetadata);
+
+impl NewCmpValueFeedbackMetadata {
+    /// Create a new [`NewCmpValueFeedbackMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a pair, returning `true` if it was not seen before.
+    /// If more than `max_pairs` are stored, the oldest ones are forgotten.
+    pub fn insert(&mut self, pair: (usize, u64), max_pairs: usize) -> bool {
+        if !self.seen.insert(pair) {
+            return false;
+        }
+        self.order.push_back(pair);
+        while self.order.len() > max_pairs {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.seen.clear();
+        self.order.clear();
+        Ok(())
+    }
+}
+
+/// A testcase metadata holding the comparison sites at which the testcase produced new values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewCmpValueMetadata {
+    /// The comparison sites, in ascending order and without duplicates
+    pub sites: Vec<usize>,
+}
+
+crate::impl_serdeany!(NewCmpValueMetadata);
+
+/// Computes a stable hash of the operands of a comparison.
+/// Numeric operands hash the same regardless of their width.
+#[must_use]
+pub fn cmp_value_hash(values: &CmpValues) -> u64 {
+    let mut hasher = RandomState::with_seeds(0, 0, 0, 0).build_hasher();
+    match values {
+        CmpValues::Bytes((v0, v1)) => {
+            hasher.write_u8(1);
+            hasher.write_usize(v0.len());
+            hasher.write(v0);
+            hasher.write_usize(v1.len());
+            hasher.write(v1);
+        }
+        numeric => {
+            let (v0, v1) = numeric.to_u64_tuple().unwrap();
+            hasher.write_u8(0);
+            hasher.write_u64(v0);
+            hasher.write_u64(v1);
+        }
+    }
+    hasher.finish()
+}
+
+/// A [`NewCmpValueFeedback`] considers an input interesting if, at any comparison site logged by
+/// a [`CmpObserver`], it produced operand values not seen before at that site.
+/// Only hashes of the values are stored, bounded by a maximum number of `(site, value)` pairs,
+/// after which the oldest ones are forgotten.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NewCmpValueFeedback<CM, O, S> {
+    name: String,
+    observer_name: String,
+    /// The maximum number of remembered pairs
+    max_pairs: usize,
+    /// The sites with new values in the last run
+    last_sites: Option<Vec<usize>>,
+    phantom: PhantomData<(CM, O, S)>,
+}
+
+impl<CM, O, S> NewCmpValueFeedback<CM, O, S>
+where
+    CM: CmpMap,
+    O: CmpObserver<CM, S>,
+    S: UsesInput,
+{
+    /// Creates a new [`NewCmpValueFeedback`] for the given cmp observer
+    #[must_use]
+    pub fn new(observer: &O) -> Self {
+        Self::with_max_pairs(observer, DEFAULT_MAX_CMP_VALUES)
+    }
+
+    /// Creates a new [`NewCmpValueFeedback`] remembering at most `max_pairs` `(site, value)` pairs
+    #[must_use]
+    pub fn with_max_pairs(observer: &O, max_pairs: usize) -> Self {
+        Self {
+            name: NEW_CMP_VALUE_FEEDBACK_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            max_pairs,
+            last_sites: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<CM, O, S> Feedback<S> for NewCmpValueFeedback<CM, O, S>
+where
+    CM: CmpMap,
+    O: CmpObserver<CM, S>,
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(NewCmpValueFeedbackMetadata::new(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "NewCmpValueFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let seen = state
+            .named_metadata_map_mut()
+            .get_mut::<NewCmpValueFeedbackMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "NewCmpValueFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+
+        let cmp_map = observer.cmp_map();
+        let mut sites = Vec::new();
+        for site in 0..observer.usable_count() {
+            for execution in 0..cmp_map.usable_executions_for(site) {
+                if let Some(values) = cmp_map.values_of(site, execution) {
+                    if seen.insert((site, cmp_value_hash(&values)), self.max_pairs)
+                        && sites.last() != Some(&site)
+                    {
+                        sites.push(site);
+                    }
+                }
+            }
+        }
+        let interesting = !sites.is_empty();
+        self.last_sites = Some(sites);
+        Ok(interesting)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(sites) = self.last_sites.take() {
+            testcase.add_metadata(NewCmpValueMetadata { sites });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_sites = None;
+        Ok(())
+    }
+}
+
+impl<CM, O, S> Named for NewCmpValueFeedback<CM, O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<CM, O, S> HasObserverName for NewCmpValueFeedback<CM, O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, NewCmpValueFeedback, NewCmpValueMetadata},
+        inputs::BytesInput,
+        observers::{CmpMap, CmpValues, StdCmpObserver},
+        state::{HasMetadata, NopState},
+        Error,
+    };
+
+    /// A [`CmpMap`] logging the given `u64` operands per site
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct TestCmpMap {
+        sites: Vec<Vec<(u64, u64)>>,
+    }
+
+    impl CmpMap for TestCmpMap {
+        fn len(&self) -> usize {
+            self.sites.len()
+        }
+
+        fn executions_for(&self, idx: usize) -> usize {
+            self.sites[idx].len()
+        }
+
+        fn usable_executions_for(&self, idx: usize) -> usize {
+            self.sites[idx].len()
+        }
+
+        fn values_of(&self, idx: usize, execution: usize) -> Option<CmpValues> {
+            self.sites[idx].get(execution).copied().map(CmpValues::U64)
+        }
+
+        fn reset(&mut self) -> Result<(), Error> {
+            self.sites.clear();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_new_cmp_value_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut map = TestCmpMap::default();
+        let mut feedback = NewCmpValueFeedback::new(
+            &StdCmpObserver::<_, NopState<BytesInput>>::new("cmplog", &mut map, false),
+        );
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |sites: Vec<Vec<(u64, u64)>>| {
+            let mut map = TestCmpMap { sites };
+            let observers = tuple_list![StdCmpObserver::new("cmplog", &mut map, false)];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            (
+                interesting,
+                testcase
+                    .metadata::<NewCmpValueMetadata>()
+                    .unwrap()
+                    .sites
+                    .clone(),
+            )
+        };
+
+        assert_eq!(
+            run(vec![vec![(1, 2)], vec![(3, 4), (5, 6)]]),
+            (true, vec![0, 1])
+        );
+        // the same pairs again
+        assert_eq!(run(vec![vec![(1, 2)], vec![(5, 6)]]), (false, vec![]));
+        // a known value at another site is new
+        assert_eq!(run(vec![vec![(3, 4)], vec![(3, 4)]]), (true, vec![0]));
+    }
+}
@@ -0,0 +1,253 @@
+//! The [`CoverageDiffFeedback`] compares the covered entries of two maps, for example of two
+//! builds of the same program, for differential fuzzing.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata holding the entries covered in only one of the two maps of a
+/// [`CoverageDiffFeedback`], as indices of the first map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageDiffMetadata {
+    /// The differing indices, in ascending order
+    pub indices: Vec<usize>,
+}
+
+crate::impl_serdeany!(CoverageDiffMetadata);
+
+/// A [`CoverageDiffFeedback`] compares the covered entries (the entries differing from the initial
+/// value) of two [`MapObserver`]s. It is interesting if the symmetric difference of the covered
+/// entries is non-empty, and adds the differing indices as [`CoverageDiffMetadata`].
+///
+/// By default, the maps share their index space. Otherwise, a mapping from the indices of the
+/// first map to the indices of the second map can be given, see [`CoverageDiffFeedback::with_mapping`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CoverageDiffFeedback<O1, O2, S> {
+    name: String,
+    /// The first observer to compare against
+    o1_name: String,
+    /// The second observer to compare against
+    o2_name: String,
+    /// For each index of the first map, the corresponding index of the second map, if any
+    mapping: Option<Vec<Option<usize>>>,
+    /// The differing indices of the last run
+    last_indices: Option<Vec<usize>>,
+    phantom: PhantomData<(O1, O2, S)>,
+}
+
+impl<O1, O2, S> CoverageDiffFeedback<O1, O2, S>
+where
+    O1: MapObserver,
+    O2: MapObserver,
+{
+    /// Creates a new [`CoverageDiffFeedback`] for two maps sharing their index space
+    pub fn new(name: &str, o1: &O1, o2: &O2) -> Result<Self, Error> {
+        let o1_name = o1.name().to_string();
+        let o2_name = o2.name().to_string();
+        if o1_name == o2_name {
+            return Err(Error::illegal_argument(format!(
+                "CoverageDiffFeedback: observer names must be different (both were {o1_name})"
+            )));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            o1_name,
+            o2_name,
+            mapping: None,
+            last_indices: None,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Creates a new [`CoverageDiffFeedback`], where `mapping[i]` is the index of the second map
+    /// corresponding to index `i` of the first map. Indices without a counterpart in the other
+    /// map are not compared.
+    pub fn with_mapping(
+        name: &str,
+        o1: &O1,
+        o2: &O2,
+        mapping: Vec<Option<usize>>,
+    ) -> Result<Self, Error> {
+        let mut feedback = Self::new(name, o1, o2)?;
+        feedback.mapping = Some(mapping);
+        Ok(feedback)
+    }
+
+    /// Computes the indices, of the first map, covered in only one of the two maps
+    #[must_use]
+    pub fn diff(&self, o1: &O1, o2: &O2) -> Vec<usize> {
+        let covered1 = |i: usize| i < o1.usable_count() && *o1.get(i) != o1.initial();
+        let covered2 = |j: usize| j < o2.usable_count() && *o2.get(j) != o2.initial();
+        match &self.mapping {
+            Some(mapping) => mapping
+                .iter()
+                .enumerate()
+                .filter_map(|(i, j)| j.map(|j| (i, j)))
+                .filter(|&(i, j)| covered1(i) != covered2(j))
+                .map(|(i, _)| i)
+                .collect(),
+            None => (0..o1.usable_count().max(o2.usable_count()))
+                .filter(|&i| covered1(i) != covered2(i))
+                .collect(),
+        }
+    }
+}
+
+impl<O1, O2, S> Named for CoverageDiffFeedback<O1, O2, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O1, O2, S> Feedback<S> for CoverageDiffFeedback<O1, O2, S>
+where
+    O1: MapObserver,
+    O2: MapObserver,
+    S: UsesInput + Debug + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        fn err(name: &str) -> Error {
+            Error::key_not_found(format!("CoverageDiffFeedback: observer {name} not found"))
+        }
+        let o1 = observers
+            .match_name::<O1>(&self.o1_name)
+            .ok_or_else(|| err(&self.o1_name))?;
+        let o2 = observers
+            .match_name::<O2>(&self.o2_name)
+            .ok_or_else(|| err(&self.o2_name))?;
+
+        let indices = self.diff(o1, o2);
+        let interesting = !indices.is_empty();
+        self.last_indices = Some(indices);
+        Ok(interesting)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(indices) = self.last_indices.take() {
+            testcase.add_metadata(CoverageDiffMetadata { indices });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_indices = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{CoverageDiffFeedback, CoverageDiffMetadata, Feedback},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_coverage_diff_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut run = |feedback: &mut CoverageDiffFeedback<_, _, NopState<BytesInput>>,
+                       map1: Vec<u8>,
+                       map2: Vec<u8>| {
+            let observers = tuple_list![
+                StdMapObserver::owned("first", map1),
+                StdMapObserver::owned("second", map2)
+            ];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            let indices = testcase
+                .metadata::<CoverageDiffMetadata>()
+                .unwrap()
+                .indices
+                .clone();
+            (interesting, indices)
+        };
+
+        let first = StdMapObserver::owned("first", vec![0_u8; 4]);
+        let second = StdMapObserver::owned("second", vec![0_u8; 4]);
+        let mut feedback = CoverageDiffFeedback::new("diff", &first, &second).unwrap();
+        // same covered entries, different hitcounts
+        assert_eq!(
+            run(&mut feedback, vec![1, 0, 5, 0], vec![2, 0, 1, 0]),
+            (false, vec![])
+        );
+        assert_eq!(
+            run(&mut feedback, vec![1, 1, 0, 0], vec![1, 0, 1, 0]),
+            (true, vec![1, 2])
+        );
+
+        // the second build has its entries in reverse order, and no counterpart for entry 3
+        let mut feedback = CoverageDiffFeedback::with_mapping(
+            "diff",
+            &first,
+            &second,
+            vec![Some(3), Some(2), Some(1), None],
+        )
+        .unwrap();
+        assert_eq!(
+            run(&mut feedback, vec![1, 1, 0, 1], vec![0, 0, 1, 1]),
+            (false, vec![])
+        );
+        assert_eq!(
+            run(&mut feedback, vec![1, 0, 1, 0], vec![0, 0, 1, 1]),
+            (true, vec![1, 2])
+        );
+
+        assert!(
+            CoverageDiffFeedback::<_, _, NopState<BytesInput>>::new("diff", &first, &first)
+                .is_err()
+        );
+    }
+}
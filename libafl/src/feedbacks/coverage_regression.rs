@@ -0,0 +1,291 @@
+//! The [`CoverageRegressionFeedback`] detects inputs covering fewer map entries when they are run
+//! again, a sign of nondeterminism in the target, or of a regression.
+
+use alloc::{
+    collections::VecDeque,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::{input_hash, Testcase},
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const COVERAGE_REGRESSION_FEEDBACK_PREFIX: &str = "coverageregressionfeedback_metadata_";
+
+/// Default maximum number of inputs remembered by a [`CoverageRegressionFeedback`]
+pub const DEFAULT_MAX_INPUTS: usize = 1 << 16;
+
+/// The state of [`CoverageRegressionFeedback`]: the covered entries of each seen input, by
+/// [`input_hash`]
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct CoverageRegressionFeedbackMetadata {
+    /// The union of the entries covered by all the runs of each input, in ascending order
+    pub coverage: HashMap<u64, Vec<usize>>,
+    /// The seen inputs, in the order they were first seen
+    pub order: VecDeque<u64>,
+}
+
+crate::impl_serdeany!(CoverageRegressionFeedbackMetadata);
+
+impl CoverageRegressionFeedbackMetadata {
+    /// Create a new [`CoverageRegressionFeedbackMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the entries covered by a run of the input with the given hash, in ascending order.
+    /// Returns the entries covered by previous runs of the input, but not by this one.
+    /// If more than `max_inputs` are stored, the oldest ones are forgotten.
+    pub fn record(&mut self, hash: u64, covered: Vec<usize>, max_inputs: usize) -> Vec<usize> {
+        let Some(previous) = self.coverage.get_mut(&hash) else {
+            self.coverage.insert(hash, covered);
+            self.order.push_back(hash);
+            while self.order.len() > max_inputs {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.coverage.remove(&oldest);
+                }
+            }
+            return vec![];
+        };
+
+        let lost = previous
+            .iter()
+            .copied()
+            .filter(|i| covered.binary_search(i).is_err())
+            .collect();
+        previous.extend(covered);
+        previous.sort_unstable();
+        previous.dedup();
+        lost
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.coverage.clear();
+        self.order.clear();
+        Ok(())
+    }
+}
+
+/// A testcase metadata holding the map entries an input covered in previous runs, but not anymore
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageRegressionMetadata {
+    /// The lost indices, in ascending order
+    pub lost: Vec<usize>,
+}
+
+crate::impl_serdeany!(CoverageRegressionMetadata);
+
+/// A [`CoverageRegressionFeedback`] remembers the entries of a [`MapObserver`] covered by each
+/// input, and considers a run interesting if it covers strictly fewer of the entries covered by
+/// the previous runs of the same input. It is meant to be used as an objective, to monitor the
+/// stability of the target; the lost entries are added to the testcase as
+/// [`CoverageRegressionMetadata`].
+///
+/// Inputs are identified by their [`input_hash`]. The number of remembered inputs is bounded,
+/// after which the oldest ones are forgotten.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CoverageRegressionFeedback<O, S> {
+    name: String,
+    observer_name: String,
+    /// The maximum number of remembered inputs
+    max_inputs: usize,
+    /// The entries lost in the last run, if any
+    last_lost: Option<Vec<usize>>,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<O, S> CoverageRegressionFeedback<O, S>
+where
+    O: MapObserver,
+{
+    /// Creates a new [`CoverageRegressionFeedback`] for the given map observer
+    #[must_use]
+    pub fn new(observer: &O) -> Self {
+        Self::with_max_inputs(observer, DEFAULT_MAX_INPUTS)
+    }
+
+    /// Creates a new [`CoverageRegressionFeedback`] remembering at most `max_inputs`
+    #[must_use]
+    pub fn with_max_inputs(observer: &O, max_inputs: usize) -> Self {
+        Self {
+            name: COVERAGE_REGRESSION_FEEDBACK_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            max_inputs,
+            last_lost: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O, S> Feedback<S> for CoverageRegressionFeedback<O, S>
+where
+    O: MapObserver,
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(CoverageRegressionFeedbackMetadata::new(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "CoverageRegressionFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let initial = observer.initial();
+        let covered = (0..observer.usable_count())
+            .filter(|i| *observer.get(*i) != initial)
+            .collect();
+
+        let seen = state
+            .named_metadata_map_mut()
+            .get_mut::<CoverageRegressionFeedbackMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "CoverageRegressionFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+        let lost = seen.record(input_hash(input)?, covered, self.max_inputs);
+        self.last_lost = (!lost.is_empty()).then_some(lost);
+        Ok(self.last_lost.is_some())
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(lost) = self.last_lost.take() {
+            testcase.add_metadata(CoverageRegressionMetadata { lost });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_lost = None;
+        Ok(())
+    }
+}
+
+impl<O, S> Named for CoverageRegressionFeedback<O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, S> HasObserverName for CoverageRegressionFeedback<O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            CoverageRegressionFeedback, CoverageRegressionFeedbackMetadata,
+            CoverageRegressionMetadata, Feedback,
+        },
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_coverage_regression_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let flaky = BytesInput::new(vec![0]);
+        let other = BytesInput::new(vec![1]);
+
+        let mut feedback: CoverageRegressionFeedback<_, NopState<BytesInput>> =
+            CoverageRegressionFeedback::new(&StdMapObserver::owned("map", vec![0_u8; 8]));
+        feedback.init_state(&mut state).unwrap();
+
+        // returns the lost entries of the kept testcase, if any
+        let mut run = |input: &BytesInput, map: [u8; 8]| {
+            let observers = tuple_list![StdMapObserver::owned("map", map.to_vec())];
+            if !feedback
+                .is_interesting(&mut state, &mut mgr, input, &observers, &ExitKind::Ok)
+                .unwrap()
+            {
+                return None;
+            }
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            Some(
+                testcase
+                    .metadata::<CoverageRegressionMetadata>()
+                    .unwrap()
+                    .lost
+                    .clone(),
+            )
+        };
+
+        assert_eq!(run(&flaky, [1, 0, 1, 0, 1, 0, 0, 0]), None);
+        // the same coverage, with other hitcounts, or more coverage, is fine
+        assert_eq!(run(&flaky, [4, 0, 1, 0, 2, 0, 0, 0]), None);
+        assert_eq!(run(&flaky, [1, 0, 1, 0, 1, 1, 0, 0]), None);
+        // the coverage shrinks on a re-run
+        assert_eq!(run(&flaky, [1, 0, 0, 0, 1, 0, 0, 1]), Some(vec![2, 5]));
+        // other inputs are tracked separately
+        assert_eq!(run(&other, [0; 8]), None);
+    }
+
+    #[test]
+    fn test_coverage_regression_bounded() {
+        let mut seen = CoverageRegressionFeedbackMetadata::new();
+        for hash in 0..4 {
+            assert!(seen.record(hash, vec![1, 2], 2).is_empty());
+        }
+        assert_eq!(seen.coverage.len(), 2);
+        assert_eq!(seen.record(3, vec![1], 2), vec![2]);
+        // the oldest inputs were forgotten
+        assert!(seen.record(0, vec![], 2).is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+//! The [`DeadlineMapFeedback`] only reports novelty until a deadline, freezing the corpus for the
+//! remainder of the campaign, e.g., to test the stability of short fuzzing runs in CI.
+
+use alloc::string::String;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    time::Duration,
+};
+use std::time::Instant;
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// A [`DeadlineMapFeedback`] wraps another [`Feedback`], usually a
+/// [`crate::feedbacks::MapFeedback`], and forwards its result until the deadline.
+/// Past the deadline, no input is interesting anymore.
+///
+/// By default, the wrapped feedback is not evaluated at all past the deadline. With
+/// [`DeadlineMapFeedback::set_annotate`], it is still evaluated, its result ignored, so that it
+/// keeps its history up to date and annotates the testcases kept by other feedbacks, such as
+/// objectives.
+pub struct DeadlineMapFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    deadline: Instant,
+    /// If the wrapped feedback is still evaluated past the deadline
+    annotate: bool,
+    /// If the wrapped feedback evaluated the last run
+    evaluated: bool,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S> DeadlineMapFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`DeadlineMapFeedback`], wrapping the given feedback until `deadline`
+    pub fn new(inner: A, deadline: Instant) -> Self {
+        let name = format!("Deadline({})", inner.name());
+        Self {
+            inner,
+            deadline,
+            annotate: false,
+            evaluated: false,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a new [`DeadlineMapFeedback`], wrapping the given feedback for `budget` from now
+    pub fn with_budget(inner: A, budget: Duration) -> Self {
+        Self::new(inner, Instant::now() + budget)
+    }
+
+    /// The deadline
+    #[must_use]
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Sets the deadline
+    pub fn set_deadline(&mut self, deadline: Instant) {
+        self.deadline = deadline;
+    }
+
+    /// Returns `true` if the deadline has passed
+    #[must_use]
+    pub fn expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    /// If the wrapped feedback is still evaluated past the deadline
+    #[must_use]
+    pub fn annotate(&self) -> bool {
+        self.annotate
+    }
+
+    /// Sets if the wrapped feedback is still evaluated past the deadline, to annotate testcases
+    pub fn set_annotate(&mut self, annotate: bool) {
+        self.annotate = annotate;
+    }
+}
+
+impl<A, S> Debug for DeadlineMapFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeadlineMapFeedback")
+            .field("name", &self.name)
+            .field("deadline", &self.deadline)
+            .field("annotate", &self.annotate)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, S> Named for DeadlineMapFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S> Feedback<S> for DeadlineMapFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let expired = self.expired();
+        self.evaluated = !expired || self.annotate;
+        if !self.evaluated {
+            return Ok(false);
+        }
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        Ok(interesting && !expired)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if self.evaluated {
+            self.inner.append_metadata(state, observers, testcase)?;
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        if self.evaluated {
+            self.inner.discard_metadata(state, input)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::{thread, time::Instant};
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{DeadlineMapFeedback, Feedback, MapIndexesMetadata, MaxMapFeedback},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_deadline_map_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let initial = StdMapObserver::owned("map", vec![0_u8; 4]);
+        let mut feedback = DeadlineMapFeedback::with_budget(
+            MaxMapFeedback::<_, NopState<BytesInput>, u8>::tracking(&initial, true, false),
+            Duration::from_millis(200),
+        );
+        feedback.init_state(&mut state).unwrap();
+
+        // returns if the run is interesting, and if the kept testcase got annotated
+        let mut run = |feedback: &mut DeadlineMapFeedback<_, _>, map: [u8; 4]| {
+            let observers = tuple_list![StdMapObserver::owned("map", map.to_vec())];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            // keep the testcase anyway, as if another feedback considered it interesting
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            (interesting, testcase.has_metadata::<MapIndexesMetadata>())
+        };
+
+        // before the deadline, the novelty is forwarded
+        assert!(!feedback.expired());
+        assert_eq!(run(&mut feedback, [1, 0, 0, 0]), (true, true));
+        assert_eq!(run(&mut feedback, [1, 0, 0, 0]), (false, true));
+
+        while Instant::now() < feedback.deadline() {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(feedback.expired());
+
+        // past the deadline, nothing is interesting anymore, nor annotated by default
+        assert_eq!(run(&mut feedback, [0, 1, 0, 0]), (false, false));
+        feedback.set_annotate(true);
+        assert_eq!(run(&mut feedback, [0, 0, 1, 0]), (false, true));
+
+        // moving the deadline again unfreezes the corpus; the second entry was never evaluated
+        feedback.set_deadline(Instant::now() + Duration::from_secs(60));
+        assert_eq!(run(&mut feedback, [0, 0, 1, 0]), (false, true));
+        assert_eq!(run(&mut feedback, [0, 1, 0, 0]), (true, true));
+    }
+}
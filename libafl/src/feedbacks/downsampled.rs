@@ -0,0 +1,299 @@
+//! The [`DownsampledMapFeedback`] folds groups of entries of a (large) map into a smaller map, and
+//! only tracks novelty on the smaller map, to save memory.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName, MapFeedbackMetadata, MaxReducer, OrReducer, Reducer},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const DOWNSAMPLED_MAP_FEEDBACK_PREFIX: &str = "downsampledmapfeedback_metadata_";
+
+/// A [`DownsampledMapFeedback`] keeping the maximum of each group of entries
+pub type MaxDownsampledMapFeedback<O, S, T> = DownsampledMapFeedback<O, MaxReducer, S, T>;
+/// A [`DownsampledMapFeedback`] combining the bits of each group of entries
+pub type OrDownsampledMapFeedback<O, S, T> = DownsampledMapFeedback<O, OrReducer, S, T>;
+
+/// A [`DownsampledMapFeedback`] folds each group of `factor` consecutive entries of a
+/// [`MapObserver`] into a single entry, using the [`Reducer`] `R`, and considers an input
+/// interesting if the resulting map is novel compared to the history of all inputs added so far.
+/// The history is a [`MapFeedbackMetadata`] of the downsampled map, `factor` times smaller than the
+/// history of a [`crate::feedbacks::MapFeedback`].
+///
+/// The price are collisions: all entries of a group share a single entry, so an input covering a
+/// new entry is not novel if another entry of the same group already reached the same value (or, for
+/// a [`MaxReducer`], a higher one). The larger the factor, the more novelty is missed.
+#[derive(Clone, Debug)]
+pub struct DownsampledMapFeedback<O, R, S, T> {
+    name: String,
+    observer_name: String,
+    /// The number of entries folded into a single one
+    factor: usize,
+    phantom: PhantomData<(O, R, S, T)>,
+}
+
+impl<O, R, S, T> DownsampledMapFeedback<O, R, S, T>
+where
+    O: MapObserver<Entry = T>,
+    R: Reducer<T>,
+    T: Default + Copy + PartialEq + 'static,
+{
+    /// Creates a new [`DownsampledMapFeedback`] for the given map observer, folding each group of
+    /// `factor` consecutive entries into a single one
+    #[must_use]
+    pub fn new(observer: &O, factor: usize) -> Self {
+        Self {
+            name: DOWNSAMPLED_MAP_FEEDBACK_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            factor: factor.max(1),
+            phantom: PhantomData,
+        }
+    }
+
+    /// The number of entries folded into a single one
+    #[must_use]
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Folds the map of the observer into the downsampled map
+    fn downsample(&self, observer: &O) -> Vec<T> {
+        let initial = observer.initial();
+        let len = observer.usable_count();
+        let mut downsampled = vec![initial; (len + self.factor - 1) / self.factor];
+        for i in 0..len {
+            let value = *observer.get(i);
+            if value != initial {
+                let entry = &mut downsampled[i / self.factor];
+                *entry = R::reduce(*entry, value);
+            }
+        }
+        downsampled
+    }
+}
+
+impl<O, R, S, T> Feedback<S> for DownsampledMapFeedback<O, R, S, T>
+where
+    O: MapObserver<Entry = T>,
+    R: Reducer<T>,
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+    T: Default + Copy + PartialEq + Serialize + DeserializeOwned + Debug + 'static,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(MapFeedbackMetadata::<T>::default(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "DownsampledMapFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let downsampled = self.downsample(observer);
+        let map_state = state
+            .named_metadata_map_mut()
+            .get_mut::<MapFeedbackMetadata<T>>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "DownsampledMapFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+
+        let initial = observer.initial();
+        if map_state.history_map.len() < downsampled.len() {
+            map_state.history_map.resize(downsampled.len(), initial);
+        }
+        Ok(downsampled
+            .iter()
+            .zip(&map_state.history_map)
+            .any(|(&value, &history)| value != initial && R::reduce(history, value) != history))
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        _testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "DownsampledMapFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let downsampled = self.downsample(observer);
+        let map_state = state
+            .named_metadata_map_mut()
+            .get_mut::<MapFeedbackMetadata<T>>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "DownsampledMapFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+
+        let initial = observer.initial();
+        if map_state.history_map.len() < downsampled.len() {
+            map_state.history_map.resize(downsampled.len(), initial);
+        }
+        for (history, value) in map_state.history_map.iter_mut().zip(downsampled) {
+            if value != initial {
+                *history = R::reduce(*history, value);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<O, R, S, T> Named for DownsampledMapFeedback<O, R, S, T> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, R, S, T> HasObserverName for DownsampledMapFeedback<O, R, S, T> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        bolts::tuples::{tuple_list, Named},
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            Feedback, MapFeedbackMetadata, MaxDownsampledMapFeedback, OrDownsampledMapFeedback,
+        },
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasNamedMetadata, NopState},
+    };
+
+    const MAP_SIZE: usize = 1 << 20;
+
+    #[test]
+    fn test_downsampled_map_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback: MaxDownsampledMapFeedback<_, NopState<BytesInput>, u8> =
+            MaxDownsampledMapFeedback::new(&StdMapObserver::owned("map", vec![0_u8; 16]), 64);
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |entries: &[(usize, u8)]| {
+            let mut map = vec![0_u8; MAP_SIZE];
+            for (idx, value) in entries {
+                map[*idx] = *value;
+            }
+            let observers = tuple_list![StdMapObserver::owned("map", map)];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            if interesting {
+                feedback
+                    .append_metadata(&mut state, &observers, &mut Testcase::new(input.clone()))
+                    .unwrap();
+            }
+            interesting
+        };
+
+        assert!(run(&[(0, 1)]));
+        // a new entry in the same group, with the same value, collides
+        assert!(!run(&[(63, 1)]));
+        // a higher value in the group is novel
+        assert!(run(&[(63, 2)]));
+        assert!(!run(&[(0, 1), (1, 2)]));
+        // the next group
+        assert!(run(&[(64, 1)]));
+        assert!(run(&[(MAP_SIZE - 1, 1)]));
+
+        let history = &state
+            .named_metadata_map()
+            .get::<MapFeedbackMetadata<u8>>(feedback.name())
+            .unwrap()
+            .history_map;
+        assert_eq!(history.len(), MAP_SIZE / 64);
+        let covered: Vec<_> = history
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value != 0)
+            .map(|(idx, value)| (idx, *value))
+            .collect();
+        assert_eq!(covered, [(0, 2), (1, 1), (MAP_SIZE / 64 - 1, 1)]);
+    }
+
+    #[test]
+    fn test_or_downsampled_map_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback: OrDownsampledMapFeedback<_, NopState<BytesInput>, u8> =
+            OrDownsampledMapFeedback::new(&StdMapObserver::owned("map", vec![0_u8; 16]), 4);
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |map: [u8; 16]| {
+            let observers = tuple_list![StdMapObserver::owned("map", map.to_vec())];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            if interesting {
+                feedback
+                    .append_metadata(&mut state, &observers, &mut Testcase::new(input.clone()))
+                    .unwrap();
+            }
+            interesting
+        };
+
+        assert!(run([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+        // the bits of a group are combined
+        assert!(run([0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+        assert!(!run([0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+        assert!(run([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4]));
+    }
+}
@@ -0,0 +1,265 @@
+//! The [`EfficiencyFeedback`] keeps inputs reaching a new maximum of covered map entries per input
+//! byte, to bias the corpus toward compact inputs with a high coverage.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Debug, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{tuples::Named, HasLen},
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const EFFICIENCY_FEEDBACK_PREFIX: &str = "efficiencyfeedback_metadata_";
+
+/// The state of [`EfficiencyFeedback`]: the maximum efficiency reached so far
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EfficiencyFeedbackMetadata {
+    /// The maximum efficiency, `None` before the first covering input
+    pub max: Option<f64>,
+}
+
+crate::impl_serdeany!(EfficiencyFeedbackMetadata);
+
+impl EfficiencyFeedbackMetadata {
+    /// Create a new [`EfficiencyFeedbackMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.max = None;
+        Ok(())
+    }
+}
+
+/// A testcase metadata holding the efficiency of the testcase, as computed by an
+/// [`EfficiencyFeedback`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EfficiencyMetadata {
+    /// The number of covered map entries
+    pub covered: usize,
+    /// The number of covered map entries per input byte
+    pub efficiency: f64,
+}
+
+crate::impl_serdeany!(EfficiencyMetadata);
+
+/// Computes the number of covered map entries per input byte. An empty input counts as a single
+/// byte, so that the efficiency stays finite.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn efficiency(covered: usize, input_len: usize) -> f64 {
+    covered as f64 / input_len.max(1) as f64
+}
+
+/// An [`EfficiencyFeedback`] considers interesting the inputs covering more entries of a
+/// [`MapObserver`] per input byte, see [`efficiency`], than any input before.
+/// The maximum is kept in the state, and the efficiency of the kept inputs is added to the testcase
+/// as [`EfficiencyMetadata`]. Inputs covering nothing are never interesting.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EfficiencyFeedback<O, S> {
+    name: String,
+    observer_name: String,
+    /// The metadata of the last run, if it reached a new maximum
+    last_efficiency: Option<EfficiencyMetadata>,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<O, S> EfficiencyFeedback<O, S>
+where
+    O: MapObserver,
+    S: HasNamedMetadata,
+{
+    /// Creates a new [`EfficiencyFeedback`] for the given map observer
+    #[must_use]
+    pub fn new(observer: &O) -> Self {
+        Self {
+            name: EFFICIENCY_FEEDBACK_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            last_efficiency: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The maximum efficiency reached so far, if any
+    pub fn max(&self, state: &S) -> Result<Option<f64>, Error> {
+        state
+            .named_metadata_map()
+            .get::<EfficiencyFeedbackMetadata>(&self.name)
+            .map(|meta| meta.max)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "EfficiencyFeedback: metadata {} not found",
+                    self.name
+                ))
+            })
+    }
+}
+
+impl<O, S> Feedback<S> for EfficiencyFeedback<O, S>
+where
+    O: MapObserver,
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+    S::Input: HasLen,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(EfficiencyFeedbackMetadata::new(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_efficiency = None;
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "EfficiencyFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let initial = observer.initial();
+        let covered = (0..observer.usable_count())
+            .filter(|i| *observer.get(*i) != initial)
+            .count();
+        if covered == 0 {
+            return Ok(false);
+        }
+
+        let meta = state
+            .named_metadata_map_mut()
+            .get_mut::<EfficiencyFeedbackMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "EfficiencyFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+        let efficiency = efficiency(covered, input.len());
+        if meta.max.map_or(false, |max| efficiency <= max) {
+            return Ok(false);
+        }
+        meta.max = Some(efficiency);
+        self.last_efficiency = Some(EfficiencyMetadata {
+            covered,
+            efficiency,
+        });
+        Ok(true)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(meta) = self.last_efficiency.take() {
+            testcase.add_metadata(meta);
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_efficiency = None;
+        Ok(())
+    }
+}
+
+impl<O, S> Named for EfficiencyFeedback<O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, S> HasObserverName for EfficiencyFeedback<O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{EfficiencyFeedback, EfficiencyMetadata, Feedback},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_efficiency_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let mut feedback: EfficiencyFeedback<_, NopState<BytesInput>> =
+            EfficiencyFeedback::new(&StdMapObserver::owned("map", vec![0_u8; 8]));
+        feedback.init_state(&mut state).unwrap();
+
+        // returns the efficiency of the kept testcase, if any
+        let mut run = |input_len: usize, covered: usize| {
+            let input = BytesInput::new(vec![0; input_len]);
+            let mut map = vec![0_u8; 8];
+            map[..covered].fill(1);
+            let observers = tuple_list![StdMapObserver::owned("map", map)];
+            if !feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+            {
+                return None;
+            }
+            let mut testcase = Testcase::new(input);
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            let meta = *testcase.metadata::<EfficiencyMetadata>().unwrap();
+            assert_eq!(meta.covered, covered);
+            Some(meta.efficiency)
+        };
+
+        // nothing covered
+        assert!(run(4, 0).is_none());
+        // a large input covering much
+        assert!((run(100, 8).unwrap() - 0.08).abs() < f64::EPSILON);
+        // a small input covering less beats it
+        assert!((run(2, 4).unwrap() - 2.0).abs() < f64::EPSILON);
+        assert!(run(4, 8).is_none());
+        assert!(run(10, 8).is_none());
+        // an empty input counts as a single byte
+        assert!((run(0, 3).unwrap() - 3.0).abs() < f64::EPSILON);
+        assert!(run(0, 3).is_none());
+
+        let max = feedback.max(&state).unwrap().unwrap();
+        assert!((max - 3.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,249 @@
+//! The [`ErrorVariantFeedback`] keeps one representative input per distinct error variant
+//! reported by the target through a [`ValueObserver`].
+
+use alloc::string::{String, ToString};
+use core::{fmt::Debug, marker::PhantomData};
+
+use hashbrown::HashSet;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{ObserversTuple, ValueObserver},
+    state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
+    Error,
+};
+
+/// The state of [`ErrorVariantFeedback`]
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct ErrorVariantFeedbackMetadata {
+    /// The names of all error variants seen so far
+    pub seen: HashSet<String>,
+}
+
+crate::impl_serdeany!(ErrorVariantFeedbackMetadata);
+
+/// A testcase metadata holding the error variant first triggered by this testcase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorVariantMetadata {
+    /// The name of the variant, such as `InvalidHeader`
+    pub variant: String,
+}
+
+crate::impl_serdeany!(ErrorVariantMetadata);
+
+/// Returns the name of the variant of `error`, i.e., its [`Debug`] representation without the fields
+fn variant_name<E>(error: &E) -> String
+where
+    E: Debug,
+{
+    let repr = format!("{error:?}");
+    match repr.find(['(', '{', ' ']) {
+        Some(end) => repr[..end].to_string(),
+        None => repr,
+    }
+}
+
+/// An [`ErrorVariantFeedback`] reads the error reported by the target from a [`ValueObserver`]
+/// of `Option<E>`, where `None` means no error, and reports as interesting the inputs triggering
+/// an error variant not seen before. Variants are told apart by name, ignoring their fields.
+/// The variant is added to the testcase as [`ErrorVariantMetadata`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ErrorVariantFeedback<E, S> {
+    name: String,
+    observer_name: String,
+    /// The novel variant of the last run, if any
+    last_variant: Option<String>,
+    phantom: PhantomData<(E, S)>,
+}
+
+impl<E, S> ErrorVariantFeedback<E, S>
+where
+    E: Debug + Serialize + DeserializeOwned,
+{
+    /// Creates a new [`ErrorVariantFeedback`] for the given [`ValueObserver`]
+    #[must_use]
+    pub fn new(observer: &ValueObserver<Option<E>>) -> Self {
+        Self::with_names(
+            &("errorvariantfeedback_".to_string() + observer.name()),
+            observer.name(),
+        )
+    }
+
+    /// Creates a new [`ErrorVariantFeedback`].
+    /// Setting an observer name that doesn't exist would eventually trigger a panic.
+    #[must_use]
+    pub fn with_names(name: &str, observer_name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            observer_name: observer_name.to_string(),
+            last_variant: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<E, S> Feedback<S> for ErrorVariantFeedback<E, S>
+where
+    E: Debug + Serialize + DeserializeOwned + 'static,
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(ErrorVariantFeedbackMetadata::default(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_variant = None;
+
+        let observer = observers
+            .match_name::<ValueObserver<Option<E>>>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "ErrorVariantFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let Some(error) = observer.get_ref() else {
+            return Ok(false);
+        };
+        let variant = variant_name(error);
+
+        let meta = state
+            .named_metadata_map_mut()
+            .get_mut::<ErrorVariantFeedbackMetadata>(&self.name)
+            .unwrap();
+        if meta.seen.contains(&variant) {
+            Ok(false)
+        } else {
+            meta.seen.insert(variant.clone());
+            self.last_variant = Some(variant);
+            Ok(true)
+        }
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(variant) = self.last_variant.take() {
+            testcase.add_metadata(ErrorVariantMetadata { variant });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_variant = None;
+        Ok(())
+    }
+}
+
+impl<E, S> Named for ErrorVariantFeedback<E, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<E, S> HasObserverName for ErrorVariantFeedback<E, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{ErrorVariantFeedback, ErrorVariantMetadata, Feedback},
+        inputs::BytesInput,
+        observers::ValueObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[allow(dead_code)]
+    enum ParseError {
+        InvalidHeader,
+        BadLength(usize),
+        Unexpected { offset: usize, byte: u8 },
+    }
+
+    #[test]
+    fn test_error_variant_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let initial: Option<ParseError> = None;
+        let mut feedback = ErrorVariantFeedback::new(&ValueObserver::new("error", &initial));
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |error: Option<ParseError>| {
+            let observers = tuple_list![ValueObserver::new("error", &error)];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            let variant = testcase
+                .metadata()
+                .get::<ErrorVariantMetadata>()
+                .map(|meta| meta.variant.clone());
+            assert_eq!(interesting, variant.is_some());
+            variant
+        };
+
+        assert_eq!(run(None), None);
+        assert_eq!(
+            run(Some(ParseError::BadLength(3))).as_deref(),
+            Some("BadLength")
+        );
+        // same variant, different fields
+        assert_eq!(run(Some(ParseError::BadLength(7))), None);
+        assert_eq!(
+            run(Some(ParseError::InvalidHeader)).as_deref(),
+            Some("InvalidHeader")
+        );
+        assert_eq!(
+            run(Some(ParseError::Unexpected {
+                offset: 4,
+                byte: 0xff
+            }))
+            .as_deref(),
+            Some("Unexpected")
+        );
+        assert_eq!(run(Some(ParseError::InvalidHeader)), None);
+        assert_eq!(run(None), None);
+    }
+}
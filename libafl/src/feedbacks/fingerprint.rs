@@ -0,0 +1,295 @@
+//! The [`CoverageFingerprintFeedback`] dedups inputs by a hash of their covered map entries,
+//! a coarser but cheaper alternative to tracking the novelty of each entry.
+
+use alloc::{
+    collections::VecDeque,
+    string::{String, ToString},
+};
+use core::{
+    fmt::Debug,
+    hash::{BuildHasher, Hasher},
+    marker::PhantomData,
+};
+
+use ahash::RandomState;
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const COVERAGE_FINGERPRINT_FEEDBACK_PREFIX: &str = "coveragefingerprintfeedback_metadata_";
+
+/// Default maximum number of fingerprints remembered by a [`CoverageFingerprintFeedback`]
+pub const DEFAULT_MAX_FINGERPRINTS: usize = 1 << 20;
+
+/// The state of [`CoverageFingerprintFeedback`]: the seen fingerprints, oldest first
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct CoverageFingerprintFeedbackMetadata {
+    /// The seen fingerprints
+    pub fingerprints: HashSet<u64>,
+    /// The seen fingerprints, in the order they were first seen
+    pub order: VecDeque<u64>,
+}
+
+crate::impl_serdeany!(CoverageFingerprintFeedbackMetadata);
+
+impl CoverageFingerprintFeedbackMetadata {
+    /// Create a new [`CoverageFingerprintFeedbackMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a fingerprint, returning `true` if it was not seen before.
+    /// If more than `max_fingerprints` are stored, the oldest ones are forgotten.
+    pub fn insert(&mut self, fingerprint: u64, max_fingerprints: usize) -> bool {
+        if !self.fingerprints.insert(fingerprint) {
+            return false;
+        }
+        self.order.push_back(fingerprint);
+        while self.order.len() > max_fingerprints {
+            if let Some(oldest) = self.order.pop_front() {
+                self.fingerprints.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.fingerprints.clear();
+        self.order.clear();
+        Ok(())
+    }
+}
+
+/// A testcase metadata holding the coverage fingerprint of the testcase
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CoverageFingerprintMetadata {
+    /// The hash over the covered map indices
+    pub fingerprint: u64,
+}
+
+crate::impl_serdeany!(CoverageFingerprintMetadata);
+
+/// Computes the fingerprint of a map: a stable hash over the indices of all entries that differ
+/// from the initial value. The values of the entries, e.g. hitcounts, are ignored.
+#[must_use]
+pub fn coverage_fingerprint<O>(observer: &O) -> u64
+where
+    O: MapObserver,
+{
+    let mut hasher = RandomState::with_seeds(0, 0, 0, 0).build_hasher();
+    let initial = observer.initial();
+    for i in 0..observer.usable_count() {
+        if *observer.get(i) != initial {
+            hasher.write_usize(i);
+        }
+    }
+    hasher.finish()
+}
+
+/// A [`CoverageFingerprintFeedback`] considers an input interesting if the set of covered entries
+/// of a [`MapObserver`] was not seen before, see [`coverage_fingerprint`].
+/// Only the hashes are stored, bounded by a maximum number of fingerprints, after which the
+/// oldest ones are forgotten.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CoverageFingerprintFeedback<O, S> {
+    name: String,
+    observer_name: String,
+    /// The maximum number of remembered fingerprints
+    max_fingerprints: usize,
+    /// The fingerprint of the last run
+    last_fingerprint: Option<u64>,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<O, S> CoverageFingerprintFeedback<O, S>
+where
+    O: MapObserver,
+{
+    /// Creates a new [`CoverageFingerprintFeedback`] for the given map observer
+    #[must_use]
+    pub fn new(observer: &O) -> Self {
+        Self::with_max_fingerprints(observer, DEFAULT_MAX_FINGERPRINTS)
+    }
+
+    /// Creates a new [`CoverageFingerprintFeedback`] remembering at most `max_fingerprints`
+    #[must_use]
+    pub fn with_max_fingerprints(observer: &O, max_fingerprints: usize) -> Self {
+        Self {
+            name: COVERAGE_FINGERPRINT_FEEDBACK_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            max_fingerprints,
+            last_fingerprint: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O, S> Feedback<S> for CoverageFingerprintFeedback<O, S>
+where
+    O: MapObserver,
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(CoverageFingerprintFeedbackMetadata::new(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "CoverageFingerprintFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let fingerprint = coverage_fingerprint(observer);
+        self.last_fingerprint = Some(fingerprint);
+
+        let seen = state
+            .named_metadata_map_mut()
+            .get_mut::<CoverageFingerprintFeedbackMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "CoverageFingerprintFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+        Ok(seen.insert(fingerprint, self.max_fingerprints))
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(fingerprint) = self.last_fingerprint.take() {
+            testcase.add_metadata(CoverageFingerprintMetadata { fingerprint });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_fingerprint = None;
+        Ok(())
+    }
+}
+
+impl<O, S> Named for CoverageFingerprintFeedback<O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, S> HasObserverName for CoverageFingerprintFeedback<O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            CoverageFingerprintFeedback, CoverageFingerprintFeedbackMetadata,
+            CoverageFingerprintMetadata, Feedback,
+        },
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_coverage_fingerprint_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback: CoverageFingerprintFeedback<_, NopState<BytesInput>> =
+            CoverageFingerprintFeedback::new(&StdMapObserver::owned("map", vec![0_u8; 16]));
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |map: [u8; 16]| {
+            let observers = tuple_list![StdMapObserver::owned("map", map.to_vec())];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            (
+                interesting,
+                testcase
+                    .metadata::<CoverageFingerprintMetadata>()
+                    .unwrap()
+                    .fingerprint,
+            )
+        };
+
+        let mut first = [0; 16];
+        first[1] = 1;
+        first[7] = 3;
+        // same covered set, different hitcounts
+        let mut second = [0; 16];
+        second[1] = 128;
+        second[7] = 1;
+        let mut third = [0; 16];
+        third[1] = 1;
+
+        let (interesting, fingerprint) = run(first);
+        assert!(interesting);
+        assert_eq!(run(second), (false, fingerprint));
+        let (interesting, other) = run(third);
+        assert!(interesting);
+        assert_ne!(other, fingerprint);
+    }
+
+    #[test]
+    fn test_coverage_fingerprint_bounded() {
+        let mut seen = CoverageFingerprintFeedbackMetadata::new();
+        for fingerprint in 0..4 {
+            assert!(seen.insert(fingerprint, 2));
+        }
+        assert_eq!(seen.fingerprints.len(), 2);
+        assert!(!seen.insert(3, 2));
+        // the oldest fingerprints were forgotten
+        assert!(seen.insert(0, 2));
+    }
+}
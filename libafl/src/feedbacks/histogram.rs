@@ -0,0 +1,282 @@
+//! The [`HistogramFeedback`] accumulates the hit counts of the map entries covered by the corpus,
+//! for end-of-campaign reporting.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const HISTOGRAM_FEEDBACK_PREFIX: &str = "histogramfeedback_metadata_";
+
+/// The state of [`HistogramFeedback`]: the hit counts of each map entry, summed over the runs of
+/// the testcases added to the corpus
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CoverageHistogramMetadata {
+    /// The summed hit counts, by map index
+    pub counts: Vec<u64>,
+    /// The number of merged runs
+    pub runs: usize,
+}
+
+crate::impl_serdeany!(CoverageHistogramMetadata);
+
+impl CoverageHistogramMetadata {
+    /// Create a new [`CoverageHistogramMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the hit counts of a run, by map index
+    pub fn merge<I>(&mut self, counts: I)
+    where
+        I: IntoIterator<Item = u64>,
+    {
+        for (i, count) in counts.into_iter().enumerate() {
+            if i >= self.counts.len() {
+                self.counts.resize(i + 1, 0);
+            }
+            self.counts[i] = self.counts[i].saturating_add(count);
+        }
+        self.runs += 1;
+    }
+
+    /// The indices of the covered map entries, with their summed hit counts
+    pub fn covered(&self) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.counts
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.counts.clear();
+        self.runs = 0;
+        Ok(())
+    }
+}
+
+/// A [`HistogramFeedback`] wraps another [`Feedback`], usually a map feedback, and merges the hit
+/// counts of a [`MapObserver`] into a [`CoverageHistogramMetadata`] in the state whenever a
+/// testcase is added to the corpus. See [`HistogramFeedback::snapshot`].
+///
+/// The result of the wrapped feedback is forwarded unchanged.
+pub struct HistogramFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    observer_name: String,
+    name: String,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<A, O, S> HistogramFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor + HasNamedMetadata,
+{
+    /// Creates a new [`HistogramFeedback`], wrapping the given feedback, and accumulating the hit
+    /// counts of the given map observer
+    pub fn new(inner: A, observer: &O) -> Self {
+        Self {
+            inner,
+            observer_name: observer.name().to_string(),
+            name: HISTOGRAM_FEEDBACK_PREFIX.to_string() + observer.name(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// A copy of the histogram accumulated so far
+    pub fn snapshot(&self, state: &S) -> Result<CoverageHistogramMetadata, Error> {
+        state
+            .named_metadata_map()
+            .get::<CoverageHistogramMetadata>(&self.name)
+            .cloned()
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "HistogramFeedback: metadata {} not found",
+                    self.name
+                ))
+            })
+    }
+}
+
+impl<A, O, S> Debug for HistogramFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HistogramFeedback")
+            .field("name", &self.name)
+            .field("observer_name", &self.observer_name)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, O, S> Named for HistogramFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, O, S> HasObserverName for HistogramFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+impl<A, O, S> Feedback<S> for HistogramFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    O: MapObserver,
+    O::Entry: Into<u64>,
+    S: UsesInput + HasClientPerfMonitor + HasNamedMetadata,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(CoverageHistogramMetadata::new(), &self.name);
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.inner
+            .is_interesting(state, manager, input, observers, exit_kind)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "HistogramFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let histogram = state
+            .named_metadata_map_mut()
+            .get_mut::<CoverageHistogramMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "HistogramFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+        histogram.merge((0..observer.usable_count()).map(|i| (*observer.get(i)).into()));
+
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, HistogramFeedback, MaxMapFeedback},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_histogram_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let initial = StdMapObserver::owned("map", vec![0_u8; 4]);
+        let mut feedback = HistogramFeedback::new(
+            MaxMapFeedback::<_, NopState<BytesInput>, u8>::new(&initial),
+            &initial,
+        );
+        feedback.init_state(&mut state).unwrap();
+
+        for map in [[1, 0, 2, 0], [1, 0, 2, 0], [3, 0, 5, 1], [0, 0, 1, 0]] {
+            let observers = tuple_list![StdMapObserver::owned("map", map.to_vec())];
+            if feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+            {
+                let mut testcase = Testcase::new(input.clone());
+                feedback
+                    .append_metadata(&mut state, &observers, &mut testcase)
+                    .unwrap();
+            } else {
+                feedback.discard_metadata(&mut state, &input).unwrap();
+            }
+        }
+
+        // only the first and the third run were novel, and kept
+        let histogram = feedback.snapshot(&state).unwrap();
+        assert_eq!(histogram.runs, 2);
+        assert_eq!(histogram.counts, vec![4, 0, 7, 1]);
+        assert_eq!(
+            histogram.covered().collect::<Vec<_>>(),
+            vec![(0, 4), (2, 7), (3, 1)]
+        );
+    }
+}
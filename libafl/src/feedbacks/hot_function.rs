@@ -0,0 +1,230 @@
+//! The [`HotFunctionFeedback`] flags inputs spending most of their execution time in a given
+//! function, e.g. to find slow paths that a global timeout misses.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Debug, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// The prefix of the feedback names
+pub const HOT_FUNCTION_FEEDBACK_PREFIX: &str = "hotfunctionfeedback_";
+
+/// A testcase metadata holding the share of the execution time spent in the hot function, as
+/// computed by a [`HotFunctionFeedback`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HotFunctionMetadata {
+    /// The index of the function in the map
+    pub function: usize,
+    /// The time spent in the function
+    pub time: u64,
+    /// The time spent in the function, divided by the time spent in all functions
+    pub share: f64,
+}
+
+crate::impl_serdeany!(HotFunctionMetadata);
+
+/// A [`HotFunctionFeedback`] reads a [`MapObserver`] holding the time spent in each instrumented
+/// function, in any unit, by function index. An input is interesting if the share of the total
+/// time spent in the configured function exceeds the threshold. The share is added to the testcase
+/// as [`HotFunctionMetadata`]. Runs without any recorded time are never interesting.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HotFunctionFeedback<O, S> {
+    name: String,
+    observer_name: String,
+    /// The index of the watched function in the map
+    function: usize,
+    /// The share of the total time above which an input is interesting
+    threshold: f64,
+    /// The metadata of the last run, if interesting
+    last_share: Option<HotFunctionMetadata>,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<O, S> HotFunctionFeedback<O, S>
+where
+    O: MapObserver,
+{
+    /// Creates a new [`HotFunctionFeedback`] for the given map observer of per-function times,
+    /// flagging inputs spending more than `threshold` (between `0.0` and `1.0`) of the total time
+    /// in the function at index `function`
+    #[must_use]
+    pub fn new(observer: &O, function: usize, threshold: f64) -> Self {
+        Self {
+            name: HOT_FUNCTION_FEEDBACK_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            function,
+            threshold,
+            last_share: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The index of the watched function in the map
+    #[must_use]
+    pub fn function(&self) -> usize {
+        self.function
+    }
+
+    /// The share of the total time above which an input is interesting
+    #[must_use]
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+impl<O, S> Feedback<S> for HotFunctionFeedback<O, S>
+where
+    O: MapObserver,
+    O::Entry: Into<u64>,
+    S: UsesInput + Debug + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    #[allow(clippy::cast_precision_loss)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_share = None;
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "HotFunctionFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        if self.function >= observer.usable_count() {
+            return Err(Error::illegal_argument(format!(
+                "HotFunctionFeedback: function {} out of the bounds of observer {}",
+                self.function, self.observer_name
+            )));
+        }
+
+        let total = (0..observer.usable_count())
+            .map(|i| (*observer.get(i)).into())
+            .fold(0_u64, u64::saturating_add);
+        if total == 0 {
+            return Ok(false);
+        }
+        let time: u64 = (*observer.get(self.function)).into();
+        let share = time as f64 / total as f64;
+        if share <= self.threshold {
+            return Ok(false);
+        }
+        self.last_share = Some(HotFunctionMetadata {
+            function: self.function,
+            time,
+            share,
+        });
+        Ok(true)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(meta) = self.last_share.take() {
+            testcase.add_metadata(meta);
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_share = None;
+        Ok(())
+    }
+}
+
+impl<O, S> Named for HotFunctionFeedback<O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, S> HasObserverName for HotFunctionFeedback<O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, HotFunctionFeedback, HotFunctionMetadata},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_hot_function_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+        let mut feedback: HotFunctionFeedback<_, NopState<BytesInput>> =
+            HotFunctionFeedback::new(&StdMapObserver::owned("times", vec![0_u64; 4]), 2, 0.5);
+
+        // returns the metadata of the run, if interesting
+        let mut run = |times: [u64; 4]| {
+            let observers = tuple_list![StdMapObserver::owned("times", times.to_vec())];
+            if !feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+            {
+                return None;
+            }
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            Some(*testcase.metadata::<HotFunctionMetadata>().unwrap())
+        };
+
+        // nothing recorded
+        assert!(run([0, 0, 0, 0]).is_none());
+        // below and at the threshold
+        assert!(run([30, 40, 20, 10]).is_none());
+        assert!(run([25, 0, 50, 25]).is_none());
+        // above the threshold
+        let meta = run([10, 10, 60, 20]).unwrap();
+        assert_eq!(meta.function, 2);
+        assert_eq!(meta.time, 60);
+        assert!((meta.share - 0.6).abs() < f64::EPSILON);
+        let meta = run([0, 0, 7, 0]).unwrap();
+        assert!((meta.share - 1.0).abs() < f64::EPSILON);
+        // the total saturates instead of overflowing
+        let meta = run([u64::MAX, 0, u64::MAX, 0]).unwrap();
+        assert!((meta.share - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -21,7 +21,7 @@ use crate::{
     events::{Event, EventFirer},
     executors::ExitKind,
     feedbacks::{Feedback, HasObserverName},
-    inputs::UsesInput,
+    inputs::{Input, UsesInput},
     monitors::UserStats,
     observers::{MapObserver, Observer, ObserversTuple, UsesObserver},
     state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
@@ -297,6 +297,54 @@ impl MapNoveltiesMetadata {
     }
 }
 
+/// A testcase metadata holding the names of the observers whose [`MapFeedback`]s considered the
+/// testcase interesting
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InterestingObserversMetadata {
+    /// The observer names, in the order they were recorded and without duplicates
+    pub names: Vec<String>,
+}
+
+crate::impl_serdeany!(InterestingObserversMetadata);
+
+impl InterestingObserversMetadata {
+    /// Creates a new [`struct@InterestingObserversMetadata`]
+    #[must_use]
+    pub fn new(names: Vec<String>) -> Self {
+        Self { names }
+    }
+
+    /// Records the name of an observer, if not recorded yet
+    pub fn add(&mut self, name: &str) {
+        if !self.names.iter().any(|n| n == name) {
+            self.names.push(name.to_string());
+        }
+    }
+
+    /// Records all names of the `other` metadata
+    pub fn merge(&mut self, other: &Self) {
+        for name in &other.names {
+            self.add(name);
+        }
+    }
+
+    /// Records the name of an observer into the metadata of the `testcase`, adding the metadata
+    /// if needed
+    pub fn record<I>(testcase: &mut Testcase<I>, name: &str)
+    where
+        I: Input,
+    {
+        if let Some(meta) = testcase
+            .metadata_map_mut()
+            .get_mut::<InterestingObserversMetadata>()
+        {
+            meta.add(name);
+        } else {
+            testcase.add_metadata(Self::new(vec![name.to_string()]));
+        }
+    }
+}
+
 /// The state of [`MapFeedback`]
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
 #[serde(bound = "T: DeserializeOwned")]
@@ -366,6 +414,12 @@ pub struct MapFeedback<N, O, R, S, T> {
     observer_name: String,
     /// Name of the feedback as shown in the `UserStats`
     stats_name: String,
+    /// If set, only the map entries allowed by this mask are considered
+    allowlist: Option<Vec<bool>>,
+    /// The minimum number of novel entries for the map to be considered `interesting`
+    min_new_edges: usize,
+    /// If the last observation was `interesting`
+    last_interesting: bool,
     /// Phantom Data of Reducer
     phantom: PhantomData<(N, O, R, S, T)>,
 }
@@ -438,6 +492,9 @@ where
             let meta = MapNoveltiesMetadata::new(novelties);
             testcase.add_metadata(meta);
         }
+        if core::mem::take(&mut self.last_interesting) {
+            InterestingObserversMetadata::record(testcase, &self.observer_name);
+        }
         let observer = observers.match_name::<O>(&self.observer_name).unwrap();
         let initial = observer.initial();
         let map_state = state
@@ -446,6 +503,7 @@ where
             .unwrap();
 
         let history_map = map_state.history_map.as_mut_slice();
+        let allowlist = self.allowlist.as_deref();
         if self.indexes {
             let mut indices = Vec::new();
 
@@ -453,7 +511,7 @@ where
                 .as_iter()
                 .copied()
                 .enumerate()
-                .filter(|(_, value)| *value != initial)
+                .filter(|(i, value)| *value != initial && is_allowed(allowlist, *i))
             {
                 history_map[i] = R::reduce(history_map[i], value);
                 indices.push(i);
@@ -465,13 +523,18 @@ where
                 .as_iter()
                 .copied()
                 .enumerate()
-                .filter(|(_, value)| *value != initial)
+                .filter(|(i, value)| *value != initial && is_allowed(allowlist, *i))
             {
                 history_map[i] = R::reduce(history_map[i], value);
             }
         }
         Ok(())
     }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_interesting = false;
+        Ok(())
+    }
 }
 
 /// Specialize for the common coverage map size, maximization of u8s
@@ -488,9 +551,9 @@ where
         &mut self,
         state: &mut S,
         manager: &mut EM,
-        _input: &S::Input,
+        input: &S::Input,
         observers: &OT,
-        _exit_kind: &ExitKind,
+        exit_kind: &ExitKind,
     ) -> Result<bool, Error>
     where
         EM: EventFirer<State = S>,
@@ -499,6 +562,11 @@ where
         // 128 bits vectors
         type VectorType = core::simd::u8x16;
 
+        if self.allowlist.is_some() || self.min_new_edges > 1 {
+            // The vectorized implementation doesn't support the allowlist and the novelty threshold
+            return self.is_interesting_default(state, manager, input, observers, exit_kind);
+        }
+
         let mut interesting = false;
         // TODO Replace with match_name_type when stable
         let observer = observers.match_name::<O>(&self.observer_name).unwrap();
@@ -611,6 +679,7 @@ where
             )?;
         }
 
+        self.last_interesting = interesting;
         Ok(interesting)
     }
 }
@@ -641,6 +710,14 @@ fn create_stats_name(name: &str) -> String {
     name.to_lowercase()
 }
 
+/// Returns `true` if the map entry at `idx` is allowed by the (optional) allowlist mask
+#[inline]
+fn is_allowed(allowlist: Option<&[bool]>, idx: usize) -> bool {
+    allowlist.map_or(true, |allowlist| {
+        allowlist.get(idx).copied().unwrap_or(false)
+    })
+}
+
 impl<N, O, R, S, T> MapFeedback<N, O, R, S, T>
 where
     T: PartialEq + Default + Copy + 'static + Serialize + DeserializeOwned + Debug,
@@ -660,6 +737,9 @@ where
             observer_name: map_observer.name().to_string(),
             stats_name: create_stats_name(map_observer.name()),
             always_track: false,
+            allowlist: None,
+            min_new_edges: 1,
+            last_interesting: false,
             phantom: PhantomData,
         }
     }
@@ -674,6 +754,9 @@ where
             observer_name: map_observer.name().to_string(),
             stats_name: create_stats_name(map_observer.name()),
             always_track: false,
+            allowlist: None,
+            min_new_edges: 1,
+            last_interesting: false,
             phantom: PhantomData,
         }
     }
@@ -689,6 +772,9 @@ where
             stats_name: create_stats_name(name),
             phantom: PhantomData,
             always_track: false,
+            allowlist: None,
+            min_new_edges: 1,
+            last_interesting: false,
         }
     }
 
@@ -699,6 +785,35 @@ where
         self.always_track = always_track;
     }
 
+    /// Only consider the given map indexes (for example, a `Range` of edges belonging to a single component)
+    /// for novelty, ignoring entries outside of the allowlist.
+    pub fn set_allowlist<I>(&mut self, allowed: I)
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let mut allowlist = Vec::new();
+        for idx in allowed {
+            if allowlist.len() <= idx {
+                allowlist.resize(idx + 1, false);
+            }
+            allowlist[idx] = true;
+        }
+        self.allowlist = Some(allowlist);
+    }
+
+    /// Remove the allowlist, considering all map entries again
+    pub fn clear_allowlist(&mut self) {
+        self.allowlist = None;
+    }
+
+    /// Only consider the map `interesting` if at least `min_new_edges` entries are novel,
+    /// instead of a single one, to avoid many tiny incremental saves in noisy targets.
+    /// The history map is only updated for `interesting` inputs, so novel entries below the
+    /// threshold are not lost, and still count for later inputs.
+    pub fn set_min_new_edges(&mut self, min_new_edges: usize) {
+        self.min_new_edges = min_new_edges.max(1);
+    }
+
     /// Creating a new `MapFeedback` with a specific name. This is usefully whenever the same
     /// feedback is needed twice, but with a different history. Using `new()` always results in the
     /// same name and therefore also the same history.
@@ -711,6 +826,9 @@ where
             observer_name: map_observer.name().to_string(),
             stats_name: create_stats_name(name),
             always_track: false,
+            allowlist: None,
+            min_new_edges: 1,
+            last_interesting: false,
             phantom: PhantomData,
         }
     }
@@ -730,6 +848,9 @@ where
             stats_name: create_stats_name(name),
             name: name.to_string(),
             always_track: false,
+            allowlist: None,
+            min_new_edges: 1,
+            last_interesting: false,
             phantom: PhantomData,
         }
     }
@@ -765,6 +886,7 @@ where
         let history_map = map_state.history_map.as_slice();
 
         let initial = observer.initial();
+        let allowlist = self.allowlist.as_deref();
 
         if let Some(novelties) = self.novelties.as_mut() {
             novelties.clear();
@@ -772,27 +894,31 @@ where
                 .as_iter()
                 .copied()
                 .enumerate()
-                .filter(|(_, item)| *item != initial)
+                .filter(|(i, item)| *item != initial && is_allowed(allowlist, *i))
             {
                 let existing = unsafe { *history_map.get_unchecked(i) };
                 let reduced = R::reduce(existing, item);
                 if N::is_novel(existing, reduced) {
-                    interesting = true;
                     novelties.push(i);
                 }
             }
+            interesting = novelties.len() >= self.min_new_edges;
         } else {
+            let mut novel_count = 0;
             for (i, item) in observer
                 .as_iter()
                 .copied()
                 .enumerate()
-                .filter(|(_, item)| *item != initial)
+                .filter(|(i, item)| *item != initial && is_allowed(allowlist, *i))
             {
                 let existing = unsafe { *history_map.get_unchecked(i) };
                 let reduced = R::reduce(existing, item);
                 if N::is_novel(existing, reduced) {
-                    interesting = true;
-                    break;
+                    novel_count += 1;
+                    if novel_count >= self.min_new_edges {
+                        interesting = true;
+                        break;
+                    }
                 }
             }
         }
@@ -819,6 +945,7 @@ where
             )?;
         }
 
+        self.last_interesting = interesting;
         Ok(interesting)
     }
 }
@@ -932,7 +1059,20 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::feedbacks::{AllIsNovel, IsNovel, NextPow2IsNovel};
+    use alloc::vec::Vec;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            AllIsNovel, EagerOrFeedback, Feedback, InterestingObserversMetadata, IsNovel,
+            MaxMapFeedback, NextPow2IsNovel,
+        },
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
 
     #[test]
     fn test_map_is_novel() {
@@ -953,6 +1093,132 @@ mod tests {
         assert!(NextPow2IsNovel::is_novel(254_u8, 255));
         assert!(!NextPow2IsNovel::is_novel(255_u8, 255));
     }
+
+    #[test]
+    fn test_map_feedback_allowlist() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback: MaxMapFeedback<_, NopState<BytesInput>, u8> =
+            MaxMapFeedback::new(&StdMapObserver::owned("map", vec![0_u8; 64]));
+        feedback.set_allowlist(16..32);
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |feedback: &mut MaxMapFeedback<_, _, u8>, covered: &[usize]| {
+            let mut map = vec![0_u8; 64];
+            for idx in covered {
+                map[*idx] = 1;
+            }
+            let observers = tuple_list![StdMapObserver::owned("map", map)];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            if interesting {
+                let mut testcase = crate::corpus::Testcase::new(input.clone());
+                feedback
+                    .append_metadata(&mut state, &observers, &mut testcase)
+                    .unwrap();
+            }
+            interesting
+        };
+
+        // coverage outside of the allowlist is never interesting
+        assert!(!run(&mut feedback, &[0, 1, 15, 32, 63]));
+        assert!(!run(&mut feedback, &[2, 40]));
+        // coverage inside of the allowlist is
+        assert!(run(&mut feedback, &[16]));
+        assert!(!run(&mut feedback, &[16, 0, 63]));
+        assert!(run(&mut feedback, &[31, 33]));
+
+        feedback.clear_allowlist();
+        assert!(run(&mut feedback, &[0]));
+    }
+
+    #[test]
+    fn test_map_feedback_min_new_edges() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback: MaxMapFeedback<_, NopState<BytesInput>, u8> =
+            MaxMapFeedback::new(&StdMapObserver::owned("map", vec![0_u8; 64]));
+        feedback.set_min_new_edges(3);
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |feedback: &mut MaxMapFeedback<_, _, u8>, covered: &[usize]| {
+            let mut map = vec![0_u8; 64];
+            for idx in covered {
+                map[*idx] = 1;
+            }
+            let observers = tuple_list![StdMapObserver::owned("map", map)];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            if interesting {
+                let mut testcase = crate::corpus::Testcase::new(input.clone());
+                feedback
+                    .append_metadata(&mut state, &observers, &mut testcase)
+                    .unwrap();
+            }
+            interesting
+        };
+
+        // not enough new edges
+        assert!(!run(&mut feedback, &[0]));
+        assert!(!run(&mut feedback, &[0, 1]));
+        // enough new edges, committed to the history
+        assert!(run(&mut feedback, &[0, 1, 2]));
+        assert!(!run(&mut feedback, &[0, 1, 2, 3, 4]));
+        assert!(run(&mut feedback, &[3, 4, 5, 6]));
+
+        // tracking novelties uses the same threshold (sharing the history of the feedback above)
+        let mut tracking: MaxMapFeedback<_, NopState<BytesInput>, u8> =
+            MaxMapFeedback::tracking(&StdMapObserver::owned("map", vec![0_u8; 64]), false, true);
+        tracking.set_min_new_edges(2);
+        assert!(!run(&mut tracking, &[10]));
+        assert!(run(&mut tracking, &[10, 11]));
+    }
+
+    #[test]
+    fn test_interesting_observers_metadata() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback: EagerOrFeedback<_, _, NopState<BytesInput>> = EagerOrFeedback::new(
+            MaxMapFeedback::<_, _, u8>::new(&StdMapObserver::owned("map1", vec![0_u8; 8])),
+            MaxMapFeedback::<_, _, u8>::new(&StdMapObserver::owned("map2", vec![0_u8; 8])),
+        );
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |map1: Vec<u8>, map2: Vec<u8>| {
+            let observers = tuple_list![
+                StdMapObserver::owned("map1", map1),
+                StdMapObserver::owned("map2", map2)
+            ];
+            assert!(feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap());
+            let mut testcase = crate::corpus::Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            testcase
+                .metadata::<InterestingObserversMetadata>()
+                .unwrap()
+                .names
+                .clone()
+        };
+
+        // only the second map is novel
+        assert_eq!(run(vec![0; 8], vec![0, 1, 0, 0, 0, 0, 0, 0]), ["map2"]);
+        // both maps are novel
+        assert_eq!(
+            run(vec![1, 0, 0, 0, 0, 0, 0, 0], vec![0, 0, 1, 0, 0, 0, 0, 0]),
+            ["map1", "map2"]
+        );
+    }
 }
 
 /// `MapFeedback` Python bindings
@@ -0,0 +1,215 @@
+//! The [`MemoizeFeedback`] skips the evaluation of the wrapped feedback if the input did not change
+//! since the last run, for example while an input is re-run during calibration.
+
+use alloc::string::String;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::{input_hash, Testcase},
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// A [`MemoizeFeedback`] wraps another [`Feedback`], caching the hash of the last input the wrapped
+/// feedback found not interesting. If the same input is evaluated again, it is not interesting,
+/// without calling the wrapped feedback. Interesting results are never cached, so an input is
+/// never saved without the wrapped feedback evaluating it and adding its metadata.
+pub struct MemoizeFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    /// If the cache is used at all
+    enabled: bool,
+    /// The hash of the last input the wrapped feedback found not interesting
+    cache: Option<u64>,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S> MemoizeFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`MemoizeFeedback`], wrapping the given feedback
+    pub fn new(inner: A) -> Self {
+        let name = format!("Memoize({})", inner.name());
+        Self {
+            inner,
+            enabled: true,
+            cache: None,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Enables or disables the cache. Disabling it also flushes it.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.flush();
+        }
+    }
+
+    /// Flushes the cache, so that the next input is evaluated by the wrapped feedback
+    pub fn flush(&mut self) {
+        self.cache = None;
+    }
+}
+
+impl<A, S> Debug for MemoizeFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoizeFeedback")
+            .field("name", &self.name)
+            .field("enabled", &self.enabled)
+            .field("cache", &self.cache)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, S> Named for MemoizeFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S> Feedback<S> for MemoizeFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        if !self.enabled {
+            return self
+                .inner
+                .is_interesting(state, manager, input, observers, exit_kind);
+        }
+
+        let hash = input_hash(input)?;
+        if self.cache == Some(hash) {
+            return Ok(false);
+        }
+
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        self.cache = if interesting { None } else { Some(hash) };
+        Ok(interesting)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use crate::{
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, FnFeedback, MemoizeFeedback},
+        inputs::{BytesInput, HasBytesVec},
+        state::NopState,
+    };
+
+    #[test]
+    fn test_memoize_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+
+        let calls = Cell::new(0);
+        let mut feedback = MemoizeFeedback::new(FnFeedback::new(
+            "counting",
+            |_state: &mut NopState<BytesInput>, input: &BytesInput, _exit_kind: &ExitKind| {
+                calls.set(calls.get() + 1);
+                Ok(input.bytes().len() > 2)
+            },
+        ));
+
+        let mut run = |feedback: &mut MemoizeFeedback<_, _>, input: &BytesInput| {
+            feedback
+                .is_interesting(&mut state, &mut mgr, input, &(), &ExitKind::Ok)
+                .unwrap()
+        };
+
+        let input = BytesInput::new(vec![1]);
+        assert!(!run(&mut feedback, &input));
+        assert!(!run(&mut feedback, &input));
+        assert_eq!(calls.get(), 1);
+
+        // interesting inputs are always evaluated
+        let interesting = BytesInput::new(vec![1, 2, 3]);
+        assert!(run(&mut feedback, &interesting));
+        assert!(run(&mut feedback, &interesting));
+        assert_eq!(calls.get(), 3);
+
+        // a different input is evaluated
+        let other = BytesInput::new(vec![4]);
+        assert!(!run(&mut feedback, &other));
+        assert!(!run(&mut feedback, &other));
+        assert_eq!(calls.get(), 4);
+
+        // after a flush, the same input is evaluated again
+        feedback.flush();
+        assert!(!run(&mut feedback, &other));
+        assert_eq!(calls.get(), 5);
+
+        // without the cache, every run is evaluated
+        feedback.set_enabled(false);
+        assert!(!run(&mut feedback, &other));
+        assert!(!run(&mut feedback, &other));
+        assert_eq!(calls.get(), 7);
+    }
+}
@@ -0,0 +1,244 @@
+//! The [`CoverageMilestoneFeedback`] reports an input as interesting whenever the fraction of
+//! covered entries of a map crosses the next configured milestone.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasNamedMetadata},
+    Error,
+};
+
+/// The default milestones used by [`CoverageMilestoneFeedback`]
+pub const DEFAULT_COVERAGE_MILESTONES: [f64; 6] = [0.1, 0.25, 0.5, 0.75, 0.9, 1.0];
+
+/// The state of [`CoverageMilestoneFeedback`]
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct CoverageMilestoneFeedbackMetadata {
+    /// All entries covered so far
+    pub covered: Vec<bool>,
+    /// The number of covered entries
+    pub covered_count: usize,
+    /// The index of the next milestone to reach
+    pub next_milestone: usize,
+}
+
+crate::impl_serdeany!(CoverageMilestoneFeedbackMetadata);
+
+/// A testcase metadata holding the coverage milestone reached by this testcase
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CoverageMilestoneMetadata {
+    /// The milestone reached, as a fraction of the total entries
+    pub milestone: f64,
+    /// The covered fraction of the total entries after this testcase
+    pub ratio: f64,
+}
+
+crate::impl_serdeany!(CoverageMilestoneMetadata);
+
+/// A [`CoverageMilestoneFeedback`] tracks the overall covered entries of a [`MapObserver`] and reports
+/// an input as interesting when the covered fraction of the `total` entries crosses the next milestone.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CoverageMilestoneFeedback<O, S> {
+    name: String,
+    observer_name: String,
+    /// The total number of entries that can be covered
+    total: usize,
+    /// The milestones, as sorted fractions of `total`
+    milestones: Vec<f64>,
+    /// The milestone reached in the last run, if any, and the covered fraction
+    last_reached: Option<(f64, f64)>,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<O, S> CoverageMilestoneFeedback<O, S>
+where
+    O: MapObserver,
+{
+    /// Creates a new [`CoverageMilestoneFeedback`] for a target with `total` coverable entries,
+    /// using [`DEFAULT_COVERAGE_MILESTONES`].
+    #[must_use]
+    pub fn new(observer: &O, total: usize) -> Self {
+        Self::with_milestones(observer, total, &DEFAULT_COVERAGE_MILESTONES)
+    }
+
+    /// Creates a new [`CoverageMilestoneFeedback`] for a target with `total` coverable entries,
+    /// firing at each of the given `milestones` (fractions in `0.0..=1.0`).
+    #[must_use]
+    pub fn with_milestones(observer: &O, total: usize, milestones: &[f64]) -> Self {
+        let mut milestones = milestones.to_vec();
+        milestones.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Self {
+            name: "coveragemilestonefeedback_".to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            total,
+            milestones,
+            last_reached: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The milestones of this feedback
+    #[must_use]
+    pub fn milestones(&self) -> &[f64] {
+        &self.milestones
+    }
+}
+
+impl<O, S> Feedback<S> for CoverageMilestoneFeedback<O, S>
+where
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor + HasNamedMetadata + Debug,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(CoverageMilestoneFeedbackMetadata::default(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    #[allow(clippy::cast_precision_loss)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_reached = None;
+        let observer = observers.match_name::<O>(&self.observer_name).unwrap();
+        let meta = state
+            .named_metadata_map_mut()
+            .get_mut::<CoverageMilestoneFeedbackMetadata>(&self.name)
+            .unwrap();
+
+        let len = observer.usable_count();
+        if meta.covered.len() < len {
+            meta.covered.resize(len, false);
+        }
+        let initial = observer.initial();
+        for i in 0..len {
+            if !meta.covered[i] && *observer.get(i) != initial {
+                meta.covered[i] = true;
+                meta.covered_count += 1;
+            }
+        }
+
+        let ratio = if self.total == 0 {
+            0.0
+        } else {
+            meta.covered_count as f64 / self.total as f64
+        };
+        let mut reached = None;
+        while meta.next_milestone < self.milestones.len()
+            && ratio >= self.milestones[meta.next_milestone]
+        {
+            reached = Some(self.milestones[meta.next_milestone]);
+            meta.next_milestone += 1;
+        }
+
+        self.last_reached = reached.map(|milestone| (milestone, ratio));
+        Ok(reached.is_some())
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some((milestone, ratio)) = self.last_reached.take() {
+            testcase.add_metadata(CoverageMilestoneMetadata { milestone, ratio });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_reached = None;
+        Ok(())
+    }
+}
+
+impl<O, S> Named for CoverageMilestoneFeedback<O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, S> HasObserverName for CoverageMilestoneFeedback<O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{CoverageMilestoneFeedback, Feedback},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_coverage_milestones() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback = CoverageMilestoneFeedback::with_milestones(
+            &StdMapObserver::owned("map", vec![0_u8; 20]),
+            20,
+            &[0.25, 0.5, 1.0],
+        );
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |covered: usize| {
+            let mut map = vec![0_u8; 20];
+            map[..covered].fill(1);
+            let observers = tuple_list![StdMapObserver::owned("map", map)];
+            feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+        };
+
+        // 10%, below the first milestone
+        assert!(!run(2));
+        // 25%, first milestone
+        assert!(run(5));
+        assert!(!run(5));
+        // 45%, no new milestone
+        assert!(!run(9));
+        // 50%, second milestone
+        assert!(run(10));
+        // 95%
+        assert!(!run(19));
+        // 100%, last milestone
+        assert!(run(20));
+        assert!(!run(20));
+    }
+}
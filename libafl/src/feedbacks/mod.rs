@@ -9,6 +9,178 @@ pub use map::*;
 
 pub mod differential;
 pub use differential::DiffFeedback;
+
+pub mod notify;
+pub use notify::NotifyFeedback;
+
+pub mod new_value;
+pub use new_value::{NewValueFeedback, NewValueMetadata};
+
+pub mod closure;
+pub use closure::{FnFeedback, ObserverFnFeedback};
+
+pub mod milestone;
+pub use milestone::{
+    CoverageMilestoneFeedback, CoverageMilestoneFeedbackMetadata, CoverageMilestoneMetadata,
+};
+
+pub mod tagged;
+pub use tagged::{FeedbackOriginMetadata, TaggedOrFeedback};
+
+pub mod module_combo;
+pub use module_combo::{ModuleComboFeedback, ModuleComboFeedbackMetadata};
+
+pub mod unique_crash;
+pub use unique_crash::{UniqueCrashFeedback, UniqueCrashFeedbackMetadata};
+
+#[cfg(feature = "async_feedback")]
+pub mod async_feedback;
+#[cfg(feature = "async_feedback")]
+pub use async_feedback::{AsyncFeedback, BlockOnFeedback};
+
+#[cfg(feature = "std")]
+pub mod assertion;
+#[cfg(feature = "std")]
+pub use assertion::{AssertionFeedback, AssertionMetadata, DEFAULT_ASSERTION_PATTERNS};
+
+pub mod snapshot;
+pub use snapshot::{MapSnapshotMetadata, SnapshotMapFeedback};
+
+pub mod multi_map;
+pub use multi_map::{
+    AflMultiMapFeedback, MaxMultiMapFeedback, MultiMapFeedback, MultiMapNoveltyMetadata,
+};
+
+#[cfg(feature = "std")]
+pub mod ndjson;
+#[cfg(feature = "std")]
+pub use ndjson::{NdjsonFeedback, NdjsonFlushPolicy};
+
+#[cfg(feature = "std")]
+pub mod bloom_dedup;
+#[cfg(feature = "std")]
+pub use bloom_dedup::{BloomDedupFeedback, BloomFilterMetadata};
+
+pub mod error_variant;
+pub use error_variant::{ErrorVariantFeedback, ErrorVariantFeedbackMetadata, ErrorVariantMetadata};
+
+pub mod memoize;
+pub use memoize::MemoizeFeedback;
+
+pub mod range;
+pub use range::RangeFeedback;
+
+pub mod byte_influence;
+pub use byte_influence::{ByteInfluenceFeedback, ByteInfluenceMetadata};
+
+pub mod stable;
+pub use stable::StableCoverageFeedback;
+
+pub mod byte_budget;
+pub use byte_budget::ByteBudgetFeedback;
+
+pub mod builder;
+pub use builder::FeedbackBuilder;
+
+pub mod coverage_diff;
+pub use coverage_diff::{CoverageDiffFeedback, CoverageDiffMetadata};
+
+pub mod fingerprint;
+pub use fingerprint::{
+    CoverageFingerprintFeedback, CoverageFingerprintFeedbackMetadata, CoverageFingerprintMetadata,
+};
+
+pub mod region;
+pub use region::{RegionCoverageFeedback, RegionCoverageFeedbackMetadata, RegionCoverageMetadata};
+
+pub mod plateau;
+pub use plateau::{PlateauFeedback, PLATEAU_DETECTED_TAG};
+
+pub mod burst;
+pub use burst::{BurstCooldown, BurstSampleFeedback};
+
+pub mod rare_edge;
+pub use rare_edge::{RareEdgeFeedback, RareEdgeFeedbackMetadata};
+
+pub mod cmp_value;
+pub use cmp_value::{NewCmpValueFeedback, NewCmpValueFeedbackMetadata, NewCmpValueMetadata};
+
+pub mod scalar_max;
+pub use scalar_max::{ScalarMaxFeedback, ScalarMaxFeedbackMetadata, ScalarMaxMetadata};
+
+pub mod downsampled;
+pub use downsampled::{
+    DownsampledMapFeedback, MaxDownsampledMapFeedback, OrDownsampledMapFeedback,
+};
+
+pub mod checkpoint;
+pub use checkpoint::PostCheckpointMapFeedback;
+
+pub mod top_k;
+pub use top_k::{TopKFeedback, TopKFeedbackMetadata, TopKMetadata};
+
+pub mod quick_minimize;
+pub use quick_minimize::{trim_trailing_zeros, MinimizedInputMetadata, QuickMinimizeFeedback};
+
+#[cfg(all(feature = "std", unix))]
+pub mod shared_map;
+#[cfg(all(feature = "std", unix))]
+pub use shared_map::{SharedMapFeedback, SharedMapFile};
+
+#[cfg(feature = "std")]
+pub mod deadline;
+#[cfg(feature = "std")]
+pub use deadline::DeadlineMapFeedback;
+
+pub mod parent_delta;
+pub use parent_delta::{ParentDeltaFeedback, ParentDeltaMetadata};
+
+pub mod size_ratio;
+pub use size_ratio::{size_ratio, SizeRatioFeedback, SizeRatioMetadata};
+
+#[cfg(feature = "std")]
+pub mod overrun;
+#[cfg(feature = "std")]
+pub use overrun::{OverrunFeedback, OverrunPolicy};
+
+pub mod coverage_regression;
+pub use coverage_regression::{
+    CoverageRegressionFeedback, CoverageRegressionFeedbackMetadata, CoverageRegressionMetadata,
+};
+
+pub mod histogram;
+pub use histogram::{CoverageHistogramMetadata, HistogramFeedback};
+
+pub mod namespaced;
+pub use namespaced::{namespaced_metadata, NamespacedFeedback, NamespacedMetadata};
+
+pub mod reservoir;
+pub use reservoir::{ReservoirFeedback, ReservoirFeedbackMetadata, ReservoirMetadata};
+
+pub mod efficiency;
+pub use efficiency::{
+    efficiency, EfficiencyFeedback, EfficiencyFeedbackMetadata, EfficiencyMetadata,
+};
+
+pub mod unique_contribution;
+pub use unique_contribution::{
+    UniqueContributionFeedback, UniqueContributionFeedbackMetadata, UniqueContributionMetadata,
+};
+
+pub mod hot_function;
+pub use hot_function::{HotFunctionFeedback, HotFunctionMetadata};
+
+pub mod pair_coverage;
+pub use pair_coverage::{PairCoverageFeedback, PairCoverageFeedbackMetadata};
+
+pub mod mutator_attribution;
+pub use mutator_attribution::{
+    LastMutatorMetadata, MutatorAttributionFeedback, MutatorOriginMetadata, UNKNOWN_MUTATOR,
+};
+
+pub mod valid;
+pub use valid::{ValidFeedback, ValidityMetadata};
+
 #[cfg(feature = "std")]
 pub mod concolic;
 #[cfg(feature = "std")]
@@ -23,14 +195,20 @@ pub use new_hash_feedback::NewHashFeedbackMetadata;
 
 #[cfg(feature = "nautilus")]
 pub mod nautilus;
-use alloc::string::{String, ToString};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
 use core::{
     fmt::{self, Debug, Formatter},
     marker::PhantomData,
+    time::Duration,
 };
 
 #[cfg(feature = "nautilus")]
 pub use nautilus::*;
+#[cfg(feature = "std")]
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -38,9 +216,9 @@ use crate::{
     corpus::Testcase,
     events::EventFirer,
     executors::ExitKind,
-    inputs::UsesInput,
+    inputs::{Input, UsesInput},
     observers::{ListObserver, ObserversTuple, TimeObserver},
-    state::HasClientPerfMonitor,
+    state::{HasClientPerfMonitor, HasMetadata},
     Error,
 };
 
@@ -146,6 +324,10 @@ where
     pub first: A,
     /// Second [`Feedback`]
     pub second: B,
+    /// The name, computed lazily on the first call to [`Named::name`]
+    #[cfg(feature = "std")]
+    name: OnceCell<String>,
+    #[cfg(not(feature = "std"))]
     name: String,
     phantom: PhantomData<(S, FL)>,
 }
@@ -157,6 +339,13 @@ where
     FL: FeedbackLogic<A, B, S>,
     S: UsesInput + HasClientPerfMonitor,
 {
+    #[cfg(feature = "std")]
+    fn name(&self) -> &str {
+        self.name
+            .get_or_init(|| Self::format_name(&self.first, &self.second))
+    }
+
+    #[cfg(not(feature = "std"))]
     fn name(&self) -> &str {
         self.name.as_ref()
     }
@@ -171,7 +360,10 @@ where
 {
     /// Create a new combined feedback
     pub fn new(first: A, second: B) -> Self {
-        let name = format!("{} ({},{})", FL::name(), first.name(), second.name());
+        #[cfg(feature = "std")]
+        let name = OnceCell::new();
+        #[cfg(not(feature = "std"))]
+        let name = Self::format_name(&first, &second);
         Self {
             first,
             second,
@@ -179,6 +371,10 @@ where
             phantom: PhantomData,
         }
     }
+
+    fn format_name(first: &A, second: &B) -> String {
+        format!("{} ({},{})", FL::name(), first.name(), second.name())
+    }
 }
 
 impl<A, B, FL, S> Feedback<S> for CombinedFeedback<A, B, FL, S>
@@ -253,8 +449,18 @@ where
     where
         OT: ObserversTuple<S>,
     {
+        // Take the observer names recorded so far, so a child replacing the metadata can not drop them
+        let mut interesting_observers = testcase
+            .metadata_map_mut()
+            .remove::<InterestingObserversMetadata>();
         self.first.append_metadata(state, observers, testcase)?;
-        self.second.append_metadata(state, observers, testcase)
+        merge_interesting_observers(&mut interesting_observers, testcase);
+        self.second.append_metadata(state, observers, testcase)?;
+        merge_interesting_observers(&mut interesting_observers, testcase);
+        if let Some(merged) = interesting_observers {
+            testcase.metadata_map_mut().insert_boxed(merged);
+        }
+        Ok(())
     }
 
     #[inline]
@@ -264,6 +470,24 @@ where
     }
 }
 
+/// Moves the [`InterestingObserversMetadata`] of the `testcase`, if any, into `merged`
+fn merge_interesting_observers<I>(
+    merged: &mut Option<Box<InterestingObserversMetadata>>,
+    testcase: &mut Testcase<I>,
+) where
+    I: Input,
+{
+    if let Some(recorded) = testcase
+        .metadata_map_mut()
+        .remove::<InterestingObserversMetadata>()
+    {
+        match merged {
+            Some(merged) => merged.merge(&recorded),
+            None => *merged = Some(recorded),
+        }
+    }
+}
+
 /// Logical combination of two feedbacks
 pub trait FeedbackLogic<A, B, S>: 'static + Debug
 where
@@ -890,12 +1114,76 @@ impl Default for TimeoutFeedback {
 /// A feedback factory for timeout feedbacks
 pub type TimeoutFeedbackFactory = DefaultFeedbackFactory<TimeoutFeedback>;
 
-/// Nop feedback that annotates execution time in the new testcase, if any
-/// for this Feedback, the testcase is never interesting (use with an OR).
-/// It decides, if the given [`TimeObserver`] value of a run is interesting.
+/// A [`NotTimeoutFeedback`] reports as interesting if the run did not time out.
+/// Useful to exclude the (noisy, partial) coverage of timeouts, for example with `feedback_and_fast!`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NotTimeoutFeedback {}
+
+impl<S> Feedback<S> for NotTimeoutFeedback
+where
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        _observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        Ok(*exit_kind != ExitKind::Timeout)
+    }
+}
+
+impl Named for NotTimeoutFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        "NotTimeoutFeedback"
+    }
+}
+
+impl NotTimeoutFeedback {
+    /// Returns a new [`NotTimeoutFeedback`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for NotTimeoutFeedback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// When a [`TimeFeedback`] considers a run interesting
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeFeedbackMode {
+    /// Never interesting, only annotates the execution time of new testcases (use with an OR)
+    #[default]
+    Annotate,
+    /// Interesting if the run took longer than the given [`Duration`]
+    SlowerThan(Duration),
+    /// Interesting if the run took longer than all runs before
+    NewMax,
+}
+
+/// Feedback that annotates execution time in the new testcase, if any.
+/// It decides, depending on its [`TimeFeedbackMode`], if the given [`TimeObserver`] value of a run
+/// is interesting. By default, the testcase is never interesting (use with an OR).
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct TimeFeedback {
     name: String,
+    mode: TimeFeedbackMode,
+    /// The longest runtime so far, for [`TimeFeedbackMode::NewMax`]
+    max_runtime: Option<Duration>,
+    /// The new longest runtime of the last run, committed once its testcase is added
+    last_max: Option<Duration>,
 }
 
 impl<S> Feedback<S> for TimeFeedback
@@ -908,15 +1196,37 @@ where
         _state: &mut S,
         _manager: &mut EM,
         _input: &S::Input,
-        _observers: &OT,
+        observers: &OT,
         _exit_kind: &ExitKind,
     ) -> Result<bool, Error>
     where
         EM: EventFirer<State = S>,
         OT: ObserversTuple<S>,
     {
+        // Only annotating, the observer is not needed before `append_metadata`
+        let slower_than = match self.mode {
+            TimeFeedbackMode::Annotate => return Ok(false),
+            TimeFeedbackMode::SlowerThan(threshold) => Some(threshold),
+            TimeFeedbackMode::NewMax => None,
+        };
         // TODO Replace with match_name_type when stable
-        Ok(false)
+        let observer = observers
+            .match_name::<TimeObserver>(self.name())
+            .ok_or_else(|| {
+                Error::key_not_found(format!("TimeFeedback: observer {} not found", self.name))
+            })?;
+        let Some(runtime) = *observer.last_runtime() else {
+            return Ok(false);
+        };
+        if let Some(threshold) = slower_than {
+            return Ok(runtime > threshold);
+        }
+        if self.max_runtime.map_or(true, |max| runtime > max) {
+            self.last_max = Some(runtime);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
     }
 
     /// Append to the testcase the generated metadata in case of a new corpus item
@@ -932,12 +1242,16 @@ where
     {
         let observer = observers.match_name::<TimeObserver>(self.name()).unwrap();
         *testcase.exec_time_mut() = *observer.last_runtime();
+        if let Some(max) = self.last_max.take() {
+            self.max_runtime = Some(max);
+        }
         Ok(())
     }
 
     /// Discard the stored metadata in case that the testcase is not added to the corpus
     #[inline]
     fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_max = None;
         Ok(())
     }
 }
@@ -951,20 +1265,47 @@ impl Named for TimeFeedback {
 
 impl TimeFeedback {
     /// Creates a new [`TimeFeedback`], deciding if the value of a [`TimeObserver`] with the given `name` of a run is interesting.
+    /// The feedback only annotates the execution time, see [`TimeFeedbackMode::Annotate`].
     #[must_use]
     pub fn new(name: &'static str) -> Self {
-        Self {
-            name: name.to_string(),
-        }
+        Self::with_name_and_mode(name, TimeFeedbackMode::Annotate)
     }
 
     /// Creates a new [`TimeFeedback`], deciding if the given [`TimeObserver`] value of a run is interesting.
+    /// The feedback only annotates the execution time, see [`TimeFeedbackMode::Annotate`].
     #[must_use]
     pub fn with_observer(observer: &TimeObserver) -> Self {
+        Self::with_mode(observer, TimeFeedbackMode::Annotate)
+    }
+
+    /// Creates a new [`TimeFeedback`] for the given [`TimeObserver`], in the given [`TimeFeedbackMode`].
+    #[must_use]
+    pub fn with_mode(observer: &TimeObserver, mode: TimeFeedbackMode) -> Self {
+        Self::with_name_and_mode(observer.name(), mode)
+    }
+
+    /// Creates a new [`TimeFeedback`] for the [`TimeObserver`] with the given `name`, in the given [`TimeFeedbackMode`].
+    #[must_use]
+    pub fn with_name_and_mode(name: &str, mode: TimeFeedbackMode) -> Self {
         Self {
-            name: observer.name().to_string(),
+            name: name.to_string(),
+            mode,
+            max_runtime: None,
+            last_max: None,
         }
     }
+
+    /// The [`TimeFeedbackMode`] of this feedback
+    #[must_use]
+    pub fn mode(&self) -> TimeFeedbackMode {
+        self.mode
+    }
+
+    /// The longest runtime so far, only tracked in [`TimeFeedbackMode::NewMax`]
+    #[must_use]
+    pub fn max_runtime(&self) -> Option<Duration> {
+        self.max_runtime
+    }
 }
 
 /// Consider interesting a testcase if the list in `ListObserver` is not empty.
@@ -1693,3 +2034,138 @@ pub mod pybind {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        bolts::tuples::{tuple_list, Named},
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            ConstFeedback, EagerOrFeedback, FastAndFeedback, Feedback, NotTimeoutFeedback,
+            TimeFeedback, TimeFeedbackMode,
+        },
+        inputs::BytesInput,
+        observers::TimeObserver,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_combined_feedback_name() {
+        let feedback: EagerOrFeedback<_, _, NopState<BytesInput>> =
+            EagerOrFeedback::new(ConstFeedback::True, ConstFeedback::False);
+        // the name is only built on the first call to `name`
+        #[cfg(feature = "std")]
+        assert!(feedback.name.get().is_none());
+        let first = feedback.name();
+        assert_eq!(first, "Eager OR (ConstFeedback,ConstFeedback)");
+        let second = feedback.name();
+        assert_eq!(first, second);
+        assert_eq!(first.as_ptr(), second.as_ptr());
+
+        let nested: FastAndFeedback<_, _, NopState<BytesInput>> =
+            FastAndFeedback::new(ConstFeedback::True, feedback);
+        assert_eq!(
+            nested.name(),
+            "Fast AND (ConstFeedback,Eager OR (ConstFeedback,ConstFeedback))"
+        );
+    }
+
+    #[test]
+    fn test_not_timeout_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback = NotTimeoutFeedback::new();
+        for (exit_kind, expected) in [
+            (ExitKind::Timeout, false),
+            (ExitKind::Crash, true),
+            (ExitKind::Ok, true),
+        ] {
+            assert_eq!(
+                feedback
+                    .is_interesting(&mut state, &mut mgr, &input, &(), &exit_kind)
+                    .unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_time_feedback_modes() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut run = |feedback: &mut TimeFeedback, runtime: u64| {
+            let mut observer = TimeObserver::new("time");
+            observer.set_last_runtime(Some(Duration::from_millis(runtime)));
+            let observers = tuple_list![observer];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            assert_eq!(*testcase.exec_time(), Some(Duration::from_millis(runtime)));
+            interesting
+        };
+
+        let observer = TimeObserver::new("time");
+        let mut annotate = TimeFeedback::with_observer(&observer);
+        assert_eq!(annotate.mode(), TimeFeedbackMode::Annotate);
+        assert!(!run(&mut annotate, 10));
+        assert!(!run(&mut annotate, 1000));
+
+        let mut slower_than = TimeFeedback::with_mode(
+            &observer,
+            TimeFeedbackMode::SlowerThan(Duration::from_millis(100)),
+        );
+        assert!(!run(&mut slower_than, 10));
+        assert!(!run(&mut slower_than, 100));
+        assert!(run(&mut slower_than, 101));
+        assert!(run(&mut slower_than, 101));
+
+        let mut new_max = TimeFeedback::with_mode(&observer, TimeFeedbackMode::NewMax);
+        assert!(run(&mut new_max, 10));
+        assert!(!run(&mut new_max, 10));
+        assert!(!run(&mut new_max, 5));
+        assert!(run(&mut new_max, 20));
+        assert_eq!(new_max.max_runtime(), Some(Duration::from_millis(20)));
+
+        // the mode and the maximum are serialized
+        let serialized = postcard::to_allocvec(&new_max).unwrap();
+        let mut loaded: TimeFeedback = postcard::from_bytes(&serialized).unwrap();
+        assert_eq!(loaded.mode(), TimeFeedbackMode::NewMax);
+        assert!(!run(&mut loaded, 15));
+
+        // a new maximum only counts once its testcase is added to the corpus
+        let mut observer = TimeObserver::new("time");
+        observer.set_last_runtime(Some(Duration::from_millis(30)));
+        let observers = tuple_list![observer];
+        assert!(loaded
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert_eq!(loaded.max_runtime(), Some(Duration::from_millis(20)));
+        loaded.discard_metadata(&mut state, &input).unwrap();
+        assert_eq!(loaded.max_runtime(), Some(Duration::from_millis(20)));
+        assert!(loaded
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        let mut testcase = Testcase::new(input.clone());
+        loaded
+            .append_metadata(&mut state, &observers, &mut testcase)
+            .unwrap();
+        assert_eq!(loaded.max_runtime(), Some(Duration::from_millis(30)));
+
+        // annotating does not need the observer to decide
+        assert!(!annotate
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap());
+    }
+}
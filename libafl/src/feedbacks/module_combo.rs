@@ -0,0 +1,198 @@
+//! The [`ModuleComboFeedback`] reports an input as interesting whenever it covers a new
+//! combination of modules, regardless of the individual entries covered in each module.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasNamedMetadata},
+    Error,
+};
+
+/// The state of [`ModuleComboFeedback`]
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct ModuleComboFeedbackMetadata {
+    /// All module combinations seen so far, as bitsets of module ids
+    pub seen: HashSet<Vec<u64>>,
+}
+
+crate::impl_serdeany!(ModuleComboFeedbackMetadata);
+
+/// A [`ModuleComboFeedback`] maps each covered entry of a [`MapObserver`] to a module id,
+/// using the user-supplied `index_to_module` function, and reports an input as interesting
+/// when the set of touched modules was never seen together before.
+pub struct ModuleComboFeedback<F, O, S>
+where
+    F: Fn(usize) -> usize,
+{
+    name: String,
+    observer_name: String,
+    index_to_module: F,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<F, O, S> ModuleComboFeedback<F, O, S>
+where
+    F: Fn(usize) -> usize,
+    O: MapObserver,
+{
+    /// Creates a new [`ModuleComboFeedback`], mapping map indices to module ids with `index_to_module`.
+    pub fn new(observer: &O, index_to_module: F) -> Self {
+        Self {
+            name: "modulecombofeedback_".to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            index_to_module,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, O, S> Debug for ModuleComboFeedback<F, O, S>
+where
+    F: Fn(usize) -> usize,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModuleComboFeedback")
+            .field("name", &self.name)
+            .field("observer_name", &self.observer_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, O, S> Feedback<S> for ModuleComboFeedback<F, O, S>
+where
+    F: Fn(usize) -> usize,
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor + HasNamedMetadata + Debug,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(ModuleComboFeedbackMetadata::default(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers.match_name::<O>(&self.observer_name).unwrap();
+
+        let mut combo: Vec<u64> = Vec::new();
+        let initial = observer.initial();
+        for i in 0..observer.usable_count() {
+            if *observer.get(i) != initial {
+                let module = (self.index_to_module)(i);
+                let word = module / 64;
+                if combo.len() <= word {
+                    combo.resize(word + 1, 0);
+                }
+                combo[word] |= 1 << (module % 64);
+            }
+        }
+
+        if combo.is_empty() {
+            return Ok(false);
+        }
+
+        let meta = state
+            .named_metadata_map_mut()
+            .get_mut::<ModuleComboFeedbackMetadata>(&self.name)
+            .unwrap();
+        Ok(meta.seen.insert(combo))
+    }
+}
+
+impl<F, O, S> Named for ModuleComboFeedback<F, O, S>
+where
+    F: Fn(usize) -> usize,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<F, O, S> HasObserverName for ModuleComboFeedback<F, O, S>
+where
+    F: Fn(usize) -> usize,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, ModuleComboFeedback},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_module_combo_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        // 4 modules of 4 entries each
+        let mut feedback = ModuleComboFeedback::new(
+            &StdMapObserver::owned("map", vec![0_u8; 16]),
+            |idx: usize| idx / 4,
+        );
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |covered: &[usize]| {
+            let mut map = vec![0_u8; 16];
+            for idx in covered {
+                map[*idx] = 1;
+            }
+            let observers = tuple_list![StdMapObserver::owned("map", map)];
+            feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+        };
+
+        // nothing covered
+        assert!(!run(&[]));
+        // module 0
+        assert!(run(&[0]));
+        // module 0 again, other entries
+        assert!(!run(&[1, 2]));
+        // modules 0 and 1 together
+        assert!(run(&[3, 4]));
+        assert!(!run(&[0, 7]));
+        // module 1 alone
+        assert!(run(&[5]));
+        // modules 0, 1 and 3
+        assert!(run(&[0, 5, 15]));
+    }
+}
@@ -0,0 +1,264 @@
+//! Multi map feedback, aggregating the novelty of several map observers (for example, edges and
+//! comparison operands) into a single decision.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    bolts::{tuples::Named, AsIter},
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{
+        DifferentIsNovel, Feedback, IsNovel, MapFeedbackMetadata, MaxReducer, OrReducer, Reducer,
+    },
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
+    Error,
+};
+
+/// A [`MultiMapFeedback`] that strives to maximize the contents of all maps.
+pub type MaxMultiMapFeedback<O, S, T> = MultiMapFeedback<DifferentIsNovel, O, MaxReducer, S, T>;
+/// A [`MultiMapFeedback`] combining the bits of all maps, AFL-style.
+pub type AflMultiMapFeedback<O, S, T> = MultiMapFeedback<DifferentIsNovel, O, OrReducer, S, T>;
+
+/// A testcase metadata holding the names of the observers whose map was novel for this testcase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiMapNoveltyMetadata {
+    /// The names of the novel map observers
+    pub observers: Vec<String>,
+}
+
+crate::impl_serdeany!(MultiMapNoveltyMetadata);
+
+/// A [`MultiMapFeedback`] keeps a separate history for each of the given map observers,
+/// all of the same type, and reports as interesting if *any* of them shows novelty.
+/// Once the testcase is added, the histories of all maps are updated,
+/// and the novel maps are recorded as [`MultiMapNoveltyMetadata`].
+#[derive(Clone, Debug)]
+pub struct MultiMapFeedback<N, O, R, S, T> {
+    /// Name identifier of this instance
+    name: String,
+    /// Name identifiers of the observers
+    observer_names: Vec<String>,
+    /// For each observer, if its map was novel in the last observation
+    novel: Vec<bool>,
+    /// Phantom Data of Reducer
+    phantom: PhantomData<(N, O, R, S, T)>,
+}
+
+impl<N, O, R, S, T> MultiMapFeedback<N, O, R, S, T> {
+    /// Create a new [`MultiMapFeedback`] for the map observers with the given names
+    #[must_use]
+    pub fn new(name: &str, observer_names: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            observer_names: observer_names.iter().map(|s| (*s).to_string()).collect(),
+            novel: vec![false; observer_names.len()],
+            phantom: PhantomData,
+        }
+    }
+
+    /// The names of the observed maps
+    #[must_use]
+    pub fn observer_names(&self) -> &[String] {
+        &self.observer_names
+    }
+
+    /// The name of the history metadata for the given observer
+    fn history_name(&self, observer_name: &str) -> String {
+        format!("{}_{observer_name}", self.name)
+    }
+}
+
+impl<N, O, R, S, T> Feedback<S> for MultiMapFeedback<N, O, R, S, T>
+where
+    N: IsNovel<T> + Debug,
+    O: MapObserver<Entry = T> + for<'it> AsIter<'it, Item = T>,
+    R: Reducer<T> + Debug,
+    S: UsesInput + HasClientPerfMonitor + HasNamedMetadata + Debug,
+    T: Default + Copy + Serialize + DeserializeOwned + PartialEq + Debug + 'static,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        // The histories are resized on-demand in `is_interesting`
+        for observer_name in &self.observer_names {
+            state.add_named_metadata(
+                MapFeedbackMetadata::<T>::default(),
+                &self.history_name(observer_name),
+            );
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        for (idx, observer_name) in self.observer_names.iter().enumerate() {
+            let observer = observers.match_name::<O>(observer_name).ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "MultiMapFeedback: observer {observer_name} not found"
+                ))
+            })?;
+            let initial = observer.initial();
+
+            let map_state = state
+                .named_metadata_map_mut()
+                .get_mut::<MapFeedbackMetadata<T>>(&self.history_name(observer_name))
+                .unwrap();
+            if map_state.history_map.len() < observer.len() {
+                map_state.history_map.resize(observer.len(), initial);
+            }
+            let history_map = map_state.history_map.as_slice();
+
+            self.novel[idx] = observer
+                .as_iter()
+                .copied()
+                .enumerate()
+                .filter(|(_, item)| *item != initial)
+                .any(|(i, item)| {
+                    let existing = history_map[i];
+                    N::is_novel(existing, R::reduce(existing, item))
+                });
+        }
+        Ok(self.novel.iter().any(|novel| *novel))
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        let mut novel_observers = Vec::new();
+        for (idx, observer_name) in self.observer_names.iter().enumerate() {
+            let observer = observers.match_name::<O>(observer_name).ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "MultiMapFeedback: observer {observer_name} not found"
+                ))
+            })?;
+            let initial = observer.initial();
+
+            let map_state = state
+                .named_metadata_map_mut()
+                .get_mut::<MapFeedbackMetadata<T>>(&self.history_name(observer_name))
+                .unwrap();
+            if map_state.history_map.len() < observer.len() {
+                map_state.history_map.resize(observer.len(), initial);
+            }
+            let history_map = map_state.history_map.as_mut_slice();
+
+            for (i, value) in observer
+                .as_iter()
+                .copied()
+                .enumerate()
+                .filter(|(_, value)| *value != initial)
+            {
+                history_map[i] = R::reduce(history_map[i], value);
+            }
+
+            if core::mem::take(&mut self.novel[idx]) {
+                novel_observers.push(observer_name.clone());
+            }
+        }
+        testcase.add_metadata(MultiMapNoveltyMetadata {
+            observers: novel_observers,
+        });
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.novel.iter_mut().for_each(|novel| *novel = false);
+        Ok(())
+    }
+}
+
+impl<N, O, R, S, T> Named for MultiMapFeedback<N, O, R, S, T> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, MaxMultiMapFeedback, MultiMapNoveltyMetadata},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_multi_map_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback: MaxMultiMapFeedback<StdMapObserver<'_, u8, false>, _, u8> =
+            MaxMultiMapFeedback::new("multimap", &["edges", "cmps"]);
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |edges: [u8; 4], cmps: [u8; 4]| {
+            let observers = tuple_list![
+                StdMapObserver::owned("edges", edges.to_vec()),
+                StdMapObserver::owned("cmps", cmps.to_vec())
+            ];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            let mut testcase = Testcase::new(input.clone());
+            if interesting {
+                feedback
+                    .append_metadata(&mut state, &observers, &mut testcase)
+                    .unwrap();
+            } else {
+                feedback.discard_metadata(&mut state, &input).unwrap();
+            }
+            testcase
+                .metadata()
+                .get::<MultiMapNoveltyMetadata>()
+                .map(|meta| meta.observers.clone())
+        };
+
+        assert_eq!(
+            run([1, 0, 0, 0], [0, 0, 0, 0]),
+            Some(vec!["edges".to_string()])
+        );
+        assert_eq!(run([1, 0, 0, 0], [0, 0, 0, 0]), None);
+        // novelty in the cmps map only
+        assert_eq!(
+            run([1, 0, 0, 0], [0, 0, 3, 0]),
+            Some(vec!["cmps".to_string()])
+        );
+        assert_eq!(run([1, 0, 0, 0], [0, 0, 2, 0]), None);
+        assert_eq!(
+            run([0, 1, 0, 0], [0, 0, 4, 0]),
+            Some(vec!["edges".to_string(), "cmps".to_string()])
+        );
+    }
+}
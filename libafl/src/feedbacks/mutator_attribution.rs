@@ -0,0 +1,148 @@
+//! The [`MutatorAttributionFeedback`] records on each saved testcase the mutator that created its
+//! input, to analyze the effectiveness of the mutators.
+
+use alloc::string::{String, ToString};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// The mutator origin recorded when the state holds no [`LastMutatorMetadata`]
+pub const UNKNOWN_MUTATOR: &str = "unknown";
+
+/// A state metadata holding the name of the mutator last applied to the current input, set by the
+/// mutational stage (or mutator) before the input is executed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastMutatorMetadata {
+    /// The name of the mutator
+    pub name: String,
+}
+
+crate::impl_serdeany!(LastMutatorMetadata);
+
+impl LastMutatorMetadata {
+    /// Creates a new [`LastMutatorMetadata`] for the mutator with the given name
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+        }
+    }
+}
+
+/// A testcase metadata holding the name of the mutator that created the input of the testcase, as
+/// recorded by a [`MutatorAttributionFeedback`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MutatorOriginMetadata {
+    /// The name of the mutator, [`UNKNOWN_MUTATOR`] if unknown
+    pub name: String,
+}
+
+crate::impl_serdeany!(MutatorOriginMetadata);
+
+/// A [`MutatorAttributionFeedback`] copies the name of the last applied mutator, from the
+/// [`LastMutatorMetadata`] of the state, into a [`MutatorOriginMetadata`] of each saved testcase.
+/// Without [`LastMutatorMetadata`], e.g. for the initial inputs, the origin is [`UNKNOWN_MUTATOR`].
+///
+/// It never considers an input interesting itself, so combine it with other feedbacks, e.g. in an
+/// [`crate::feedback_or`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MutatorAttributionFeedback {}
+
+impl MutatorAttributionFeedback {
+    /// Creates a new [`MutatorAttributionFeedback`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<S> Feedback<S> for MutatorAttributionFeedback
+where
+    S: UsesInput + HasClientPerfMonitor + HasMetadata,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        _observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        Ok(false)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        let name = state
+            .metadata::<LastMutatorMetadata>()
+            .map_or_else(|_| UNKNOWN_MUTATOR.to_string(), |meta| meta.name.clone());
+        testcase.add_metadata(MutatorOriginMetadata { name });
+        Ok(())
+    }
+}
+
+impl Named for MutatorAttributionFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        "MutatorAttributionFeedback"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        corpus::Testcase,
+        feedbacks::{
+            Feedback, LastMutatorMetadata, MutatorAttributionFeedback, MutatorOriginMetadata,
+            UNKNOWN_MUTATOR,
+        },
+        inputs::BytesInput,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_mutator_attribution_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut feedback = MutatorAttributionFeedback::new();
+
+        // returns the recorded origin of a saved testcase
+        let mut save = |state: &mut NopState<BytesInput>| {
+            let mut testcase = Testcase::new(BytesInput::new(vec![0]));
+            feedback.append_metadata(state, &(), &mut testcase).unwrap();
+            testcase
+                .metadata::<MutatorOriginMetadata>()
+                .unwrap()
+                .name
+                .clone()
+        };
+
+        assert_eq!(save(&mut state), UNKNOWN_MUTATOR);
+        state.add_metadata(LastMutatorMetadata::new("BitFlipMutator"));
+        assert_eq!(save(&mut state), "BitFlipMutator");
+        state.add_metadata(LastMutatorMetadata::new("ByteIncMutator"));
+        assert_eq!(save(&mut state), "ByteIncMutator");
+    }
+}
@@ -0,0 +1,281 @@
+//! The [`NamespacedFeedback`] keeps the testcase metadata of a feedback apart from the metadata of
+//! other feedbacks, so that composed feedbacks adding metadata of the same type don't overwrite
+//! each other's.
+
+use alloc::string::{String, ToString};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    mem,
+};
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{
+        serdeany::{SerdeAny, SerdeAnyMap},
+        tuples::Named,
+    },
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::{Input, UsesInput},
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata holding the metadata added by each [`NamespacedFeedback`], by namespace
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct NamespacedMetadata {
+    /// The metadata of each namespace
+    pub namespaces: HashMap<String, SerdeAnyMap>,
+}
+
+crate::impl_serdeany!(NamespacedMetadata);
+
+impl NamespacedMetadata {
+    /// The metadata of the given namespace, if any
+    #[must_use]
+    pub fn namespace(&self, namespace: &str) -> Option<&SerdeAnyMap> {
+        self.namespaces.get(namespace)
+    }
+
+    /// The metadata of type `M` in the given namespace, if any
+    #[must_use]
+    pub fn get<M>(&self, namespace: &str) -> Option<&M>
+    where
+        M: SerdeAny,
+    {
+        self.namespace(namespace)?.get::<M>()
+    }
+}
+
+/// Gets the metadata of type `M` added to the testcase by the [`NamespacedFeedback`] with the
+/// given namespace, if any
+#[must_use]
+pub fn namespaced_metadata<'a, I, M>(testcase: &'a Testcase<I>, namespace: &str) -> Option<&'a M>
+where
+    I: Input,
+    M: SerdeAny,
+{
+    testcase
+        .metadata::<NamespacedMetadata>()
+        .ok()?
+        .get::<M>(namespace)
+}
+
+/// A [`NamespacedFeedback`] wraps another [`Feedback`], and moves the testcase metadata it adds
+/// into its namespace in the [`NamespacedMetadata`] of the testcase, so that it doesn't overwrite,
+/// nor get overwritten by, metadata of the same type added by other feedbacks.
+///
+/// While adding its metadata, the wrapped feedback only sees the metadata of its own namespace.
+/// To retrieve it, look the namespace up in the [`NamespacedMetadata`], e.g.:
+///
+/// ```rust,ignore
+/// let meta = namespaced_metadata::<_, MapIndexesMetadata>(&testcase, "edges");
+/// // or
+/// let meta = testcase
+///     .metadata::<NamespacedMetadata>()?
+///     .get::<MapIndexesMetadata>("edges");
+/// ```
+///
+/// The result of the wrapped feedback is forwarded unchanged.
+pub struct NamespacedFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    namespace: String,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S> NamespacedFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`NamespacedFeedback`], wrapping the given feedback, and keeping its metadata
+    /// in `namespace`
+    pub fn new(namespace: &str, inner: A) -> Self {
+        let name = format!("Namespaced({namespace}, {})", inner.name());
+        Self {
+            inner,
+            namespace: namespace.to_string(),
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The namespace of the metadata of the wrapped feedback
+    #[must_use]
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+}
+
+impl<A, S> Debug for NamespacedFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NamespacedFeedback")
+            .field("name", &self.name)
+            .field("namespace", &self.namespace)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, S> Named for NamespacedFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S> Feedback<S> for NamespacedFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.inner
+            .is_interesting(state, manager, input, observers, exit_kind)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        // swap in the metadata of the namespace, and swap it out again afterwards
+        let mut namespaced = testcase
+            .metadata_map_mut()
+            .remove::<NamespacedMetadata>()
+            .map(|meta| *meta)
+            .unwrap_or_default();
+        let mut metadata = namespaced
+            .namespaces
+            .remove(&self.namespace)
+            .unwrap_or_default();
+        mem::swap(testcase.metadata_map_mut(), &mut metadata);
+        let res = self.inner.append_metadata(state, observers, testcase);
+        mem::swap(testcase.metadata_map_mut(), &mut metadata);
+
+        namespaced
+            .namespaces
+            .insert(self.namespace.clone(), metadata);
+        testcase.add_metadata(namespaced);
+        res
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            namespaced_metadata, Feedback, MapIndexesMetadata, MaxMapFeedback, NamespacedFeedback,
+            NamespacedMetadata,
+        },
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_namespaced_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        // two map feedbacks, both adding a `MapIndexesMetadata`
+        let edges = StdMapObserver::owned("edges", vec![0_u8; 4]);
+        let cmps = StdMapObserver::owned("cmps", vec![0_u8; 4]);
+        let mut edges_feedback = NamespacedFeedback::new(
+            "edges",
+            MaxMapFeedback::<_, NopState<BytesInput>, u8>::tracking(&edges, true, false),
+        );
+        let mut cmps_feedback = NamespacedFeedback::new(
+            "cmps",
+            MaxMapFeedback::<_, NopState<BytesInput>, u8>::tracking(&cmps, true, false),
+        );
+        edges_feedback.init_state(&mut state).unwrap();
+        cmps_feedback.init_state(&mut state).unwrap();
+
+        let observers = tuple_list![
+            StdMapObserver::owned("edges", vec![1, 0, 1, 0]),
+            StdMapObserver::owned("cmps", vec![0, 0, 0, 1])
+        ];
+        assert!(edges_feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert!(cmps_feedback
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        let mut testcase = Testcase::new(input.clone());
+        edges_feedback
+            .append_metadata(&mut state, &observers, &mut testcase)
+            .unwrap();
+        cmps_feedback
+            .append_metadata(&mut state, &observers, &mut testcase)
+            .unwrap();
+
+        // both metadata survive, none in the top-level map
+        assert!(!testcase.has_metadata::<MapIndexesMetadata>());
+        assert_eq!(
+            namespaced_metadata::<_, MapIndexesMetadata>(&testcase, "edges")
+                .unwrap()
+                .list,
+            vec![0, 2]
+        );
+        assert_eq!(
+            testcase
+                .metadata::<NamespacedMetadata>()
+                .unwrap()
+                .get::<MapIndexesMetadata>("cmps")
+                .unwrap()
+                .list,
+            vec![3]
+        );
+        assert!(namespaced_metadata::<_, MapIndexesMetadata>(&testcase, "other").is_none());
+    }
+}
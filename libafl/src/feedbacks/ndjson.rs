@@ -0,0 +1,252 @@
+//! The [`NdjsonFeedback`] streams a newline-delimited JSON record for every interesting result of
+//! the wrapped feedback to a [`Write`], for ingestion by external dashboards.
+
+use alloc::string::String;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::{
+    bolts::{current_time, tuples::Named},
+    corpus::{input_hash, Testcase},
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// When an [`NdjsonFeedback`] flushes its writer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NdjsonFlushPolicy {
+    /// Flush after every record
+    EveryRecord,
+    /// Flush after every `n` records
+    EveryN(usize),
+    /// Never flush automatically, see [`NdjsonFeedback::flush`]
+    Manual,
+}
+
+/// A single record written by the [`NdjsonFeedback`]
+#[derive(Debug, Serialize)]
+struct NdjsonRecord<'a> {
+    input_hash: String,
+    feedback_name: &'a str,
+    /// Seconds since the UNIX epoch
+    timestamp: f64,
+    exit_kind: ExitKind,
+}
+
+/// An [`NdjsonFeedback`] wraps another [`Feedback`] and, whenever it is interesting, writes a record
+/// `{"input_hash", "feedback_name", "timestamp", "exit_kind"}` as a single JSON line to the supplied writer.
+/// The result of the wrapped feedback is forwarded unchanged.
+pub struct NdjsonFeedback<A, S, W>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+    W: Write,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    writer: W,
+    flush_policy: NdjsonFlushPolicy,
+    /// Records written since the last flush
+    unflushed: usize,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S, W> NdjsonFeedback<A, S, W>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+    W: Write,
+{
+    /// Creates a new [`NdjsonFeedback`], flushing the writer after every record
+    pub fn new(inner: A, writer: W) -> Self {
+        Self::with_flush_policy(inner, writer, NdjsonFlushPolicy::EveryRecord)
+    }
+
+    /// Creates a new [`NdjsonFeedback`] with the given [`NdjsonFlushPolicy`]
+    pub fn with_flush_policy(inner: A, writer: W, flush_policy: NdjsonFlushPolicy) -> Self {
+        let name = format!("Ndjson({})", inner.name());
+        Self {
+            inner,
+            writer,
+            flush_policy,
+            unflushed: 0,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The writer the records are written to
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Flushes the records written so far
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        self.unflushed = 0;
+        Ok(())
+    }
+
+    fn write_record(&mut self, input: &S::Input, exit_kind: &ExitKind) -> Result<(), Error> {
+        let record = NdjsonRecord {
+            input_hash: format!("{:016x}", input_hash(input)?),
+            feedback_name: self.inner.name(),
+            timestamp: current_time().as_secs_f64(),
+            exit_kind: *exit_kind,
+        };
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+        self.unflushed += 1;
+
+        match self.flush_policy {
+            NdjsonFlushPolicy::EveryRecord => self.flush(),
+            NdjsonFlushPolicy::EveryN(n) if self.unflushed >= n => self.flush(),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<A, S, W> Debug for NdjsonFeedback<A, S, W>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+    W: Write,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NdjsonFeedback")
+            .field("name", &self.name)
+            .field("flush_policy", &self.flush_policy)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, S, W> Named for NdjsonFeedback<A, S, W>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+    W: Write,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S, W> Feedback<S> for NdjsonFeedback<A, S, W>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+    W: Write,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        if interesting {
+            self.write_record(input, exit_kind)?;
+        }
+        Ok(interesting)
+    }
+
+    #[inline]
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    #[inline]
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{CrashFeedback, Feedback, NdjsonFeedback, NdjsonFlushPolicy},
+        inputs::BytesInput,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_ndjson_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+
+        let mut feedback = NdjsonFeedback::with_flush_policy(
+            CrashFeedback::new(),
+            Vec::new(),
+            NdjsonFlushPolicy::EveryN(2),
+        );
+
+        let runs = [
+            (ExitKind::Ok, false),
+            (ExitKind::Crash, true),
+            (ExitKind::Timeout, false),
+            (ExitKind::Crash, true),
+        ];
+        for (i, (exit_kind, expected)) in runs.iter().enumerate() {
+            let input = BytesInput::new(vec![u8::try_from(i).unwrap()]);
+            assert_eq!(
+                feedback
+                    .is_interesting(&mut state, &mut mgr, &input, &(), exit_kind)
+                    .unwrap(),
+                *expected
+            );
+        }
+        feedback.flush().unwrap();
+
+        let output = core::str::from_utf8(feedback.writer()).unwrap();
+        let records: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        for record in &records {
+            assert_eq!(record["feedback_name"], "CrashFeedback");
+            assert_eq!(record["exit_kind"], "Crash");
+            assert_eq!(record["input_hash"].as_str().unwrap().len(), 16);
+            assert!(record["timestamp"].as_f64().unwrap() > 0.0);
+        }
+        assert_ne!(records[0]["input_hash"], records[1]["input_hash"]);
+    }
+}
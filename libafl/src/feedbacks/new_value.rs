@@ -0,0 +1,230 @@
+//! The [`NewValueFeedback`] keeps a set of all values seen in a [`ValueObserver`] and only keeps novel ones
+
+use alloc::string::{String, ToString};
+use core::{fmt::Debug, hash::Hash};
+
+use hashbrown::HashSet;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{ObserversTuple, ValueObserver},
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata holding the novel value observed by a [`NewValueFeedback`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct NewValueMetadata<T>
+where
+    T: Debug + Serialize + 'static,
+{
+    /// The novel value
+    pub value: T,
+}
+
+crate::impl_serdeany!(
+    NewValueMetadata<T: Debug + Serialize + DeserializeOwned + 'static>,
+    <u8>,<u16>,<u32>,<u64>,<usize>,<i8>,<i16>,<i32>,<i64>,<isize>,<bool>,<char>
+);
+
+impl<T> NewValueMetadata<T>
+where
+    T: Debug + Serialize + 'static,
+{
+    /// Creates a new [`struct@NewValueMetadata`]
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self { value }
+    }
+}
+
+/// A [`NewValueFeedback`] maintains a set of already seen values of a [`ValueObserver`]
+/// and considers interesting the inputs producing an unseen one.
+/// The novel value is added to the testcase as [`struct@NewValueMetadata`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct NewValueFeedback<T>
+where
+    T: Debug + Serialize + Hash + Eq,
+{
+    name: String,
+    observer_name: String,
+    /// All values seen so far
+    seen: HashSet<T>,
+    /// The novel value of the last run, if any
+    last_novel: Option<T>,
+}
+
+impl<T> NewValueFeedback<T>
+where
+    T: Debug + Serialize + DeserializeOwned + Hash + Eq,
+{
+    /// Creates a new [`NewValueFeedback`] for the given [`ValueObserver`].
+    #[must_use]
+    pub fn new(observer: &ValueObserver<T>) -> Self {
+        Self::with_names(
+            &("newvaluefeedback_".to_string() + observer.name()),
+            observer.name(),
+        )
+    }
+
+    /// Creates a new [`NewValueFeedback`].
+    /// Setting an observer name that doesn't exist would eventually trigger a panic.
+    #[must_use]
+    pub fn with_names(name: &str, observer_name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            observer_name: observer_name.to_string(),
+            seen: HashSet::new(),
+            last_novel: None,
+        }
+    }
+
+    /// The set of values seen so far
+    #[must_use]
+    pub fn seen(&self) -> &HashSet<T> {
+        &self.seen
+    }
+}
+
+impl<S, T> Feedback<S> for NewValueFeedback<T>
+where
+    S: UsesInput + HasClientPerfMonitor,
+    T: Debug + Serialize + DeserializeOwned + Hash + Eq + Clone + 'static,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<ValueObserver<T>>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "NewValueFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+
+        let value = observer.get_ref();
+        if self.seen.contains(value) {
+            self.last_novel = None;
+            Ok(false)
+        } else {
+            self.seen.insert(value.clone());
+            self.last_novel = Some(value.clone());
+            Ok(true)
+        }
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(value) = self.last_novel.take() {
+            testcase.add_metadata(NewValueMetadata::new(value));
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_novel = None;
+        Ok(())
+    }
+}
+
+impl<T> Named for NewValueFeedback<T>
+where
+    T: Debug + Serialize + Hash + Eq,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T> HasObserverName for NewValueFeedback<T>
+where
+    T: Debug + Serialize + Hash + Eq,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, NewValueFeedback, NewValueMetadata},
+        inputs::BytesInput,
+        observers::ValueObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_new_value_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let initial = 0_u32;
+        let mut feedback = NewValueFeedback::new(&ValueObserver::new("value", &initial));
+
+        let mut run = |feedback: &mut NewValueFeedback<u32>, value: u32| {
+            let observers = tuple_list![ValueObserver::new("value", &value)];
+            feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+        };
+
+        assert!(run(&mut feedback, 1));
+        assert!(!run(&mut feedback, 1));
+        assert!(run(&mut feedback, 2));
+        assert!(!run(&mut feedback, 1));
+        assert!(!run(&mut feedback, 2));
+        assert!(run(&mut feedback, 3));
+        assert_eq!(feedback.seen().len(), 3);
+
+        let mut testcase = Testcase::new(BytesInput::new(vec![0]));
+        Feedback::<NopState<BytesInput>>::append_metadata(
+            &mut feedback,
+            &mut NopState::new(),
+            &(),
+            &mut testcase,
+        )
+        .unwrap();
+        assert_eq!(
+            testcase
+                .metadata()
+                .get::<NewValueMetadata<u32>>()
+                .unwrap()
+                .value,
+            3
+        );
+    }
+}
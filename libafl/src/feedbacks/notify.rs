@@ -0,0 +1,224 @@
+//! Notify Feedback, firing a custom event through the [`EventFirer`] whenever the wrapped feedback is interesting.
+//!
+
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::{Event, EventFirer},
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// A [`NotifyFeedback`] wraps another [`Feedback`] and, each time the wrapped feedback reports an
+/// input as interesting, fires an [`Event::CustomBuf`] through the event manager.
+/// The payload of the event is derived from the input using the given `payload_fn`.
+///
+/// The result of the wrapped feedback is forwarded unchanged.
+pub struct NotifyFeedback<A, F, S>
+where
+    A: Feedback<S>,
+    F: FnMut(&S::Input) -> Vec<u8>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    /// The tag of the fired [`Event::CustomBuf`]
+    tag: String,
+    /// The function used to derive the payload from the input
+    payload_fn: F,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, F, S> NotifyFeedback<A, F, S>
+where
+    A: Feedback<S>,
+    F: FnMut(&S::Input) -> Vec<u8>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`NotifyFeedback`], firing an [`Event::CustomBuf`] with the given `tag`
+    /// and a payload computed by `payload_fn` whenever `inner` is interesting.
+    pub fn new(inner: A, tag: &str, payload_fn: F) -> Self {
+        let name = format!("Notify({})", inner.name());
+        Self {
+            inner,
+            tag: tag.into(),
+            payload_fn,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The tag of the fired [`Event::CustomBuf`]
+    #[must_use]
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+}
+
+impl<A, F, S> Debug for NotifyFeedback<A, F, S>
+where
+    A: Feedback<S>,
+    F: FnMut(&S::Input) -> Vec<u8>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotifyFeedback")
+            .field("name", &self.name)
+            .field("tag", &self.tag)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<A, F, S> Named for NotifyFeedback<A, F, S>
+where
+    A: Feedback<S>,
+    F: FnMut(&S::Input) -> Vec<u8>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, F, S> Feedback<S> for NotifyFeedback<A, F, S>
+where
+    A: Feedback<S>,
+    F: FnMut(&S::Input) -> Vec<u8>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        if interesting {
+            manager.fire(
+                state,
+                Event::CustomBuf {
+                    buf: (self.payload_fn)(input),
+                    tag: self.tag.clone(),
+                },
+            )?;
+        }
+        Ok(interesting)
+    }
+
+    #[inline]
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    #[inline]
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec::Vec};
+    use core::marker::PhantomData;
+
+    use crate::{
+        events::{Event, EventFirer},
+        executors::ExitKind,
+        feedbacks::{ConstFeedback, Feedback, NotifyFeedback},
+        inputs::{BytesInput, HasBytesVec, UsesInput},
+        state::{NopState, UsesState},
+    };
+
+    struct RecordingEventFirer<S> {
+        fired: Vec<(String, Vec<u8>)>,
+        phantom: PhantomData<S>,
+    }
+    impl<S> UsesState for RecordingEventFirer<S>
+    where
+        S: UsesInput,
+    {
+        type State = S;
+    }
+    impl<S> EventFirer for RecordingEventFirer<S>
+    where
+        S: UsesInput,
+    {
+        fn fire(&mut self, _state: &mut S, event: Event<S::Input>) -> Result<(), crate::Error> {
+            if let Event::CustomBuf { buf, tag } = event {
+                self.fired.push((tag, buf));
+            }
+            Ok(())
+        }
+    }
+
+    fn test_notify(inner_result: bool) {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = RecordingEventFirer {
+            fired: Vec::new(),
+            phantom: PhantomData,
+        };
+        let mut feedback = NotifyFeedback::new(
+            ConstFeedback::new(inner_result),
+            "interesting",
+            |input: &BytesInput| input.bytes().to_vec(),
+        );
+
+        let input = BytesInput::new(vec![1, 2, 3]);
+        let res = feedback
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap();
+        assert_eq!(res, inner_result);
+
+        if inner_result {
+            assert_eq!(mgr.fired.len(), 1);
+            assert_eq!(mgr.fired[0].0, "interesting");
+            assert_eq!(mgr.fired[0].1, vec![1, 2, 3]);
+        } else {
+            assert!(mgr.fired.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_notify_interesting() {
+        test_notify(true);
+    }
+
+    #[test]
+    fn test_notify_not_interesting() {
+        test_notify(false);
+    }
+}
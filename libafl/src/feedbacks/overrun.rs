@@ -0,0 +1,242 @@
+//! The [`OverrunFeedback`] accounts for the evaluations of a slow feedback, such as a custom
+//! feedback written in Python, that exceed a time budget.
+//!
+//! This is not a watchdog: the wrapped feedback is never interrupted, so a feedback that hangs
+//! still hangs the fuzzer.
+
+use alloc::string::String;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    time::Duration,
+};
+use std::time::Instant;
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// What an [`OverrunFeedback`] does with the result of an evaluation that exceeded its time budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverrunPolicy {
+    /// The input is not interesting
+    NotInteresting,
+    /// The evaluation fails with an [`Error`]
+    Error,
+}
+
+/// An [`OverrunFeedback`] wraps another [`Feedback`] and measures the time it takes to evaluate
+/// each input. Once the wrapped feedback returns after more than the budget, the overrun is counted
+/// and logged, and its result is dropped according to the [`OverrunPolicy`]. Otherwise, the result
+/// of the wrapped feedback is forwarded unchanged.
+///
+/// The time is only accounted for after the fact: the wrapped feedback is not interrupted, and no
+/// deadline is enforced, so hangs are not addressed. Use a timeout in the executor for those.
+pub struct OverrunFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    budget: Duration,
+    policy: OverrunPolicy,
+    /// The number of evaluations exceeding the budget
+    overruns: usize,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S> OverrunFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`OverrunFeedback`], wrapping the given feedback, and applying `policy` to
+    /// its evaluations taking longer than `budget`
+    pub fn new(inner: A, budget: Duration, policy: OverrunPolicy) -> Self {
+        let name = format!("Overrun({})", inner.name());
+        Self {
+            inner,
+            budget,
+            policy,
+            overruns: 0,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The time budget of each evaluation
+    #[must_use]
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// What happens to the result of an evaluation exceeding the budget
+    #[must_use]
+    pub fn policy(&self) -> OverrunPolicy {
+        self.policy
+    }
+
+    /// The number of evaluations exceeding the budget so far
+    #[must_use]
+    pub fn overruns(&self) -> usize {
+        self.overruns
+    }
+}
+
+impl<A, S> Debug for OverrunFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OverrunFeedback")
+            .field("name", &self.name)
+            .field("budget", &self.budget)
+            .field("policy", &self.policy)
+            .field("overruns", &self.overruns)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, S> Named for OverrunFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S> Feedback<S> for OverrunFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let start = Instant::now();
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        let elapsed = start.elapsed();
+        if elapsed <= self.budget {
+            return Ok(interesting);
+        }
+
+        self.overruns += 1;
+        log::warn!(
+            "{}: the evaluation took {elapsed:?}, more than the budget of {:?}",
+            self.name,
+            self.budget
+        );
+        match self.policy {
+            OverrunPolicy::NotInteresting => Ok(false),
+            OverrunPolicy::Error => Err(Error::illegal_state(format!(
+                "{}: the evaluation took {elapsed:?}, more than the budget of {:?}",
+                self.name, self.budget
+            ))),
+        }
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::thread;
+
+    use crate::{
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, FnFeedback, OverrunFeedback, OverrunPolicy},
+        inputs::{BytesInput, HasBytesVec},
+        state::NopState,
+    };
+
+    #[test]
+    fn test_overrun_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        // always interesting, sleeping for as many tens of milliseconds as the first input byte
+        let sleeping = || {
+            FnFeedback::new(
+                "sleeping",
+                |_state: &mut NopState<BytesInput>, input: &BytesInput, _| {
+                    thread::sleep(Duration::from_millis(10 * u64::from(input.bytes()[0])));
+                    Ok(true)
+                },
+            )
+        };
+        let fast = BytesInput::new(vec![0]);
+        let slow = BytesInput::new(vec![20]);
+
+        let mut feedback = OverrunFeedback::new(
+            sleeping(),
+            Duration::from_millis(100),
+            OverrunPolicy::NotInteresting,
+        );
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &fast, &(), &ExitKind::Ok)
+            .unwrap());
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &slow, &(), &ExitKind::Ok)
+            .unwrap());
+        assert_eq!(feedback.overruns(), 1);
+
+        let mut feedback = OverrunFeedback::new(
+            sleeping(),
+            Duration::from_millis(100),
+            OverrunPolicy::Error,
+        );
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &slow, &(), &ExitKind::Ok)
+            .is_err());
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &fast, &(), &ExitKind::Ok)
+            .unwrap());
+        assert_eq!(feedback.overruns(), 1);
+    }
+}
@@ -0,0 +1,297 @@
+//! The [`PairCoverageFeedback`] keeps inputs covering a new pair of map entries in the same run,
+//! catching interactions between edges that plain edge coverage misses.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const PAIR_COVERAGE_FEEDBACK_PREFIX: &str = "paircoveragefeedback_metadata_";
+
+/// The default number of covered entries sampled per run, see [`PairCoverageFeedback`]
+pub const DEFAULT_MAX_SAMPLED_ENTRIES: usize = 64;
+
+/// The state of [`PairCoverageFeedback`]: the pairs of map entries covered together so far
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct PairCoverageFeedbackMetadata {
+    /// The seen pairs, lower index first
+    pub pairs: HashSet<(usize, usize)>,
+}
+
+crate::impl_serdeany!(PairCoverageFeedbackMetadata);
+
+impl PairCoverageFeedbackMetadata {
+    /// Create a new [`PairCoverageFeedbackMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.pairs.clear();
+        Ok(())
+    }
+}
+
+/// The rank of a map entry in the sample: a fixed pseudo-random permutation of the indices
+#[inline]
+fn sample_rank(idx: usize) -> u64 {
+    (idx as u64)
+        .wrapping_mul(0x9e37_79b9_7f4a_7c15)
+        .rotate_left(32)
+}
+
+/// A [`PairCoverageFeedback`] considers interesting the inputs covering two entries of a
+/// [`MapObserver`] in the same run, that were never covered together by a saved testcase before.
+/// The seen pairs are kept in the state, and updated when a testcase is saved.
+///
+/// A run covering `n` entries covers `n * (n - 1) / 2` pairs. To bound the work per run and the
+/// growth of the seen pairs, at most `max_entries` covered entries are sampled per run, so at most
+/// `max_entries * (max_entries - 1) / 2` pairs. The sample is the `max_entries` covered entries
+/// first in a fixed pseudo-random order of the indices. As the order is the same for every run,
+/// runs covering similar entries sample similar entries, and pairs among the sampled entries are
+/// compared consistently across runs. Pairs of entries that are never sampled together are missed.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PairCoverageFeedback<O, S> {
+    name: String,
+    observer_name: String,
+    /// The maximum number of covered entries sampled per run
+    max_entries: usize,
+    /// The new pairs of the last run
+    last_pairs: Option<Vec<(usize, usize)>>,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<O, S> PairCoverageFeedback<O, S>
+where
+    O: MapObserver,
+{
+    /// Creates a new [`PairCoverageFeedback`] for the given map observer, sampling at most
+    /// [`DEFAULT_MAX_SAMPLED_ENTRIES`] covered entries per run
+    #[must_use]
+    pub fn new(observer: &O) -> Self {
+        Self::with_max_entries(observer, DEFAULT_MAX_SAMPLED_ENTRIES)
+    }
+
+    /// Creates a new [`PairCoverageFeedback`] for the given map observer, sampling at most
+    /// `max_entries` covered entries per run
+    #[must_use]
+    pub fn with_max_entries(observer: &O, max_entries: usize) -> Self {
+        Self {
+            name: PAIR_COVERAGE_FEEDBACK_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            max_entries,
+            last_pairs: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The maximum number of covered entries sampled per run
+    #[must_use]
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    /// The sampled covered entries of the observer, in ascending order
+    fn sample(&self, observer: &O) -> Vec<usize> {
+        let initial = observer.initial();
+        let mut covered: Vec<usize> = (0..observer.usable_count())
+            .filter(|i| *observer.get(*i) != initial)
+            .collect();
+        if covered.len() > self.max_entries {
+            covered.sort_unstable_by_key(|idx| sample_rank(*idx));
+            covered.truncate(self.max_entries);
+            covered.sort_unstable();
+        }
+        covered
+    }
+}
+
+impl<O, S> Feedback<S> for PairCoverageFeedback<O, S>
+where
+    O: MapObserver,
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(PairCoverageFeedbackMetadata::new(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_pairs = None;
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "PairCoverageFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let sampled = self.sample(observer);
+
+        let seen = &state
+            .named_metadata_map()
+            .get::<PairCoverageFeedbackMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "PairCoverageFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?
+            .pairs;
+        let new_pairs: Vec<_> = sampled
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| sampled[i + 1..].iter().map(move |b| (*a, *b)))
+            .filter(|pair| !seen.contains(pair))
+            .collect();
+        if new_pairs.is_empty() {
+            return Ok(false);
+        }
+        self.last_pairs = Some(new_pairs);
+        Ok(true)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        _observers: &OT,
+        _testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(pairs) = self.last_pairs.take() {
+            state
+                .named_metadata_map_mut()
+                .get_mut::<PairCoverageFeedbackMetadata>(&self.name)
+                .ok_or_else(|| {
+                    Error::key_not_found(format!(
+                        "PairCoverageFeedback: metadata {} not found",
+                        self.name
+                    ))
+                })?
+                .pairs
+                .extend(pairs);
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_pairs = None;
+        Ok(())
+    }
+}
+
+impl<O, S> Named for PairCoverageFeedback<O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, S> HasObserverName for PairCoverageFeedback<O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{
+        bolts::tuples::{tuple_list, Named},
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, PairCoverageFeedback, PairCoverageFeedbackMetadata},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasNamedMetadata, NopState},
+    };
+
+    #[test]
+    fn test_pair_coverage_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+        let mut feedback: PairCoverageFeedback<_, NopState<BytesInput>> =
+            PairCoverageFeedback::with_max_entries(&StdMapObserver::owned("map", vec![0_u8; 8]), 3);
+        feedback.init_state(&mut state).unwrap();
+        let name = feedback.name().to_string();
+
+        // saves the run if interesting
+        let mut run = |state: &mut NopState<BytesInput>, covered: &[usize]| {
+            let mut map = vec![0_u8; 8];
+            for idx in covered {
+                map[*idx] = 1;
+            }
+            let observers = tuple_list![StdMapObserver::owned("map", map)];
+            let interesting = feedback
+                .is_interesting(state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            if interesting {
+                feedback
+                    .append_metadata(state, &observers, &mut Testcase::new(input.clone()))
+                    .unwrap();
+            }
+            interesting
+        };
+
+        assert!(run(&mut state, &[0, 1]));
+        assert!(run(&mut state, &[2, 3]));
+        assert!(!run(&mut state, &[0, 1]));
+        assert!(!run(&mut state, &[3, 2]));
+        // a single entry covers no pair
+        assert!(!run(&mut state, &[4]));
+        // all entries were covered before, but never 1 and 2 together
+        assert!(run(&mut state, &[1, 2]));
+        assert!(!run(&mut state, &[1, 2]));
+
+        // only 3 of the 8 entries are sampled, so only 3 new pairs
+        let seen = |state: &NopState<BytesInput>| {
+            state
+                .named_metadata_map()
+                .get::<PairCoverageFeedbackMetadata>(&name)
+                .unwrap()
+                .pairs
+                .len()
+        };
+        let before = seen(&state);
+        assert!(run(&mut state, &[0, 1, 2, 3, 4, 5, 6, 7]));
+        let added = seen(&state) - before;
+        assert!(added > 0 && added <= 3);
+    }
+}
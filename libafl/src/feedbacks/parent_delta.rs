@@ -0,0 +1,260 @@
+//! The [`ParentDeltaFeedback`] records the map entries a new testcase covers on top of its parent,
+//! to guide targeted mutations.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::{Corpus, CorpusId, Testcase},
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName, MapIndexesMetadata},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasCorpus, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata holding the map entries covered by the testcase, but not by its parent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParentDeltaMetadata {
+    /// The id of the parent
+    pub parent: CorpusId,
+    /// The added indices, in ascending order
+    pub added: Vec<usize>,
+}
+
+crate::impl_serdeany!(ParentDeltaMetadata);
+
+/// A [`ParentDeltaFeedback`] wraps another [`Feedback`], usually a map feedback. If the wrapped
+/// feedback finds the input interesting, the entries of the [`MapObserver`] covered by the input
+/// but not by the current (scheduled) testcase, its parent, are added to the testcase as
+/// [`ParentDeltaMetadata`].
+///
+/// The coverage of the parent is taken from its [`MapIndexesMetadata`], so the testcases must have
+/// been saved by a map feedback tracking indexes, see [`crate::feedbacks::MapFeedback::tracking`],
+/// e.g., the wrapped one. Without a current testcase, or without its coverage, no metadata is added.
+///
+/// The result of the wrapped feedback is forwarded unchanged.
+pub struct ParentDeltaFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    observer_name: String,
+    /// The metadata computed in the last run, if the wrapped feedback was interesting
+    last_delta: Option<ParentDeltaMetadata>,
+    name: String,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<A, O, S> ParentDeltaFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`ParentDeltaFeedback`], wrapping the given feedback, and comparing the
+    /// coverage of the given map observer
+    pub fn new(inner: A, observer: &O) -> Self {
+        let name = format!("ParentDelta({})", inner.name());
+        Self {
+            inner,
+            observer_name: observer.name().to_string(),
+            last_delta: None,
+            name,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<A, O, S> Debug for ParentDeltaFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParentDeltaFeedback")
+            .field("name", &self.name)
+            .field("observer_name", &self.observer_name)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, O, S> Named for ParentDeltaFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, O, S> HasObserverName for ParentDeltaFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+impl<A, O, S> Feedback<S> for ParentDeltaFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor + HasCorpus,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_delta = None;
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        if !interesting {
+            return Ok(false);
+        }
+
+        let Some(parent) = *state.corpus().current() else {
+            return Ok(true);
+        };
+        let parent_testcase = state.corpus().get(parent)?.borrow();
+        let Ok(parent_indexes) = parent_testcase.metadata::<MapIndexesMetadata>() else {
+            return Ok(true);
+        };
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "ParentDeltaFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+
+        // the indexes of the map feedbacks are recorded in ascending order
+        let initial = observer.initial();
+        let added = (0..observer.usable_count())
+            .filter(|i| *observer.get(*i) != initial)
+            .filter(|i| parent_indexes.list.binary_search(i).is_err())
+            .collect();
+        self.last_delta = Some(ParentDeltaMetadata { parent, added });
+        Ok(true)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(delta) = self.last_delta.take() {
+            testcase.add_metadata(delta);
+        }
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.last_delta = None;
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            ConstFeedback, Feedback, MapIndexesMetadata, ParentDeltaFeedback, ParentDeltaMetadata,
+        },
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_parent_delta_feedback() {
+        let mut corpus = InMemoryCorpus::new();
+        let mut parent = Testcase::new(BytesInput::new(vec![0]));
+        parent.add_metadata(MapIndexesMetadata::new(vec![0, 2]));
+        let parent = corpus.add(parent).unwrap();
+        let unsaved = corpus.add(Testcase::new(BytesInput::new(vec![1]))).unwrap();
+
+        let mut feedback = ParentDeltaFeedback::new(
+            ConstFeedback::new(true),
+            &StdMapObserver::owned("map", vec![0_u8; 5]),
+        );
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![2]);
+
+        let cases = [
+            // the child adds the entries 1 and 4
+            (Some(parent), vec![1, 3, 1, 0, 1], Some(vec![1, 4])),
+            (Some(parent), vec![1, 0, 0, 0, 0], Some(vec![])),
+            // without a parent, or without its coverage, nothing is recorded
+            (None, vec![1, 1, 1, 1, 1], None),
+            (Some(unsaved), vec![1, 1, 1, 1, 1], None),
+        ];
+        for (current, map, expected) in cases {
+            *state.corpus_mut().current_mut() = current;
+            let observers = tuple_list![StdMapObserver::owned("map", map)];
+            assert!(feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap());
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            let delta = testcase.metadata::<ParentDeltaMetadata>().ok();
+            assert_eq!(delta.map(|delta| delta.added.clone()), expected);
+            if let Some(delta) = delta {
+                assert_eq!(Some(delta.parent), current);
+            }
+        }
+    }
+}
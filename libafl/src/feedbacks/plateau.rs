@@ -0,0 +1,241 @@
+//! The [`PlateauFeedback`] detects coverage plateaus, i.e. long runs of evaluations without novelty,
+//! and notifies the event manager about them.
+
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::{Event, EventFirer},
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// The tag of the [`Event::CustomBuf`] fired by a [`PlateauFeedback`]
+pub const PLATEAU_DETECTED_TAG: &str = "PlateauDetected";
+
+/// A [`PlateauFeedback`] wraps a map feedback, such as a [`crate::feedbacks::MaxMapFeedback`], and
+/// counts the consecutive evaluations the wrapped feedback did not find interesting.
+/// Once the count reaches the threshold, it fires an [`Event::CustomBuf`] tagged with
+/// [`PLATEAU_DETECTED_TAG`], whose payload is the threshold as little-endian `u64`.
+/// The event is fired once per plateau: the count is only reset by the next novel input.
+///
+/// Handlers registered through [`crate::events::HasCustomBufHandlers`] can react to the event,
+/// e.g., by switching the mutation strategy. The result of the wrapped feedback is forwarded
+/// unchanged.
+pub struct PlateauFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    /// The number of consecutive non-novel evaluations considered a plateau
+    threshold: usize,
+    /// The number of consecutive non-novel evaluations so far
+    stale_evaluations: usize,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S> PlateauFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`PlateauFeedback`], wrapping the given feedback, detecting a plateau after
+    /// `threshold` consecutive non-novel evaluations
+    pub fn new(inner: A, threshold: usize) -> Self {
+        let name = format!("Plateau({})", inner.name());
+        Self {
+            inner,
+            threshold,
+            stale_evaluations: 0,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The number of consecutive non-novel evaluations considered a plateau
+    #[must_use]
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// The number of consecutive non-novel evaluations so far
+    #[must_use]
+    pub fn stale_evaluations(&self) -> usize {
+        self.stale_evaluations
+    }
+
+    /// Returns `true` if the fuzzer is currently on a plateau
+    #[must_use]
+    pub fn on_plateau(&self) -> bool {
+        self.stale_evaluations >= self.threshold
+    }
+}
+
+impl<A, S> Debug for PlateauFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlateauFeedback")
+            .field("name", &self.name)
+            .field("threshold", &self.threshold)
+            .field("stale_evaluations", &self.stale_evaluations)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<A, S> Named for PlateauFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S> Feedback<S> for PlateauFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        if interesting {
+            self.stale_evaluations = 0;
+        } else {
+            self.stale_evaluations = self.stale_evaluations.saturating_add(1);
+            if self.stale_evaluations == self.threshold {
+                let buf: Vec<u8> = (self.threshold as u64).to_le_bytes().to_vec();
+                manager.fire(
+                    state,
+                    Event::CustomBuf {
+                        buf,
+                        tag: PLATEAU_DETECTED_TAG.into(),
+                    },
+                )?;
+            }
+        }
+        Ok(interesting)
+    }
+
+    #[inline]
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    #[inline]
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec::Vec};
+    use core::marker::PhantomData;
+
+    use crate::{
+        events::{Event, EventFirer},
+        executors::ExitKind,
+        feedbacks::{ConstFeedback, Feedback, PlateauFeedback, PLATEAU_DETECTED_TAG},
+        inputs::{BytesInput, UsesInput},
+        state::{NopState, UsesState},
+    };
+
+    struct RecordingEventFirer<S> {
+        fired: Vec<String>,
+        phantom: PhantomData<S>,
+    }
+    impl<S> UsesState for RecordingEventFirer<S>
+    where
+        S: UsesInput,
+    {
+        type State = S;
+    }
+    impl<S> EventFirer for RecordingEventFirer<S>
+    where
+        S: UsesInput,
+    {
+        fn fire(&mut self, _state: &mut S, event: Event<S::Input>) -> Result<(), crate::Error> {
+            if let Event::CustomBuf { tag, .. } = event {
+                self.fired.push(tag);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_plateau_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = RecordingEventFirer {
+            fired: Vec::new(),
+            phantom: PhantomData,
+        };
+        let input = BytesInput::new(vec![0]);
+        let mut feedback = PlateauFeedback::new(ConstFeedback::new(false), 3);
+
+        for _ in 0..10 {
+            assert!(!feedback
+                .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                .unwrap());
+        }
+        assert!(feedback.on_plateau());
+        assert_eq!(mgr.fired, vec![PLATEAU_DETECTED_TAG]);
+
+        // novelty resets the count, so the next plateau is reported again
+        feedback.inner = ConstFeedback::new(true);
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap());
+        assert_eq!(feedback.stale_evaluations(), 0);
+        feedback.inner = ConstFeedback::new(false);
+        for _ in 0..3 {
+            feedback
+                .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                .unwrap();
+        }
+        assert_eq!(mgr.fired.len(), 2);
+    }
+}
@@ -0,0 +1,222 @@
+//! The [`QuickMinimizeFeedback`] records a cheaply minimized form of each interesting input, for a
+//! quick triage without running a full minimization stage.
+
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{tuples::Named, AsSlice},
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::{HasTargetBytes, UsesInput},
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata holding the minimized bytes of its input, as computed by a
+/// [`QuickMinimizeFeedback`]. The input of the testcase itself is left untouched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinimizedInputMetadata {
+    /// The minimized bytes
+    pub bytes: Vec<u8>,
+}
+
+crate::impl_serdeany!(MinimizedInputMetadata);
+
+impl MinimizedInputMetadata {
+    /// Creates a new [`MinimizedInputMetadata`]
+    #[must_use]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+}
+
+/// Removes the trailing zero bytes, a minimization for [`QuickMinimizeFeedback`]
+#[must_use]
+pub fn trim_trailing_zeros(bytes: &[u8]) -> Vec<u8> {
+    let len = bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |pos| pos + 1);
+    bytes[..len].to_vec()
+}
+
+/// A [`QuickMinimizeFeedback`] wraps another [`Feedback`]. When the wrapped feedback considers an
+/// input interesting, the target bytes of the input are minimized with the given closure, such as
+/// [`trim_trailing_zeros`], and added to the testcase as [`MinimizedInputMetadata`].
+/// The closure runs once per interesting input, so it should be cheap; it does not execute the
+/// target, so the minimized bytes may not reproduce the behavior of the original input.
+pub struct QuickMinimizeFeedback<A, F, S>
+where
+    A: Feedback<S>,
+    F: Fn(&[u8]) -> Vec<u8>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    /// The minimization
+    minimize: F,
+    /// The minimized bytes of the last interesting input
+    last_minimized: Option<Vec<u8>>,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, F, S> QuickMinimizeFeedback<A, F, S>
+where
+    A: Feedback<S>,
+    F: Fn(&[u8]) -> Vec<u8>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`QuickMinimizeFeedback`], wrapping the given feedback, and recording the
+    /// inputs it considers interesting as minimized by `minimize`
+    pub fn new(inner: A, minimize: F) -> Self {
+        let name = format!("QuickMinimize({})", inner.name());
+        Self {
+            inner,
+            minimize,
+            last_minimized: None,
+            name,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<A, F, S> Debug for QuickMinimizeFeedback<A, F, S>
+where
+    A: Feedback<S>,
+    F: Fn(&[u8]) -> Vec<u8>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuickMinimizeFeedback")
+            .field("name", &self.name)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, F, S> Named for QuickMinimizeFeedback<A, F, S>
+where
+    A: Feedback<S>,
+    F: Fn(&[u8]) -> Vec<u8>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, F, S> Feedback<S> for QuickMinimizeFeedback<A, F, S>
+where
+    A: Feedback<S>,
+    F: Fn(&[u8]) -> Vec<u8>,
+    S: UsesInput + HasClientPerfMonitor,
+    S::Input: HasTargetBytes,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        self.last_minimized = interesting.then(|| (self.minimize)(input.target_bytes().as_slice()));
+        Ok(interesting)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(bytes) = self.last_minimized.take() {
+            testcase.add_metadata(MinimizedInputMetadata::new(bytes));
+        }
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.last_minimized = None;
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            trim_trailing_zeros, ConstFeedback, Feedback, MinimizedInputMetadata,
+            QuickMinimizeFeedback,
+        },
+        inputs::{BytesInput, HasBytesVec},
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_quick_minimize_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![b'a', 0, b'b', 0, 0, 0]);
+
+        let mut feedback =
+            QuickMinimizeFeedback::new(ConstFeedback::new(true), trim_trailing_zeros);
+        assert!(feedback
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap());
+        let mut testcase = Testcase::new(input.clone());
+        feedback
+            .append_metadata(&mut state, &(), &mut testcase)
+            .unwrap();
+        assert_eq!(
+            testcase.metadata::<MinimizedInputMetadata>().unwrap().bytes,
+            [b'a', 0, b'b']
+        );
+        // the original input is untouched
+        assert_eq!(testcase.input().as_ref().unwrap().bytes(), input.bytes());
+        assert_eq!(input.bytes().len(), 6);
+
+        // nothing is recorded if the wrapped feedback does not fire
+        let mut feedback =
+            QuickMinimizeFeedback::new(ConstFeedback::new(false), |bytes: &[u8]| {
+                bytes[..1].to_vec()
+            });
+        assert!(!feedback
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap());
+        let mut testcase = Testcase::new(input);
+        feedback
+            .append_metadata(&mut state, &(), &mut testcase)
+            .unwrap();
+        assert!(testcase.metadata::<MinimizedInputMetadata>().is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! The [`RangeFeedback`] keeps the inputs for which a [`ValueObserver`] reports a value within a band,
+//! for example a latency sweet spot.
+
+use alloc::string::{String, ToString};
+use core::{
+    fmt::Debug,
+    ops::{Bound, RangeBounds},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{ObserversTuple, ValueObserver},
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// A [`RangeFeedback`] reports as interesting if the value of a [`ValueObserver`] lies within the
+/// configured bounds. Both bounds are inclusive by default, see [`RangeFeedback::with_bounds`]
+/// for exclusive or unbounded ends.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct RangeFeedback<T>
+where
+    T: Debug + Serialize,
+{
+    name: String,
+    observer_name: String,
+    /// The lower bound
+    lo: Bound<T>,
+    /// The upper bound
+    hi: Bound<T>,
+}
+
+impl<T> RangeFeedback<T>
+where
+    T: Debug + Serialize + DeserializeOwned + PartialOrd,
+{
+    /// Creates a new [`RangeFeedback`], interesting if `lo <= value <= hi`
+    #[must_use]
+    pub fn new(observer: &ValueObserver<T>, lo: T, hi: T) -> Self {
+        Self::with_bounds(observer, Bound::Included(lo), Bound::Included(hi))
+    }
+
+    /// Creates a new [`RangeFeedback`] with the given (inclusive, exclusive, or unbounded) bounds
+    #[must_use]
+    pub fn with_bounds(observer: &ValueObserver<T>, lo: Bound<T>, hi: Bound<T>) -> Self {
+        Self {
+            name: "rangefeedback_".to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            lo,
+            hi,
+        }
+    }
+
+    /// Returns `true` if the value lies within the bounds
+    #[must_use]
+    pub fn contains(&self, value: &T) -> bool {
+        (self.lo.as_ref(), self.hi.as_ref()).contains(value)
+    }
+}
+
+impl<S, T> Feedback<S> for RangeFeedback<T>
+where
+    S: UsesInput + HasClientPerfMonitor,
+    T: Debug + Serialize + DeserializeOwned + PartialOrd + 'static,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<ValueObserver<T>>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "RangeFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        Ok(self.contains(observer.get_ref()))
+    }
+}
+
+impl<T> Named for RangeFeedback<T>
+where
+    T: Debug + Serialize,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T> HasObserverName for RangeFeedback<T>
+where
+    T: Debug + Serialize,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ops::Bound;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, RangeFeedback},
+        inputs::BytesInput,
+        observers::ValueObserver,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_range_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let initial = 0_u64;
+        let observer = ValueObserver::new("latency", &initial);
+        let mut inclusive = RangeFeedback::new(&observer, 10, 20);
+        let mut exclusive =
+            RangeFeedback::with_bounds(&observer, Bound::Excluded(10), Bound::Excluded(20));
+
+        let mut run = |feedback: &mut RangeFeedback<u64>, value: u64| {
+            feedback
+                .is_interesting(
+                    &mut state,
+                    &mut mgr,
+                    &input,
+                    &tuple_list![ValueObserver::new("latency", &value)],
+                    &ExitKind::Ok,
+                )
+                .unwrap()
+        };
+
+        // below, at the bounds, inside, above
+        for (value, expected_inclusive, expected_exclusive) in [
+            (5, false, false),
+            (10, true, false),
+            (15, true, true),
+            (20, true, false),
+            (25, false, false),
+        ] {
+            assert_eq!(run(&mut inclusive, value), expected_inclusive);
+            assert_eq!(run(&mut exclusive, value), expected_exclusive);
+        }
+
+        // a missing observer is an error
+        assert!(Feedback::<NopState<BytesInput>>::is_interesting(
+            &mut inclusive,
+            &mut NopState::new(),
+            &mut NopEventManager::new(),
+            &BytesInput::new(vec![0]),
+            &(),
+            &ExitKind::Ok
+        )
+        .is_err());
+    }
+}
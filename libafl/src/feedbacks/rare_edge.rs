@@ -0,0 +1,217 @@
+//! The [`RareEdgeFeedback`] only considers inputs interesting that cover rarely hit map entries,
+//! as entries hit by almost every input carry little information.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const RARE_EDGE_FEEDBACK_PREFIX: &str = "rareedgefeedback_metadata_";
+
+/// The state of [`RareEdgeFeedback`]: how many of the evaluated inputs hit each map entry
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct RareEdgeFeedbackMetadata {
+    /// For each map index, the number of evaluated inputs hitting it
+    pub hits: Vec<u64>,
+    /// The number of evaluated inputs
+    pub evaluations: u64,
+}
+
+crate::impl_serdeany!(RareEdgeFeedbackMetadata);
+
+impl RareEdgeFeedbackMetadata {
+    /// Create a new [`RareEdgeFeedbackMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The fraction of the evaluated inputs hitting the given map index, `0.0` before the first
+    /// evaluation
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn frequency(&self, idx: usize) -> f64 {
+        if self.evaluations == 0 {
+            return 0.0;
+        }
+        self.hits.get(idx).copied().unwrap_or(0) as f64 / self.evaluations as f64
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.hits.clear();
+        self.evaluations = 0;
+        Ok(())
+    }
+}
+
+/// A [`RareEdgeFeedback`] counts, for each entry of a [`MapObserver`], how many of the evaluated
+/// inputs hit it. An input is interesting if it hits an entry whose frequency, i.e. the fraction of
+/// all evaluated inputs (including this one) hitting it, is below the rarity threshold.
+///
+/// Inputs hitting a rare entry stay interesting until the entry is hit often enough, so this is
+/// usually combined with a novelty feedback, such as a [`crate::feedbacks::MaxMapFeedback`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RareEdgeFeedback<O, S> {
+    name: String,
+    observer_name: String,
+    /// The frequency below which an entry counts as rare
+    rarity_threshold: f64,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<O, S> RareEdgeFeedback<O, S>
+where
+    O: MapObserver,
+{
+    /// Creates a new [`RareEdgeFeedback`] for the given map observer, where entries hit by less
+    /// than `rarity_threshold` (between `0.0` and `1.0`) of the evaluated inputs count as rare
+    #[must_use]
+    pub fn new(observer: &O, rarity_threshold: f64) -> Self {
+        Self {
+            name: RARE_EDGE_FEEDBACK_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            rarity_threshold,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The frequency below which an entry counts as rare
+    #[must_use]
+    pub fn rarity_threshold(&self) -> f64 {
+        self.rarity_threshold
+    }
+}
+
+impl<O, S> Feedback<S> for RareEdgeFeedback<O, S>
+where
+    O: MapObserver,
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(RareEdgeFeedbackMetadata::new(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "RareEdgeFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let table = state
+            .named_metadata_map_mut()
+            .get_mut::<RareEdgeFeedbackMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "RareEdgeFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+
+        let initial = observer.initial();
+        let len = observer.usable_count();
+        if table.hits.len() < len {
+            table.hits.resize(len, 0);
+        }
+        let covered: Vec<usize> = (0..len).filter(|&i| *observer.get(i) != initial).collect();
+        table.evaluations += 1;
+        for &idx in &covered {
+            table.hits[idx] += 1;
+        }
+
+        Ok(covered
+            .iter()
+            .any(|&idx| table.frequency(idx) < self.rarity_threshold))
+    }
+}
+
+impl<O, S> Named for RareEdgeFeedback<O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, S> HasObserverName for RareEdgeFeedback<O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::{tuple_list, Named},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, RareEdgeFeedback, RareEdgeFeedbackMetadata},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasNamedMetadata, NopState},
+    };
+
+    #[test]
+    fn test_rare_edge_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback: RareEdgeFeedback<_, NopState<BytesInput>> =
+            RareEdgeFeedback::new(&StdMapObserver::owned("map", vec![0_u8; 4]), 0.25);
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |map: [u8; 4]| {
+            let observers = tuple_list![StdMapObserver::owned("map", map.to_vec())];
+            feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+        };
+
+        // edge 0 is hit by every input
+        for _ in 0..10 {
+            assert!(!run([1, 0, 0, 0]));
+        }
+        // edge 1 is rare, edge 0 still is not
+        assert!(run([1, 1, 0, 0]));
+        assert!(!run([3, 0, 0, 0]));
+
+        let table = state
+            .named_metadata_map()
+            .get::<RareEdgeFeedbackMetadata>(feedback.name())
+            .unwrap();
+        assert_eq!(table.evaluations, 12);
+        assert_eq!(table.hits, [12, 1, 0, 0]);
+    }
+}
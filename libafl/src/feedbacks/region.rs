@@ -0,0 +1,273 @@
+//! The [`RegionCoverageFeedback`] attributes new coverage to regions of the input, for example to
+//! the grammar rules of a structured input, to prioritize mutating the regions that made progress.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const REGION_COVERAGE_FEEDBACK_PREFIX: &str = "regioncoveragefeedback_metadata_";
+
+/// The state of [`RegionCoverageFeedback`]: the map entries covered so far
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct RegionCoverageFeedbackMetadata {
+    /// For each map index, if it was covered by an interesting input
+    pub covered: Vec<bool>,
+}
+
+crate::impl_serdeany!(RegionCoverageFeedbackMetadata);
+
+impl RegionCoverageFeedbackMetadata {
+    /// Create a new [`RegionCoverageFeedbackMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the given map index was covered before
+    #[must_use]
+    pub fn is_covered(&self, idx: usize) -> bool {
+        self.covered.get(idx).copied().unwrap_or(false)
+    }
+
+    /// Marks the given map index as covered
+    pub fn set_covered(&mut self, idx: usize) {
+        if idx >= self.covered.len() {
+            self.covered.resize(idx + 1, false);
+        }
+        self.covered[idx] = true;
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.covered.clear();
+        Ok(())
+    }
+}
+
+/// A testcase metadata holding the regions to which the new coverage of the testcase belongs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionCoverageMetadata {
+    /// The region ids, in ascending order and without duplicates
+    pub regions: Vec<usize>,
+}
+
+crate::impl_serdeany!(RegionCoverageMetadata);
+
+/// A [`RegionCoverageFeedback`] considers an input interesting if it covers map entries of a
+/// [`MapObserver`] not covered by any interesting input before. The newly covered entries are
+/// translated into region ids through the mapping given at construction, and recorded as
+/// [`RegionCoverageMetadata`]. Entries without a region still count as new coverage.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegionCoverageFeedback<O, S> {
+    name: String,
+    observer_name: String,
+    /// For each map index, the region it belongs to, if any
+    regions: Vec<Option<usize>>,
+    /// The newly covered entries of the last run
+    last_new_entries: Option<Vec<usize>>,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<O, S> RegionCoverageFeedback<O, S>
+where
+    O: MapObserver,
+{
+    /// Creates a new [`RegionCoverageFeedback`] for the given map observer, where `regions[i]`
+    /// is the region id of map index `i`
+    #[must_use]
+    pub fn new(observer: &O, regions: Vec<Option<usize>>) -> Self {
+        Self {
+            name: REGION_COVERAGE_FEEDBACK_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            regions,
+            last_new_entries: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The region id of the given map index, if any
+    #[must_use]
+    pub fn region_of(&self, idx: usize) -> Option<usize> {
+        self.regions.get(idx).copied().flatten()
+    }
+}
+
+impl<O, S> Feedback<S> for RegionCoverageFeedback<O, S>
+where
+    O: MapObserver,
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(RegionCoverageFeedbackMetadata::new(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "RegionCoverageFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let history = state
+            .named_metadata_map()
+            .get::<RegionCoverageFeedbackMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "RegionCoverageFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+
+        let initial = observer.initial();
+        let new_entries: Vec<usize> = (0..observer.usable_count())
+            .filter(|&i| *observer.get(i) != initial && !history.is_covered(i))
+            .collect();
+        let interesting = !new_entries.is_empty();
+        self.last_new_entries = Some(new_entries);
+        Ok(interesting)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(new_entries) = self.last_new_entries.take() {
+            let history = state
+                .named_metadata_map_mut()
+                .get_mut::<RegionCoverageFeedbackMetadata>(&self.name)
+                .ok_or_else(|| {
+                    Error::key_not_found(format!(
+                        "RegionCoverageFeedback: metadata {} not found",
+                        self.name
+                    ))
+                })?;
+            for &idx in &new_entries {
+                history.set_covered(idx);
+            }
+
+            let mut regions: Vec<usize> = new_entries
+                .iter()
+                .filter_map(|&idx| self.region_of(idx))
+                .collect();
+            regions.sort_unstable();
+            regions.dedup();
+            testcase.add_metadata(RegionCoverageMetadata { regions });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_new_entries = None;
+        Ok(())
+    }
+}
+
+impl<O, S> Named for RegionCoverageFeedback<O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, S> HasObserverName for RegionCoverageFeedback<O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, RegionCoverageFeedback, RegionCoverageMetadata},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_region_coverage_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        // indices 0..2 belong to region 10, 2..4 to region 20, 4 to region 30, 5 to none
+        let regions = vec![Some(10), Some(10), Some(20), Some(20), Some(30), None];
+        let mut feedback: RegionCoverageFeedback<_, NopState<BytesInput>> =
+            RegionCoverageFeedback::new(&StdMapObserver::owned("map", vec![0_u8; 6]), regions);
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |map: Vec<u8>| {
+            let observers = tuple_list![StdMapObserver::owned("map", map)];
+            let interesting = feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap();
+            if !interesting {
+                feedback.discard_metadata(&mut state, &input).unwrap();
+                return None;
+            }
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            Some(
+                testcase
+                    .metadata::<RegionCoverageMetadata>()
+                    .unwrap()
+                    .regions
+                    .clone(),
+            )
+        };
+
+        assert_eq!(run(vec![1, 1, 0, 1, 0, 0]), Some(vec![10, 20]));
+        // already covered
+        assert_eq!(run(vec![1, 0, 0, 1, 0, 0]), None);
+        // only the new entries are attributed
+        assert_eq!(run(vec![1, 1, 1, 0, 1, 0]), Some(vec![20, 30]));
+        // new coverage outside of any region
+        assert_eq!(run(vec![0, 0, 0, 0, 0, 1]), Some(vec![]));
+    }
+}
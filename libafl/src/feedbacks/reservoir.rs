@@ -0,0 +1,310 @@
+//! The [`ReservoirFeedback`] keeps a uniform random sample of bounded size of all the interesting
+//! inputs, for a representative but bounded corpus.
+
+use alloc::string::String;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    corpus::{Corpus, CorpusId, Testcase},
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasCorpus, HasMetadata, HasNamedMetadata, HasRand},
+    Error,
+};
+
+/// The state of [`ReservoirFeedback`]
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct ReservoirFeedbackMetadata {
+    /// The number of inputs found interesting by the wrapped feedback so far
+    pub seen: u64,
+}
+
+crate::impl_serdeany!(ReservoirFeedbackMetadata);
+
+impl ReservoirFeedbackMetadata {
+    /// Create a new [`ReservoirFeedbackMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.seen = 0;
+        Ok(())
+    }
+}
+
+/// A testcase metadata holding the slot of the reservoir the testcase was sampled into
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ReservoirMetadata {
+    /// The slot of the reservoir
+    pub slot: usize,
+    /// The corpus entry previously holding the slot, if any, which should be removed
+    pub evicted: Option<CorpusId>,
+}
+
+crate::impl_serdeany!(ReservoirMetadata);
+
+/// A [`ReservoirFeedback`] wraps another [`Feedback`], and keeps a uniform random sample of
+/// `capacity` of all the inputs the wrapped feedback finds interesting (reservoir sampling).
+///
+/// The first `capacity` inputs fill the reservoir. Afterwards, the `n`-th input replaces a random
+/// slot with probability `capacity / n`, using the random generator of the state, and is not
+/// interesting otherwise. The slot is added to the testcase as [`ReservoirMetadata`], along with the
+/// corpus entry holding it before, found by its own [`ReservoirMetadata`]. This feedback doesn't
+/// remove the evicted entry from the corpus itself, the fuzzer is expected to.
+pub struct ReservoirFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    /// The size of the reservoir
+    capacity: usize,
+    /// The slot taken by the last run, if sampled
+    last_slot: Option<ReservoirMetadata>,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, S> ReservoirFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`ReservoirFeedback`], wrapping the given feedback, and keeping at most
+    /// `capacity` of the inputs it finds interesting
+    pub fn new(inner: A, capacity: usize) -> Self {
+        let name = format!("Reservoir({})", inner.name());
+        Self {
+            inner,
+            capacity,
+            last_slot: None,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The size of the reservoir
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<A, S> Debug for ReservoirFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReservoirFeedback")
+            .field("name", &self.name)
+            .field("capacity", &self.capacity)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, S> Named for ReservoirFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, S> Feedback<S> for ReservoirFeedback<A, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor + HasNamedMetadata + HasRand + HasCorpus,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(ReservoirFeedbackMetadata::new(), &self.name);
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_slot = None;
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        if !interesting {
+            return Ok(false);
+        }
+
+        let meta = state
+            .named_metadata_map_mut()
+            .get_mut::<ReservoirFeedbackMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "ReservoirFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+        meta.seen += 1;
+        let seen = meta.seen;
+
+        let capacity = self.capacity as u64;
+        if seen <= capacity {
+            self.last_slot = Some(ReservoirMetadata {
+                slot: (seen - 1) as usize,
+                evicted: None,
+            });
+            return Ok(true);
+        }
+        let slot = state.rand_mut().below(seen);
+        if slot >= capacity {
+            return Ok(false);
+        }
+
+        // the newest entry holding the slot
+        let slot = slot as usize;
+        let corpus = state.corpus();
+        let evicted = corpus.ids().rev().find(|id| {
+            corpus.get(*id).map_or(false, |testcase| {
+                testcase
+                    .borrow()
+                    .metadata::<ReservoirMetadata>()
+                    .map_or(false, |meta| meta.slot == slot)
+            })
+        });
+        self.last_slot = Some(ReservoirMetadata { slot, evicted });
+        Ok(true)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(slot) = self.last_slot.take() {
+            testcase.add_metadata(slot);
+        }
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.last_slot = None;
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{ConstFeedback, Feedback, ReservoirFeedback, ReservoirMetadata},
+        inputs::BytesInput,
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_reservoir_feedback() {
+        const CAPACITY: usize = 10;
+        const STREAM: usize = 100;
+        const TRIALS: usize = 300;
+
+        let mut feedback = ReservoirFeedback::new(ConstFeedback::new(true), CAPACITY);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut mgr = NopEventManager::new();
+
+        // how often each input of the stream ends up in the reservoir
+        let mut retained = [0_usize; STREAM];
+        for trial in 0..TRIALS {
+            feedback.init_state(&mut state).unwrap();
+            let mut reservoir = Vec::new();
+            for i in 0..STREAM {
+                let input = BytesInput::new(vec![i as u8]);
+                if !feedback
+                    .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+                    .unwrap()
+                {
+                    feedback.discard_metadata(&mut state, &input).unwrap();
+                    continue;
+                }
+                let mut testcase = Testcase::new(input);
+                feedback
+                    .append_metadata(&mut state, &(), &mut testcase)
+                    .unwrap();
+                let meta = *testcase.metadata::<ReservoirMetadata>().unwrap();
+                let previous = reservoir.get(meta.slot).copied();
+                if previous.is_some() {
+                    reservoir[meta.slot] = i;
+                } else {
+                    assert_eq!(meta.slot, reservoir.len());
+                    reservoir.push(i);
+                }
+
+                // in the first trial, keep the corpus in sync, to check the evicted entries
+                if trial == 0 {
+                    let corpus = state.corpus_mut();
+                    assert_eq!(meta.evicted.is_some(), previous.is_some());
+                    if let (Some(evicted), Some(previous)) = (meta.evicted, previous) {
+                        assert_eq!(
+                            corpus.get(evicted).unwrap().borrow().input(),
+                            &Some(BytesInput::new(vec![previous as u8]))
+                        );
+                        corpus.remove(evicted).unwrap();
+                    }
+                    corpus.add(testcase).unwrap();
+                    assert_eq!(corpus.count(), reservoir.len());
+                }
+            }
+            assert_eq!(reservoir.len(), CAPACITY);
+            for i in reservoir {
+                retained[i] += 1;
+            }
+        }
+
+        // each input is retained with probability CAPACITY / STREAM, 30 times in expectation
+        let expected = TRIALS * CAPACITY / STREAM;
+        for chunk in retained.chunks(10) {
+            let sum: usize = chunk.iter().sum();
+            assert!(sum > expected * 10 * 7 / 10 && sum < expected * 10 * 13 / 10);
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! The [`ScalarMaxFeedback`] tracks the maximum of a scalar [`ValueObserver`], such as an execution
+//! time, a memory usage or a recursion depth, and only keeps inputs reaching a new maximum.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Debug, time::Duration};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{ObserversTuple, ValueObserver},
+    state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const SCALAR_MAX_FEEDBACK_PREFIX: &str = "scalarmaxfeedback_metadata_";
+
+/// The state of [`ScalarMaxFeedback`]: the maximum value observed so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct ScalarMaxFeedbackMetadata<T>
+where
+    T: Debug + Serialize + 'static,
+{
+    /// The maximum value, `None` before the first evaluation
+    pub max: Option<T>,
+}
+
+crate::impl_serdeany!(
+    ScalarMaxFeedbackMetadata<T: Debug + Serialize + DeserializeOwned + 'static>,
+    <u8>,<u16>,<u32>,<u64>,<usize>,<i8>,<i16>,<i32>,<i64>,<isize>,<Duration>
+);
+
+impl<T> ScalarMaxFeedbackMetadata<T>
+where
+    T: Debug + Serialize + 'static,
+{
+    /// Creates a new [`struct@ScalarMaxFeedbackMetadata`], without a maximum
+    #[must_use]
+    pub fn new() -> Self {
+        Self { max: None }
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.max = None;
+        Ok(())
+    }
+}
+
+impl<T> Default for ScalarMaxFeedbackMetadata<T>
+where
+    T: Debug + Serialize + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A testcase metadata holding the new maximum reached by the testcase, as observed by a
+/// [`ScalarMaxFeedback`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct ScalarMaxMetadata<T>
+where
+    T: Debug + Serialize + 'static,
+{
+    /// The new maximum
+    pub value: T,
+}
+
+crate::impl_serdeany!(
+    ScalarMaxMetadata<T: Debug + Serialize + DeserializeOwned + 'static>,
+    <u8>,<u16>,<u32>,<u64>,<usize>,<i8>,<i16>,<i32>,<i64>,<isize>,<Duration>
+);
+
+impl<T> ScalarMaxMetadata<T>
+where
+    T: Debug + Serialize + 'static,
+{
+    /// Creates a new [`struct@ScalarMaxMetadata`]
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self { value }
+    }
+}
+
+/// A [`ScalarMaxFeedback`] keeps the maximum of the values of a [`ValueObserver`] in the state,
+/// and considers interesting the inputs producing a value above it.
+/// The new maximum is added to the testcase as [`struct@ScalarMaxMetadata`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct ScalarMaxFeedback<T>
+where
+    T: Debug + Serialize,
+{
+    name: String,
+    observer_name: String,
+    /// The new maximum of the last run, if any
+    last_max: Option<T>,
+}
+
+impl<T> ScalarMaxFeedback<T>
+where
+    T: Ord + Copy + Debug + Serialize + DeserializeOwned + 'static,
+{
+    /// Creates a new [`ScalarMaxFeedback`] for the given [`ValueObserver`].
+    #[must_use]
+    pub fn new(observer: &ValueObserver<T>) -> Self {
+        Self::with_names(
+            &(SCALAR_MAX_FEEDBACK_PREFIX.to_string() + observer.name()),
+            observer.name(),
+        )
+    }
+
+    /// Creates a new [`ScalarMaxFeedback`].
+    /// Setting an observer name that doesn't exist would eventually trigger a panic.
+    #[must_use]
+    pub fn with_names(name: &str, observer_name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            observer_name: observer_name.to_string(),
+            last_max: None,
+        }
+    }
+
+    /// The maximum value observed so far, if any
+    pub fn max<S>(&self, state: &S) -> Result<Option<T>, Error>
+    where
+        S: HasNamedMetadata,
+    {
+        state
+            .named_metadata_map()
+            .get::<ScalarMaxFeedbackMetadata<T>>(&self.name)
+            .map(|meta| meta.max)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "ScalarMaxFeedback: metadata {} not found",
+                    self.name
+                ))
+            })
+    }
+}
+
+impl<S, T> Feedback<S> for ScalarMaxFeedback<T>
+where
+    S: UsesInput + HasClientPerfMonitor + HasNamedMetadata,
+    T: Ord + Copy + Debug + Serialize + DeserializeOwned + 'static,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(ScalarMaxFeedbackMetadata::<T>::new(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<ValueObserver<T>>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "ScalarMaxFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let meta = state
+            .named_metadata_map_mut()
+            .get_mut::<ScalarMaxFeedbackMetadata<T>>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "ScalarMaxFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+
+        let value = *observer.get_ref();
+        if meta.max.map_or(true, |max| value > max) {
+            meta.max = Some(value);
+            self.last_max = Some(value);
+            Ok(true)
+        } else {
+            self.last_max = None;
+            Ok(false)
+        }
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(value) = self.last_max.take() {
+            testcase.add_metadata(ScalarMaxMetadata::new(value));
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_max = None;
+        Ok(())
+    }
+}
+
+impl<T> Named for ScalarMaxFeedback<T>
+where
+    T: Debug + Serialize,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T> HasObserverName for ScalarMaxFeedback<T>
+where
+    T: Debug + Serialize,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, ScalarMaxFeedback, ScalarMaxMetadata},
+        inputs::BytesInput,
+        observers::ValueObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_scalar_max_feedback_u64() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback = ScalarMaxFeedback::new(&ValueObserver::new("rss", &0_u64));
+        Feedback::<NopState<BytesInput>>::init_state(&mut feedback, &mut state).unwrap();
+
+        let mut run = |value: u64| {
+            let observers = tuple_list![ValueObserver::new("rss", &value)];
+            feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+        };
+
+        assert!(run(10));
+        assert!(!run(10));
+        assert!(!run(3));
+        assert!(run(11));
+        assert!(!run(0));
+        assert!(run(1024));
+        assert_eq!(feedback.max(&state).unwrap(), Some(1024));
+
+        let mut testcase = Testcase::new(input);
+        Feedback::<NopState<BytesInput>>::append_metadata(
+            &mut feedback,
+            &mut state,
+            &(),
+            &mut testcase,
+        )
+        .unwrap();
+        assert_eq!(
+            testcase.metadata::<ScalarMaxMetadata<u64>>().unwrap().value,
+            1024
+        );
+    }
+
+    #[test]
+    fn test_scalar_max_feedback_duration() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback = ScalarMaxFeedback::new(&ValueObserver::new("time", &Duration::ZERO));
+        Feedback::<NopState<BytesInput>>::init_state(&mut feedback, &mut state).unwrap();
+
+        let mut run = |value: Duration| {
+            let observers = tuple_list![ValueObserver::new("time", &value)];
+            feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+        };
+
+        assert!(run(Duration::from_millis(5)));
+        assert!(!run(Duration::from_millis(4)));
+        assert!(run(Duration::from_secs(1)));
+        assert!(!run(Duration::from_millis(999)));
+        assert_eq!(feedback.max(&state).unwrap(), Some(Duration::from_secs(1)));
+    }
+}
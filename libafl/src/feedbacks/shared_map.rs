@@ -0,0 +1,342 @@
+//! The [`SharedMapFeedback`] unions the coverage of all fuzzer processes into a single map in a
+//! memory-mapped file, so that novelty is global across processes.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Debug, marker::PhantomData, ptr, slice};
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    os::unix::io::AsRawFd,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    bolts::{tuples::Named, HasLen},
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::HasClientPerfMonitor,
+    Error,
+};
+
+/// The prefix of the feedback names
+pub const SHARED_MAP_FEEDBACK_PREFIX: &str = "sharedmapfeedback_";
+
+/// A file mapped into memory with `MAP_SHARED`, so that all processes mapping it see the same
+/// bytes. Updates are serialized with an exclusive `flock` on the file, see
+/// [`SharedMapFile::with_lock`].
+#[derive(Debug)]
+pub struct SharedMapFile {
+    path: PathBuf,
+    file: File,
+    map: *mut u8,
+    len: usize,
+}
+
+impl SharedMapFile {
+    /// Opens the file at `path`, creating it if needed, and maps its first `len` bytes.
+    /// A shorter file is extended with zeros.
+    pub fn open<P>(path: P, len: usize) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        if len == 0 {
+            return Err(Error::illegal_argument(format!(
+                "Cannot map zero bytes of {}",
+                path.display()
+            )));
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(path)?;
+
+        lock(&file)?;
+        let resized = file.metadata().and_then(|metadata| {
+            if metadata.len() < len as u64 {
+                file.set_len(len as u64)
+            } else {
+                Ok(())
+            }
+        });
+        unlock(&file)?;
+        resized?;
+
+        let map = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if map == libc::MAP_FAILED || map.is_null() {
+            return Err(Error::unknown(format!(
+                "mmap() failed for {}: {}",
+                path.display(),
+                io::Error::last_os_error()
+            )));
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            map: map as *mut u8,
+            len,
+        })
+    }
+
+    /// The path of the mapped file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The number of mapped bytes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no bytes are mapped, which never happens
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Runs `f` on the mapped bytes, holding an exclusive lock on the file, so that no other
+    /// process (or thread with its own [`SharedMapFile`]) locking it accesses them concurrently
+    pub fn with_lock<F, R>(&mut self, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        lock(&self.file)?;
+        let ret = f(unsafe { slice::from_raw_parts_mut(self.map, self.len) });
+        unlock(&self.file)?;
+        Ok(ret)
+    }
+}
+
+impl Drop for SharedMapFile {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.map as *mut _, self.len);
+        }
+    }
+}
+
+/// Takes an exclusive `flock` on the file, blocking until it is available
+fn lock(file: &File) -> Result<(), Error> {
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// Releases the `flock` on the file
+fn unlock(file: &File) -> Result<(), Error> {
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_UN) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// A [`SharedMapFeedback`] ORs the entries of a byte [`MapObserver`] into a [`SharedMapFile`]
+/// shared by all fuzzer processes, and considers an input interesting if it sets bits no process
+/// has set before.
+///
+/// The check and the update happen at once, under the lock of the file, so the novelty of an entry
+/// is claimed by a single process: concurrent processes covering it at the same time don't both keep
+/// their inputs. The entries are claimed as soon as the input is evaluated, even if the input is not
+/// added to the corpus in the end, e.g., if this feedback is combined with a failing one.
+#[derive(Debug)]
+pub struct SharedMapFeedback<O, S> {
+    name: String,
+    observer_name: String,
+    map: SharedMapFile,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<O, S> SharedMapFeedback<O, S>
+where
+    O: MapObserver<Entry = u8>,
+{
+    /// Creates a new [`SharedMapFeedback`] for the given map observer, backed by the file at `path`,
+    /// created if needed
+    pub fn new<P>(observer: &O, path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self {
+            name: SHARED_MAP_FEEDBACK_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            map: SharedMapFile::open(path, observer.len())?,
+            phantom: PhantomData,
+        })
+    }
+
+    /// The shared map
+    #[must_use]
+    pub fn map(&self) -> &SharedMapFile {
+        &self.map
+    }
+}
+
+impl<O, S> Feedback<S> for SharedMapFeedback<O, S>
+where
+    O: MapObserver<Entry = u8>,
+    S: UsesInput + Debug + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "SharedMapFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let initial = observer.initial();
+        let count = observer.usable_count();
+
+        self.map.with_lock(|shared| {
+            let mut interesting = false;
+            for (i, history) in shared.iter_mut().enumerate().take(count) {
+                let value = *observer.get(i);
+                if value != initial && *history | value != *history {
+                    *history |= value;
+                    interesting = true;
+                }
+            }
+            interesting
+        })
+    }
+}
+
+impl<O, S> Named for SharedMapFeedback<O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<O, S> HasObserverName for SharedMapFeedback<O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use std::{sync::mpsc, thread};
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, SharedMapFeedback},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::NopState,
+    };
+
+    const MAP_SIZE: usize = 256;
+
+    /// Evaluates a run covering the given entries with a new feedback on the map at `path`
+    fn runner(path: &std::path::Path) -> impl FnMut(&[(usize, u8)]) -> bool {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+        let mut feedback =
+            SharedMapFeedback::new(&StdMapObserver::owned("map", vec![0_u8; MAP_SIZE]), path)
+                .unwrap();
+        move |entries: &[(usize, u8)]| {
+            let mut map = vec![0_u8; MAP_SIZE];
+            for (idx, value) in entries {
+                map[*idx] = *value;
+            }
+            let observers = tuple_list![StdMapObserver::owned("map", map)];
+            feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn test_shared_map_feedback() {
+        let path = std::env::temp_dir().join(format!(
+            "libafl_shared_map_{}_{:?}",
+            std::process::id(),
+            thread::current().id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let (to_second, from_first) = mpsc::channel();
+        let (to_first, from_second) = mpsc::channel();
+
+        let first = {
+            let path = path.clone();
+            thread::spawn(move || {
+                let mut run = runner(&path);
+                assert!(run(&[(3, 1)]));
+                assert!(!run(&[(3, 1)]));
+                to_second.send(()).unwrap();
+
+                // the novelty found by the second "process" is seen here
+                from_second.recv().unwrap();
+                assert!(!run(&[(5, 1)]));
+                assert!(!run(&[(3, 2)]));
+                assert!(run(&[(3, 4)]));
+            })
+        };
+        let second = {
+            let path = path.clone();
+            thread::spawn(move || {
+                let mut run = runner(&path);
+                from_first.recv().unwrap();
+                // the novelty found by the first "process" is seen here
+                assert!(!run(&[(3, 1)]));
+                assert!(run(&[(5, 1), (3, 1)]));
+                assert!(run(&[(3, 2)]));
+                to_first.send(()).unwrap();
+            })
+        };
+        first.join().unwrap();
+        second.join().unwrap();
+
+        // concurrent runs claim each new entry once
+        let claims: Vec<_> = (0..2)
+            .map(|_| {
+                let path = path.clone();
+                thread::spawn(move || {
+                    let mut run = runner(&path);
+                    (0..MAP_SIZE).filter(|idx| run(&[(*idx, 0x80)])).count()
+                })
+            })
+            .collect();
+        let claimed: usize = claims.into_iter().map(|t| t.join().unwrap()).sum();
+        assert_eq!(claimed, MAP_SIZE);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,210 @@
+//! The [`SizeRatioFeedback`] keeps inputs producing an anomalously large output for their size,
+//! such as zip-bomb-like expansions of compressors or serializers.
+
+use alloc::string::{String, ToString};
+use core::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{tuples::Named, HasLen},
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{ObserversTuple, ValueObserver},
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// The prefix of the feedback names
+pub const SIZE_RATIO_FEEDBACK_PREFIX: &str = "sizeratiofeedback_";
+
+/// A testcase metadata holding the output-to-input size ratio of the testcase, as computed by a
+/// [`SizeRatioFeedback`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SizeRatioMetadata {
+    /// The length of the output
+    pub output_len: usize,
+    /// The ratio of the output length to the input length
+    pub ratio: f64,
+}
+
+crate::impl_serdeany!(SizeRatioMetadata);
+
+/// Computes the ratio of `output_len` to `input_len`. An empty input counts as a single byte, so
+/// that the ratio stays finite.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn size_ratio(output_len: usize, input_len: usize) -> f64 {
+    output_len as f64 / input_len.max(1) as f64
+}
+
+/// A [`SizeRatioFeedback`] reads the length of the output of the target from a [`ValueObserver`],
+/// and considers interesting the inputs whose output-to-input size ratio, see [`size_ratio`],
+/// exceeds the threshold. The ratio is added to the testcase as [`SizeRatioMetadata`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SizeRatioFeedback {
+    name: String,
+    observer_name: String,
+    /// The ratio above which inputs are interesting
+    threshold: f64,
+    /// The metadata of the last run, if its ratio exceeds the threshold
+    last_ratio: Option<SizeRatioMetadata>,
+}
+
+impl SizeRatioFeedback {
+    /// Creates a new [`SizeRatioFeedback`], reading the output length from the given
+    /// [`ValueObserver`], and keeping inputs whose ratio exceeds `threshold`
+    #[must_use]
+    pub fn new(observer: &ValueObserver<usize>, threshold: f64) -> Self {
+        Self::with_names(
+            &(SIZE_RATIO_FEEDBACK_PREFIX.to_string() + observer.name()),
+            observer.name(),
+            threshold,
+        )
+    }
+
+    /// Creates a new [`SizeRatioFeedback`].
+    /// Setting an observer name that doesn't exist would eventually trigger a panic.
+    #[must_use]
+    pub fn with_names(name: &str, observer_name: &str, threshold: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            observer_name: observer_name.to_string(),
+            threshold,
+            last_ratio: None,
+        }
+    }
+
+    /// The ratio above which inputs are interesting
+    #[must_use]
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Sets the ratio above which inputs are interesting
+    pub fn set_threshold(&mut self, threshold: f64) {
+        self.threshold = threshold;
+    }
+}
+
+impl<S> Feedback<S> for SizeRatioFeedback
+where
+    S: UsesInput + HasClientPerfMonitor,
+    S::Input: HasLen,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<ValueObserver<usize>>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "SizeRatioFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+
+        let output_len = *observer.get_ref();
+        let ratio = size_ratio(output_len, input.len());
+        self.last_ratio =
+            (ratio > self.threshold).then_some(SizeRatioMetadata { output_len, ratio });
+        Ok(self.last_ratio.is_some())
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(meta) = self.last_ratio.take() {
+            testcase.add_metadata(meta);
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_ratio = None;
+        Ok(())
+    }
+}
+
+impl Named for SizeRatioFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl HasObserverName for SizeRatioFeedback {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, SizeRatioFeedback, SizeRatioMetadata},
+        inputs::BytesInput,
+        observers::ValueObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_size_ratio_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let mut feedback = SizeRatioFeedback::new(&ValueObserver::new("output_len", &0), 10.0);
+
+        // returns the ratio of the kept testcase, if any
+        let mut run = |input_len: usize, output_len: usize| {
+            let input = BytesInput::new(vec![0; input_len]);
+            let observers = tuple_list![ValueObserver::new("output_len", &output_len)];
+            if !feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+            {
+                return None;
+            }
+            let mut testcase = Testcase::new(input);
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            let meta = *testcase.metadata::<SizeRatioMetadata>().unwrap();
+            assert_eq!(meta.output_len, output_len);
+            Some(meta.ratio)
+        };
+
+        // compressing or mildly expanding is normal
+        assert!(run(100, 40).is_none());
+        assert!(run(100, 1000).is_none());
+        // an expansion bomb
+        assert!((run(100, 1_000_000).unwrap() - 10_000.0).abs() < f64::EPSILON);
+        // an empty input counts as a single byte
+        assert!(run(0, 5).is_none());
+        assert!(run(0, 0).is_none());
+        assert!((run(0, 11).unwrap() - 11.0).abs() < f64::EPSILON);
+    }
+}
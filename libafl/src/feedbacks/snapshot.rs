@@ -0,0 +1,242 @@
+//! Snapshot Map Feedback, attaching a full copy of the coverage map to the first interesting testcases,
+//! for debugging the early behavior of a campaign.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata holding a full copy of the map, as taken by a [`SnapshotMapFeedback`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct MapSnapshotMetadata<T>
+where
+    T: Debug + Serialize + 'static,
+{
+    /// The map contents
+    pub map: Vec<T>,
+}
+
+crate::impl_serdeany!(
+    MapSnapshotMetadata<T: Debug + Serialize + DeserializeOwned + 'static>,
+    <u8>,<u16>,<u32>,<u64>,<i8>,<i16>,<i32>,<i64>,<bool>
+);
+
+/// A [`SnapshotMapFeedback`] wraps another [`Feedback`] and, for the first `max_snapshots` testcases
+/// the wrapped feedback considered interesting, adds a copy of the map as [`struct@MapSnapshotMetadata`].
+/// Afterwards, it stops taking snapshots to save memory.
+///
+/// The result of the wrapped feedback is forwarded unchanged.
+pub struct SnapshotMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    observer_name: String,
+    /// The maximum number of snapshots to take
+    max_snapshots: usize,
+    /// The number of snapshots taken so far
+    snapshots_taken: usize,
+    /// If the wrapped feedback was interesting in the last run
+    inner_interesting: bool,
+    name: String,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<A, O, S> SnapshotMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`SnapshotMapFeedback`], snapshotting the map of the given `observer`
+    /// for the first `max_snapshots` testcases `inner` considers interesting.
+    pub fn new(inner: A, observer: &O, max_snapshots: usize) -> Self {
+        let name = format!("SnapshotMap({})", inner.name());
+        Self {
+            inner,
+            observer_name: observer.name().to_string(),
+            max_snapshots,
+            snapshots_taken: 0,
+            inner_interesting: false,
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The number of snapshots taken so far
+    #[must_use]
+    pub fn snapshots_taken(&self) -> usize {
+        self.snapshots_taken
+    }
+}
+
+impl<A, O, S> Debug for SnapshotMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapshotMapFeedback")
+            .field("name", &self.name)
+            .field("observer_name", &self.observer_name)
+            .field("max_snapshots", &self.max_snapshots)
+            .field("snapshots_taken", &self.snapshots_taken)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<A, O, S> Named for SnapshotMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, O, S> HasObserverName for SnapshotMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+impl<A, O, S> Feedback<S> for SnapshotMapFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    O: MapObserver,
+    O::Entry: Serialize + DeserializeOwned,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.inner_interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        Ok(self.inner_interesting)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if self.inner_interesting && self.snapshots_taken < self.max_snapshots {
+            let observer = observers
+                .match_name::<O>(&self.observer_name)
+                .ok_or_else(|| {
+                    Error::key_not_found(format!(
+                        "SnapshotMapFeedback: observer {} not found",
+                        self.observer_name
+                    ))
+                })?;
+            testcase.add_metadata(MapSnapshotMetadata {
+                map: observer.to_vec(),
+            });
+            self.snapshots_taken += 1;
+        }
+        self.inner_interesting = false;
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner_interesting = false;
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{ConstFeedback, Feedback, MapSnapshotMetadata, SnapshotMapFeedback},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_snapshot_map_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback = SnapshotMapFeedback::new(
+            ConstFeedback::new(true),
+            &StdMapObserver::owned("map", vec![0_u8; 8]),
+            3,
+        );
+
+        let mut snapshots = 0;
+        for i in 0..10_u8 {
+            let mut map = vec![0_u8; 8];
+            map[usize::from(i % 8)] = i + 1;
+            let observers = tuple_list![StdMapObserver::owned("map", map.clone())];
+
+            assert!(feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap());
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+
+            if let Some(meta) = testcase.metadata().get::<MapSnapshotMetadata<u8>>() {
+                assert_eq!(meta.map, map);
+                snapshots += 1;
+            }
+        }
+        assert_eq!(snapshots, 3);
+        assert_eq!(feedback.snapshots_taken(), 3);
+    }
+}
@@ -0,0 +1,292 @@
+//! The [`StableCoverageFeedback`] filters out flaky coverage, by re-running an input and only
+//! keeping it if its new edges reproduce.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName, MapFeedbackMetadata},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasNamedMetadata},
+    Error,
+};
+
+/// A [`StableCoverageFeedback`] wraps a map feedback, such as a [`crate::feedbacks::MaxMapFeedback`].
+/// Before the wrapped feedback is asked, the feedback collects the new edges of the map, i.e., the
+/// entries set in the observer, but not yet in the history of the wrapped feedback.
+/// If the wrapped feedback finds the input interesting, the input is re-executed `repetitions`
+/// times through the provided closure, which returns the map of the re-execution.
+/// The input is only confirmed as interesting if every re-execution covers all new edges.
+///
+/// Each new coverage costs `repetitions` additional executions, so keep the number low for slow
+/// targets. Rejected inputs do not update the history of the wrapped feedback, so a flaky edge
+/// costs these re-executions every time it shows up.
+pub struct StableCoverageFeedback<A, F, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    /// Re-executes the input, returning the resulting map
+    rerun: F,
+    /// How many times an interesting input is re-executed
+    repetitions: usize,
+    /// If the last interesting input was rejected, as its new edges did not reproduce
+    last_rejected: bool,
+    name: String,
+    observer_name: String,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<A, F, O, S> StableCoverageFeedback<A, F, O, S>
+where
+    A: Feedback<S>,
+    F: FnMut(&mut S, &S::Input) -> Result<Vec<O::Entry>, Error>,
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`StableCoverageFeedback`], wrapping the given map feedback for the given
+    /// map observer. `rerun` re-executes the input and returns the map of the re-execution.
+    pub fn new(inner: A, map_observer: &O, repetitions: usize, rerun: F) -> Self {
+        let name = format!("StableCoverage({})", inner.name());
+        Self {
+            inner,
+            rerun,
+            repetitions,
+            last_rejected: false,
+            name,
+            observer_name: map_observer.name().to_string(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// How many times an interesting input is re-executed
+    #[must_use]
+    pub fn repetitions(&self) -> usize {
+        self.repetitions
+    }
+
+    /// Sets how many times an interesting input is re-executed
+    pub fn set_repetitions(&mut self, repetitions: usize) {
+        self.repetitions = repetitions;
+    }
+
+    /// If the last interesting input was rejected, as its new edges did not reproduce
+    #[must_use]
+    pub fn last_rejected(&self) -> bool {
+        self.last_rejected
+    }
+}
+
+impl<A, F, O, S> Debug for StableCoverageFeedback<A, F, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StableCoverageFeedback")
+            .field("name", &self.name)
+            .field("observer_name", &self.observer_name)
+            .field("repetitions", &self.repetitions)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, F, O, S> Named for StableCoverageFeedback<A, F, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, F, O, S> HasObserverName for StableCoverageFeedback<A, F, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+impl<A, F, O, S, T> Feedback<S> for StableCoverageFeedback<A, F, O, S>
+where
+    A: Feedback<S>,
+    F: FnMut(&mut S, &S::Input) -> Result<Vec<T>, Error>,
+    O: MapObserver<Entry = T>,
+    S: UsesInput + HasClientPerfMonitor + HasNamedMetadata,
+    T: Default + Copy + Serialize + DeserializeOwned + PartialEq + Debug + 'static,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_rejected = false;
+
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "StableCoverageFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let history = &state
+            .named_metadata_map()
+            .get::<MapFeedbackMetadata<T>>(self.inner.name())
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "StableCoverageFeedback: map feedback metadata {} not found",
+                    self.inner.name()
+                ))
+            })?
+            .history_map;
+        let initial = observer.initial();
+        let new_edges: Vec<usize> = (0..observer.usable_count())
+            .filter(|&i| {
+                *observer.get(i) != initial && history.get(i).map_or(true, |h| *h == initial)
+            })
+            .collect();
+
+        if !self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?
+        {
+            return Ok(false);
+        }
+
+        for _ in 0..self.repetitions {
+            let map = (self.rerun)(state, input)?;
+            if !new_edges
+                .iter()
+                .all(|&i| map.get(i).map_or(false, |entry| *entry != initial))
+            {
+                self.last_rejected = true;
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, MaxMapFeedback, StableCoverageFeedback},
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::NopState,
+    };
+
+    fn map_with(covered: &[usize]) -> Vec<u8> {
+        let mut map = vec![0_u8; 16];
+        for idx in covered {
+            map[*idx] = 1;
+        }
+        map
+    }
+
+    #[test]
+    fn test_stable_coverage_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+        let observer = StdMapObserver::owned("map", vec![0_u8; 16]);
+
+        // a deterministic target always covers the same edges
+        let mut deterministic = StableCoverageFeedback::new(
+            MaxMapFeedback::<_, NopState<BytesInput>, u8>::with_name("deterministic", &observer),
+            &observer,
+            3,
+            |_state: &mut NopState<BytesInput>, _input: &BytesInput| Ok(map_with(&[1, 2])),
+        );
+        deterministic.init_state(&mut state).unwrap();
+        let observers = tuple_list![StdMapObserver::owned("map", map_with(&[1, 2]))];
+        assert!(deterministic
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert!(!deterministic.last_rejected());
+
+        // a flaky target only covers edge 2 in every other run
+        let mut runs = 0;
+        let mut flaky = StableCoverageFeedback::new(
+            MaxMapFeedback::<_, NopState<BytesInput>, u8>::with_name("flaky", &observer),
+            &observer,
+            3,
+            |_state: &mut NopState<BytesInput>, _input: &BytesInput| {
+                runs += 1;
+                Ok(if runs % 2 == 0 {
+                    map_with(&[1, 2])
+                } else {
+                    map_with(&[1])
+                })
+            },
+        );
+        flaky.init_state(&mut state).unwrap();
+        assert!(!flaky
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert!(flaky.last_rejected());
+
+        // edge 1 is stable, so new coverage limited to it is confirmed
+        let observers = tuple_list![StdMapObserver::owned("map", map_with(&[1]))];
+        assert!(flaky
+            .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+            .unwrap());
+        assert!(!flaky.last_rejected());
+    }
+}
@@ -0,0 +1,246 @@
+//! Tagged OR Feedback, combining two feedbacks like an eager OR while recording which one fired.
+//!
+
+use alloc::string::String;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata recording which side of a [`TaggedOrFeedback`] considered it interesting
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FeedbackOriginMetadata {
+    /// Only the first feedback was interesting
+    First,
+    /// Only the second feedback was interesting
+    Second,
+    /// Both feedbacks were interesting
+    Both,
+}
+
+crate::impl_serdeany!(FeedbackOriginMetadata);
+
+impl FeedbackOriginMetadata {
+    /// Get the origin from the results of the two feedbacks, `None` if neither was interesting
+    #[must_use]
+    pub fn from_results(first: bool, second: bool) -> Option<Self> {
+        match (first, second) {
+            (true, true) => Some(Self::Both),
+            (true, false) => Some(Self::First),
+            (false, true) => Some(Self::Second),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Eager `OR` combination of two feedbacks, attaching a [`FeedbackOriginMetadata`] to new testcases,
+/// recording which of the two feedbacks fired.
+pub struct TaggedOrFeedback<A, B, S>
+where
+    A: Feedback<S>,
+    B: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// First [`Feedback`]
+    pub first: A,
+    /// Second [`Feedback`]
+    pub second: B,
+    /// The origin of the last interesting run
+    origin: Option<FeedbackOriginMetadata>,
+    name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<A, B, S> TaggedOrFeedback<A, B, S>
+where
+    A: Feedback<S>,
+    B: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`TaggedOrFeedback`]
+    pub fn new(first: A, second: B) -> Self {
+        let name = format!("Tagged OR ({},{})", first.name(), second.name());
+        Self {
+            first,
+            second,
+            origin: None,
+            name,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<A, B, S> Debug for TaggedOrFeedback<A, B, S>
+where
+    A: Feedback<S>,
+    B: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaggedOrFeedback")
+            .field("name", &self.name)
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .field("origin", &self.origin)
+            .finish()
+    }
+}
+
+impl<A, B, S> Named for TaggedOrFeedback<A, B, S>
+where
+    A: Feedback<S>,
+    B: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, B, S> Feedback<S> for TaggedOrFeedback<A, B, S>
+where
+    A: Feedback<S>,
+    B: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.first.init_state(state)?;
+        self.second.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let a = self
+            .first
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        let b = self
+            .second
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        self.origin = FeedbackOriginMetadata::from_results(a, b);
+        Ok(a || b)
+    }
+
+    #[cfg(feature = "introspection")]
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting_introspection<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let a = self
+            .first
+            .is_interesting_introspection(state, manager, input, observers, exit_kind)?;
+        let b = self
+            .second
+            .is_interesting_introspection(state, manager, input, observers, exit_kind)?;
+        self.origin = FeedbackOriginMetadata::from_results(a, b);
+        Ok(a || b)
+    }
+
+    #[inline]
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(origin) = self.origin.take() {
+            testcase.add_metadata(origin);
+        }
+        self.first.append_metadata(state, observers, testcase)?;
+        self.second.append_metadata(state, observers, testcase)
+    }
+
+    #[inline]
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.origin = None;
+        self.first.discard_metadata(state, input)?;
+        self.second.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{ConstFeedback, Feedback, FeedbackOriginMetadata, TaggedOrFeedback},
+        inputs::BytesInput,
+        state::{HasMetadata, NopState},
+    };
+
+    fn origin_of(first: bool, second: bool) -> (bool, Option<FeedbackOriginMetadata>) {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback =
+            TaggedOrFeedback::new(ConstFeedback::new(first), ConstFeedback::new(second));
+        let interesting = feedback
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap();
+
+        let mut testcase = Testcase::new(input);
+        feedback
+            .append_metadata(&mut state, &(), &mut testcase)
+            .unwrap();
+        (
+            interesting,
+            testcase.metadata().get::<FeedbackOriginMetadata>().copied(),
+        )
+    }
+
+    #[test]
+    fn test_tagged_or_feedback() {
+        assert_eq!(
+            origin_of(true, false),
+            (true, Some(FeedbackOriginMetadata::First))
+        );
+        assert_eq!(
+            origin_of(false, true),
+            (true, Some(FeedbackOriginMetadata::Second))
+        );
+        assert_eq!(
+            origin_of(true, true),
+            (true, Some(FeedbackOriginMetadata::Both))
+        );
+        assert_eq!(origin_of(false, false), (false, None));
+    }
+}
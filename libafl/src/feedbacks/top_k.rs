@@ -0,0 +1,345 @@
+//! The [`TopKFeedback`] only keeps the `K` inputs with the best scores of a [`ValueObserver`], such
+//! as the deepest stack or the most coverage, evicting the worst of them for each better input.
+
+use alloc::{
+    collections::BinaryHeap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{cmp::Reverse, fmt::Debug, time::Duration};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{ObserversTuple, ValueObserver},
+    state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const TOP_K_FEEDBACK_PREFIX: &str = "topkfeedback_metadata_";
+
+/// The state of [`TopKFeedback`]: a min-heap of the `K` best `(score, id)` entries kept so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "T: Ord + DeserializeOwned")]
+pub struct TopKFeedbackMetadata<T>
+where
+    T: Ord + Debug + Serialize + 'static,
+{
+    /// The kept entries, the worst one on top
+    pub heap: BinaryHeap<Reverse<(T, u64)>>,
+    /// The id of the next kept entry
+    pub next_id: u64,
+}
+
+crate::impl_serdeany!(
+    TopKFeedbackMetadata<T: Ord + Debug + Serialize + DeserializeOwned + 'static>,
+    <u8>,<u16>,<u32>,<u64>,<usize>,<i8>,<i16>,<i32>,<i64>,<isize>,<Duration>
+);
+
+impl<T> TopKFeedbackMetadata<T>
+where
+    T: Ord + Debug + Serialize + 'static,
+{
+    /// Creates a new [`struct@TopKFeedbackMetadata`], without entries
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.heap.clear();
+        self.next_id = 0;
+        Ok(())
+    }
+}
+
+impl<T> Default for TopKFeedbackMetadata<T>
+where
+    T: Ord + Debug + Serialize + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A testcase metadata holding the score of a testcase kept by a [`TopKFeedback`], and the entry
+/// it evicted from the top `K`, if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct TopKMetadata<T>
+where
+    T: Debug + Serialize + 'static,
+{
+    /// The id of the entry of this testcase
+    pub id: u64,
+    /// The score of this testcase
+    pub score: T,
+    /// The id of the evicted entry, whose testcase can be dropped from the corpus, see
+    /// [`TopKFeedback::tag`]
+    pub evicted: Option<u64>,
+}
+
+crate::impl_serdeany!(
+    TopKMetadata<T: Debug + Serialize + DeserializeOwned + 'static>,
+    <u8>,<u16>,<u32>,<u64>,<usize>,<i8>,<i16>,<i32>,<i64>,<isize>,<Duration>
+);
+
+/// A [`TopKFeedback`] keeps the `K` best scores of a [`ValueObserver`] in a bounded min-heap, and
+/// considers interesting the inputs whose score enters it, i.e., inputs scoring higher than the
+/// worst of the `K` entries once the heap is full.
+///
+/// Each kept testcase is tagged with [`TopKFeedback::tag`] of its entry id, and annotated with a
+/// [`struct@TopKMetadata`] holding the id of the entry it evicted, so that the testcase of the
+/// loser can be found with [`crate::corpus::Corpus::find_by_tag`] and dropped.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct TopKFeedback<T>
+where
+    T: Debug + Serialize,
+{
+    name: String,
+    observer_name: String,
+    /// The number of kept entries
+    k: usize,
+    /// The score of the last run, if it enters the top `K`
+    last_score: Option<T>,
+}
+
+impl<T> TopKFeedback<T>
+where
+    T: Ord + Copy + Debug + Serialize + DeserializeOwned + 'static,
+{
+    /// Creates a new [`TopKFeedback`], keeping the `k` best scores of the given [`ValueObserver`]
+    #[must_use]
+    pub fn new(observer: &ValueObserver<T>, k: usize) -> Self {
+        Self::with_names(
+            &(TOP_K_FEEDBACK_PREFIX.to_string() + observer.name()),
+            observer.name(),
+            k,
+        )
+    }
+
+    /// Creates a new [`TopKFeedback`].
+    /// Setting an observer name that doesn't exist would eventually trigger a panic.
+    #[must_use]
+    pub fn with_names(name: &str, observer_name: &str, k: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            observer_name: observer_name.to_string(),
+            k: k.max(1),
+            last_score: None,
+        }
+    }
+
+    /// The number of kept entries
+    #[must_use]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// The tag of the testcase of the entry with the given id
+    #[must_use]
+    pub fn tag(&self, id: u64) -> String {
+        format!("{}_{id}", self.name)
+    }
+
+    /// The `(score, id)` entries currently in the top `K`, best first
+    pub fn entries<S>(&self, state: &S) -> Result<Vec<(T, u64)>, Error>
+    where
+        S: HasNamedMetadata,
+    {
+        let meta = state
+            .named_metadata_map()
+            .get::<TopKFeedbackMetadata<T>>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!("TopKFeedback: metadata {} not found", self.name))
+            })?;
+        let mut entries: Vec<_> = meta.heap.iter().map(|Reverse(entry)| *entry).collect();
+        entries.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(entries)
+    }
+}
+
+impl<S, T> Feedback<S> for TopKFeedback<T>
+where
+    S: UsesInput + HasClientPerfMonitor + HasNamedMetadata,
+    T: Ord + Copy + Debug + Serialize + DeserializeOwned + 'static,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(TopKFeedbackMetadata::<T>::new(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<ValueObserver<T>>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "TopKFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let meta = state
+            .named_metadata_map()
+            .get::<TopKFeedbackMetadata<T>>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!("TopKFeedback: metadata {} not found", self.name))
+            })?;
+
+        let score = *observer.get_ref();
+        let enters = meta.heap.len() < self.k
+            || meta
+                .heap
+                .peek()
+                .map_or(true, |Reverse((worst, _))| score > *worst);
+        self.last_score = enters.then_some(score);
+        Ok(enters)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        // The heap is only updated for inputs actually added to the corpus
+        let Some(score) = self.last_score.take() else {
+            return Ok(());
+        };
+        let meta = state
+            .named_metadata_map_mut()
+            .get_mut::<TopKFeedbackMetadata<T>>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!("TopKFeedback: metadata {} not found", self.name))
+            })?;
+
+        let id = meta.next_id;
+        meta.next_id += 1;
+        meta.heap.push(Reverse((score, id)));
+        let evicted = if meta.heap.len() > self.k {
+            meta.heap.pop().map(|Reverse((_, evicted))| evicted)
+        } else {
+            None
+        };
+
+        testcase.add_tag(&self.tag(id));
+        testcase.add_metadata(TopKMetadata { id, score, evicted });
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_score = None;
+        Ok(())
+    }
+}
+
+impl<T> Named for TopKFeedback<T>
+where
+    T: Debug + Serialize,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T> HasObserverName for TopKFeedback<T>
+where
+    T: Debug + Serialize,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, TopKFeedback, TopKMetadata},
+        inputs::BytesInput,
+        observers::ValueObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_top_k_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let mut feedback = TopKFeedback::new(&ValueObserver::new("depth", &0_u32), 3);
+        Feedback::<NopState<BytesInput>>::init_state(&mut feedback, &mut state).unwrap();
+
+        // returns the metadata of the kept testcase, if the score enters the top 3
+        let mut run = |score: u32| {
+            let observers = tuple_list![ValueObserver::new("depth", &score)];
+            if !feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+            {
+                return None;
+            }
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            let meta = testcase.metadata::<TopKMetadata<u32>>().unwrap().clone();
+            assert!(testcase.has_tag(&feedback.tag(meta.id)));
+            Some((meta.id, meta.evicted))
+        };
+
+        // the heap fills up without evictions
+        assert_eq!(run(5), Some((0, None)));
+        assert_eq!(run(2), Some((1, None)));
+        assert_eq!(run(7), Some((2, None)));
+        // not better than the worst entry, 2
+        assert_eq!(run(1), None);
+        assert_eq!(run(2), None);
+        // evicts the worst entries
+        assert_eq!(run(3), Some((3, Some(1))));
+        assert_eq!(run(10), Some((4, Some(3))));
+        assert_eq!(run(4), None);
+
+        let scores: Vec<_> = feedback
+            .entries(&state)
+            .unwrap()
+            .into_iter()
+            .map(|(score, _)| score)
+            .collect();
+        assert_eq!(scores, [10, 7, 5]);
+        assert_eq!(feedback.entries(&state).unwrap()[0].1, 4);
+    }
+}
@@ -0,0 +1,308 @@
+//! The [`UniqueContributionFeedback`] records the map entries a new testcase covers first in the
+//! whole campaign, its unique contribution to the global coverage.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{MapObserver, ObserversTuple},
+    state::{HasClientPerfMonitor, HasMetadata, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const UNIQUE_CONTRIBUTION_FEEDBACK_PREFIX: &str = "uniquecontributionfeedback_metadata_";
+
+/// The state of [`UniqueContributionFeedback`]: the entries covered by the testcases saved so far
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct UniqueContributionFeedbackMetadata {
+    /// If each map entry is covered, by index
+    pub covered: Vec<bool>,
+}
+
+crate::impl_serdeany!(UniqueContributionFeedbackMetadata);
+
+impl UniqueContributionFeedbackMetadata {
+    /// Create a new [`UniqueContributionFeedbackMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the entry at `idx` is covered
+    #[must_use]
+    pub fn is_covered(&self, idx: usize) -> bool {
+        self.covered.get(idx).copied().unwrap_or(false)
+    }
+
+    /// Marks the given entries as covered
+    pub fn cover(&mut self, indices: &[usize]) {
+        for idx in indices {
+            if *idx >= self.covered.len() {
+                self.covered.resize(*idx + 1, false);
+            }
+            self.covered[*idx] = true;
+        }
+    }
+
+    /// Reset the internal state
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.covered.clear();
+        Ok(())
+    }
+}
+
+/// A testcase metadata holding the map entries first covered in the campaign by the testcase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniqueContributionMetadata {
+    /// The newly covered indices, in ascending order
+    pub indices: Vec<usize>,
+}
+
+crate::impl_serdeany!(UniqueContributionMetadata);
+
+/// A [`UniqueContributionFeedback`] wraps another [`Feedback`], usually a map feedback, and keeps
+/// the union of the entries of a [`MapObserver`] covered by the saved testcases. When a testcase is
+/// saved after the wrapped feedback found it interesting, the entries it covers that were not
+/// covered before are added to it as [`UniqueContributionMetadata`], and to the union.
+///
+/// Unlike [`crate::feedbacks::ParentDeltaFeedback`], the coverage is compared to the whole
+/// campaign, not to the parent. Only the saved testcases count: entries covered by inputs that
+/// were not saved, e.g. crashes, are contributed by the first testcase saved with them.
+///
+/// The result of the wrapped feedback is forwarded unchanged.
+pub struct UniqueContributionFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// The wrapped feedback
+    pub inner: A,
+    observer_name: String,
+    /// The entries uniquely covered by the last run, if the wrapped feedback was interesting
+    last_unique: Option<Vec<usize>>,
+    name: String,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<A, O, S> UniqueContributionFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    /// Creates a new [`UniqueContributionFeedback`], wrapping the given feedback, and comparing the
+    /// coverage of the given map observer
+    pub fn new(inner: A, observer: &O) -> Self {
+        Self {
+            inner,
+            observer_name: observer.name().to_string(),
+            last_unique: None,
+            name: UNIQUE_CONTRIBUTION_FEEDBACK_PREFIX.to_string() + observer.name(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<A, O, S> Debug for UniqueContributionFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UniqueContributionFeedback")
+            .field("name", &self.name)
+            .field("observer_name", &self.observer_name)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, O, S> Named for UniqueContributionFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<A, O, S> HasObserverName for UniqueContributionFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+impl<A, O, S> Feedback<S> for UniqueContributionFeedback<A, O, S>
+where
+    A: Feedback<S>,
+    O: MapObserver,
+    S: UsesInput + HasClientPerfMonitor + HasNamedMetadata,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(UniqueContributionFeedbackMetadata::new(), &self.name);
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_unique = None;
+        let interesting = self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?;
+        if !interesting {
+            return Ok(false);
+        }
+
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "UniqueContributionFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let global = state
+            .named_metadata_map()
+            .get::<UniqueContributionFeedbackMetadata>(&self.name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "UniqueContributionFeedback: metadata {} not found",
+                    self.name
+                ))
+            })?;
+        let initial = observer.initial();
+        self.last_unique = Some(
+            (0..observer.usable_count())
+                .filter(|i| *observer.get(*i) != initial && !global.is_covered(*i))
+                .collect(),
+        );
+        Ok(true)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        state: &mut S,
+        observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(indices) = self.last_unique.take() {
+            state
+                .named_metadata_map_mut()
+                .get_mut::<UniqueContributionFeedbackMetadata>(&self.name)
+                .ok_or_else(|| {
+                    Error::key_not_found(format!(
+                        "UniqueContributionFeedback: metadata {} not found",
+                        self.name
+                    ))
+                })?
+                .cover(&indices);
+            testcase.add_metadata(UniqueContributionMetadata { indices });
+        }
+        self.inner.append_metadata(state, observers, testcase)
+    }
+
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.last_unique = None;
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            Feedback, MaxMapFeedback, UniqueContributionFeedback, UniqueContributionMetadata,
+        },
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_unique_contribution_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let initial = StdMapObserver::owned("map", vec![0_u8; 6]);
+        let mut feedback = UniqueContributionFeedback::new(
+            MaxMapFeedback::<_, NopState<BytesInput>, u8>::new(&initial),
+            &initial,
+        );
+        feedback.init_state(&mut state).unwrap();
+
+        // returns the unique contribution of the run, if saved
+        let mut run = |map: [u8; 6], save: bool| {
+            let observers = tuple_list![StdMapObserver::owned("map", map.to_vec())];
+            if !feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+            {
+                return None;
+            }
+            if !save {
+                feedback.discard_metadata(&mut state, &input).unwrap();
+                return None;
+            }
+            let mut testcase = Testcase::new(input.clone());
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            Some(
+                testcase
+                    .metadata::<UniqueContributionMetadata>()
+                    .unwrap()
+                    .indices
+                    .clone(),
+            )
+        };
+
+        assert_eq!(run([1, 0, 1, 0, 0, 0], true), Some(vec![0, 2]));
+        // a higher hitcount is novel, but adds no new entry
+        assert_eq!(run([2, 0, 1, 0, 0, 0], true), Some(vec![]));
+        // the entries of an unsaved input are contributed by the next saved one
+        assert_eq!(run([0, 0, 0, 1, 0, 0], false), None);
+        assert_eq!(run([0, 1, 0, 1, 0, 1], true), Some(vec![1, 3, 5]));
+        assert_eq!(run([2, 1, 1, 1, 0, 1], true), None);
+    }
+}
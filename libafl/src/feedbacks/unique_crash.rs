@@ -0,0 +1,159 @@
+//! The [`UniqueCrashFeedback`] only keeps crashes at a faulting address never seen before.
+//! This is coarser than a [`crate::feedbacks::NewHashFeedback`] on the backtrace, but needs no backtrace at all.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Debug, marker::PhantomData};
+
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{ObserversTuple, ValueObserver},
+    state::{HasClientPerfMonitor, HasNamedMetadata},
+    Error,
+};
+
+/// The state of [`UniqueCrashFeedback`]
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct UniqueCrashFeedbackMetadata {
+    /// All faulting addresses seen so far
+    pub seen: HashSet<usize>,
+}
+
+crate::impl_serdeany!(UniqueCrashFeedbackMetadata);
+
+/// A [`UniqueCrashFeedback`] reports as interesting if the target crashed at a faulting address
+/// not seen before. The faulting address is read from a [`ValueObserver`], to be filled by the executor
+/// or the crash handler.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UniqueCrashFeedback<S> {
+    name: String,
+    observer_name: String,
+    phantom: PhantomData<S>,
+}
+
+impl<S> UniqueCrashFeedback<S> {
+    /// Creates a new [`UniqueCrashFeedback`], reading the faulting address from the given [`ValueObserver`]
+    #[must_use]
+    pub fn new(observer: &ValueObserver<usize>) -> Self {
+        Self::with_names(
+            &("uniquecrashfeedback_".to_string() + observer.name()),
+            observer.name(),
+        )
+    }
+
+    /// Creates a new [`UniqueCrashFeedback`].
+    /// Setting an observer name that doesn't exist would eventually trigger a panic.
+    #[must_use]
+    pub fn with_names(name: &str, observer_name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            observer_name: observer_name.to_string(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<S> Feedback<S> for UniqueCrashFeedback<S>
+where
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(UniqueCrashFeedbackMetadata::default(), &self.name);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        if *exit_kind != ExitKind::Crash {
+            return Ok(false);
+        }
+
+        let observer = observers
+            .match_name::<ValueObserver<usize>>(&self.observer_name)
+            .ok_or_else(|| {
+                Error::key_not_found(format!(
+                    "UniqueCrashFeedback: observer {} not found",
+                    self.observer_name
+                ))
+            })?;
+        let address = *observer.get_ref();
+
+        let meta = state
+            .named_metadata_map_mut()
+            .get_mut::<UniqueCrashFeedbackMetadata>(&self.name)
+            .unwrap();
+        Ok(meta.seen.insert(address))
+    }
+}
+
+impl<S> Named for UniqueCrashFeedback<S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<S> HasObserverName for UniqueCrashFeedback<S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{Feedback, UniqueCrashFeedback},
+        inputs::BytesInput,
+        observers::ValueObserver,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_unique_crash_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![0]);
+
+        let initial = 0_usize;
+        let mut feedback = UniqueCrashFeedback::new(&ValueObserver::new("fault", &initial));
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |address: usize, exit_kind: ExitKind| {
+            let observers = tuple_list![ValueObserver::new("fault", &address)];
+            feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &exit_kind)
+                .unwrap()
+        };
+
+        assert!(run(0x4141, ExitKind::Crash));
+        // same bug again
+        assert!(!run(0x4141, ExitKind::Crash));
+        assert!(!run(0x4141, ExitKind::Crash));
+        // not a crash
+        assert!(!run(0x4242, ExitKind::Ok));
+        // a new faulting address
+        assert!(run(0x4242, ExitKind::Crash));
+        assert!(!run(0x4242, ExitKind::Crash));
+    }
+}
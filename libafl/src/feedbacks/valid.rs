@@ -0,0 +1,200 @@
+//! The [`ValidFeedback`] checks the validity of inputs with a closure, e.g. against a grammar, to
+//! only keep valid inputs when composed with other feedbacks.
+
+use alloc::string::{String, ToString};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata holding the validity of the input of the testcase, as checked by a
+/// [`ValidFeedback`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ValidityMetadata {
+    /// If the input is valid
+    pub valid: bool,
+}
+
+crate::impl_serdeany!(ValidityMetadata);
+
+/// A [`ValidFeedback`] considers interesting the inputs the given closure considers valid, e.g.
+/// the inputs accepted by a grammar. The validity of the saved testcases is added to them as
+/// [`ValidityMetadata`].
+///
+/// To only keep valid inputs bringing new coverage, put it first in a
+/// [`crate::feedback_and_fast`], so that the coverage feedback only evaluates valid inputs:
+///
+/// ```rust,ignore
+/// let feedback = feedback_and_fast!(
+///     ValidFeedback::new("json", |input: &BytesInput| is_json(input.bytes())),
+///     MaxMapFeedback::new(&edges_observer)
+/// );
+/// ```
+pub struct ValidFeedback<F, S>
+where
+    F: Fn(&S::Input) -> bool,
+    S: UsesInput,
+{
+    name: String,
+    is_valid: F,
+    /// The validity of the last input
+    last_valid: Option<bool>,
+    phantom: PhantomData<S>,
+}
+
+impl<F, S> ValidFeedback<F, S>
+where
+    F: Fn(&S::Input) -> bool,
+    S: UsesInput,
+{
+    /// Creates a new [`ValidFeedback`] with the given name, checking the validity of inputs with
+    /// `is_valid`
+    pub fn new(name: &str, is_valid: F) -> Self {
+        Self {
+            name: name.to_string(),
+            is_valid,
+            last_valid: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, S> Debug for ValidFeedback<F, S>
+where
+    F: Fn(&S::Input) -> bool,
+    S: UsesInput,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidFeedback")
+            .field("name", &self.name)
+            .field("last_valid", &self.last_valid)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, S> Named for ValidFeedback<F, S>
+where
+    F: Fn(&S::Input) -> bool,
+    S: UsesInput,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<F, S> Feedback<S> for ValidFeedback<F, S>
+where
+    F: Fn(&S::Input) -> bool,
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        input: &S::Input,
+        _observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let valid = (self.is_valid)(input);
+        self.last_valid = Some(valid);
+        Ok(valid)
+    }
+
+    fn append_metadata<OT>(
+        &mut self,
+        _state: &mut S,
+        _observers: &OT,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error>
+    where
+        OT: ObserversTuple<S>,
+    {
+        if let Some(valid) = self.last_valid.take() {
+            testcase.add_metadata(ValidityMetadata { valid });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_valid = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::tuples::tuple_list,
+        corpus::Testcase,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedback_and_fast,
+        feedbacks::{Feedback, MaxMapFeedback, ValidFeedback, ValidityMetadata},
+        inputs::{BytesInput, HasBytesVec},
+        observers::StdMapObserver,
+        state::{HasMetadata, NopState},
+    };
+
+    #[test]
+    fn test_valid_feedback() {
+        let mut state: NopState<BytesInput> = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let mut feedback = feedback_and_fast!(
+            ValidFeedback::new("braces", |input: &BytesInput| {
+                input.bytes().first() == Some(&b'{') && input.bytes().last() == Some(&b'}')
+            }),
+            MaxMapFeedback::<_, NopState<BytesInput>, u8>::new(&StdMapObserver::owned(
+                "map",
+                vec![0_u8; 4]
+            ))
+        );
+        feedback.init_state(&mut state).unwrap();
+
+        // saves the run if interesting, returning the recorded validity
+        let mut run = |bytes: &[u8], map: [u8; 4]| {
+            let input = BytesInput::new(bytes.to_vec());
+            let observers = tuple_list![StdMapObserver::owned("map", map.to_vec())];
+            if !feedback
+                .is_interesting(&mut state, &mut mgr, &input, &observers, &ExitKind::Ok)
+                .unwrap()
+            {
+                feedback.discard_metadata(&mut state, &input).unwrap();
+                return None;
+            }
+            let mut testcase = Testcase::new(input);
+            feedback
+                .append_metadata(&mut state, &observers, &mut testcase)
+                .unwrap();
+            Some(testcase.metadata::<ValidityMetadata>().unwrap().valid)
+        };
+
+        // new coverage, but invalid
+        assert_eq!(run(b"{1", [1, 0, 0, 0]), None);
+        assert_eq!(run(b"", [0, 1, 0, 0]), None);
+        // the same coverage, valid
+        assert_eq!(run(b"{1}", [1, 0, 0, 0]), Some(true));
+        // valid, but no new coverage
+        assert_eq!(run(b"{2}", [1, 0, 0, 0]), None);
+        assert_eq!(run(b"{}", [1, 1, 0, 0]), Some(true));
+    }
+}
@@ -418,6 +418,7 @@ where
                 // The input is a solution, add it to the respective corpus
                 let mut testcase = Testcase::with_executions(input, *state.executions());
                 testcase.set_parent_id_optional(*state.corpus().current());
+                testcase.set_objective(true);
                 self.objective_mut()
                     .append_metadata(state, observers, &mut testcase)?;
                 state.solutions_mut().add(testcase)?;
@@ -9,7 +9,7 @@ pub use cmp::*;
 #[cfg(feature = "std")]
 pub mod stdio;
 #[cfg(feature = "std")]
-pub use stdio::{StdErrObserver, StdOutObserver};
+pub use stdio::{HasCapturedOutput, StdErrObserver, StdOutObserver};
 
 #[cfg(feature = "regex")]
 pub mod stacktrace;
@@ -482,6 +482,11 @@ impl TimeObserver {
     pub fn last_runtime(&self) -> &Option<Duration> {
         &self.last_runtime
     }
+
+    /// Sets the runtime of the last execution, e.g., for an execution timed elsewhere.
+    pub fn set_last_runtime(&mut self, last_runtime: Option<Duration>) {
+        self.last_runtime = last_runtime;
+    }
 }
 
 impl<S> Observer<S> for TimeObserver
@@ -9,6 +9,12 @@ use serde::{Deserialize, Serialize};
 
 use crate::{bolts::tuples::Named, inputs::UsesInput, observers::Observer};
 
+/// An observer holding the output captured from the target during its last execution
+pub trait HasCapturedOutput {
+    /// The captured output, if any
+    fn captured_output(&self) -> Option<&[u8]>;
+}
+
 /// An observer that captures stdout of a target.
 /// Only works for supported executors.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -49,6 +55,13 @@ impl Named for StdOutObserver {
     }
 }
 
+impl HasCapturedOutput for StdOutObserver {
+    #[inline]
+    fn captured_output(&self) -> Option<&[u8]> {
+        self.stdout.as_deref()
+    }
+}
+
 /// An observer that captures stderr of a target.
 /// Only works for supported executors.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -88,3 +101,10 @@ impl Named for StdErrObserver {
         &self.name
     }
 }
+
+impl HasCapturedOutput for StdErrObserver {
+    #[inline]
+    fn captured_output(&self) -> Option<&[u8]> {
+        self.stderr.as_deref()
+    }
+}
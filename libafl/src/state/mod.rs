@@ -804,6 +804,7 @@ impl<I, C, R, SC> HasClientPerfMonitor for StdState<I, C, R, SC> {
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct NopState<I> {
     metadata: SerdeAnyMap,
+    named_metadata: NamedSerdeAnyMap,
     rand: StdRand,
     phantom: PhantomData<I>,
 }
@@ -815,6 +816,7 @@ impl<I> NopState<I> {
     pub fn new() -> Self {
         NopState {
             metadata: SerdeAnyMap::new(),
+            named_metadata: NamedSerdeAnyMap::new(),
             rand: StdRand::default(),
             phantom: PhantomData,
         }
@@ -851,6 +853,17 @@ impl<I> HasMetadata for NopState<I> {
     }
 }
 
+#[cfg(test)]
+impl<I> HasNamedMetadata for NopState<I> {
+    fn named_metadata_map(&self) -> &NamedSerdeAnyMap {
+        &self.named_metadata
+    }
+
+    fn named_metadata_map_mut(&mut self) -> &mut NamedSerdeAnyMap {
+        &mut self.named_metadata
+    }
+}
+
 #[cfg(test)]
 impl<I> HasRand for NopState<I> {
     type Rand = StdRand;
@@ -20,7 +20,7 @@ use nix::{
     libc::memset,
     sys::mman::{mmap, MapFlags, ProtFlags},
 };
-use rangemap::RangeSet;
+use rangemap::{RangeMap, RangeSet};
 use serde::{Deserialize, Serialize};
 
 use crate::asan::errors::{AsanError, AsanErrors};
@@ -43,18 +43,53 @@ pub struct Allocator {
     allocations: HashMap<usize, AllocationMetadata>,
     /// The shadow memory pages
     shadow_pages: RangeSet<usize>,
+    /// The kinds of the poisoned regions, see [`Allocator::poison_kind`]
+    poison_kinds: RangeMap<usize, PoisonKind>,
+    /// The number of mapped regions and live allocations referencing each shadow page
+    shadow_page_refs: HashMap<usize, usize>,
     /// A list of allocations
     allocation_queue: BTreeMap<usize, Vec<AllocationMetadata>>,
     /// The size of the largest allocation
     largest_allocation: usize,
     /// The total size of all allocations combined
     total_allocation_size: usize,
+    /// The allocation statistics
+    stats: AsanStats,
     /// The base address of the shadow memory
     base_mapping_addr: usize,
     /// The current mapping address
     current_mapping_addr: usize,
 }
 
+/// The number of guard bytes written right before and after each allocation
+const GUARD_SIZE: usize = 16;
+/// The value of the guard bytes
+const GUARD_MAGIC: u8 = 0xab;
+
+/// The shadow bits to try on 32-bit targets, where the whole address space gets a shadow, see
+/// [`shadow_fits_32`]
+#[cfg(any(test, target_pointer_width = "32"))]
+const SHADOW_BITS_32: [usize; 2] = [28, 29];
+
+/// Checks that the shadow for the given shadow bit covers the whole 32-bit address space without
+/// aliasing, and fits into it along with the allocations mapped right after it.
+/// The shadow starts at `1 << shadow_bit` and keeps `shadow_bit + 1` bits of the address divided
+/// by 8, see `map_to_shadow!`, so it spans `1 << (shadow_bit + 1)` bytes.
+#[cfg(any(test, target_pointer_width = "32"))]
+const fn shadow_fits_32(shadow_bit: usize) -> bool {
+    let shadow_end = 3_u64 << shadow_bit;
+    shadow_bit + 1 >= 32 - 3 && shadow_end < 1 << 32
+}
+
+#[cfg(target_pointer_width = "32")]
+const _: () = {
+    let mut i = 0;
+    while i < SHADOW_BITS_32.len() {
+        assert!(shadow_fits_32(SHADOW_BITS_32[i]));
+        i += 1;
+    }
+};
+
 #[cfg(target_vendor = "apple")]
 const ANONYMOUS_FLAG: MapFlags = MapFlags::MAP_ANON;
 #[cfg(not(target_vendor = "apple"))]
@@ -66,6 +101,39 @@ macro_rules! map_to_shadow {
     };
 }
 
+/// The kind of a poisoned region, with the shadow magic values of upstream ASAN.
+/// The shadow memory itself is a bitmap of the addressable bytes, so the allocator records the
+/// kinds on the side, see [`Allocator::poison_kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum PoisonKind {
+    /// The redzone around a heap allocation, never valid
+    HeapRedzone = 0xfa,
+    /// A released heap allocation
+    Freed = 0xfd,
+    /// A poisoned region of a stack, see [`crate::asan::asan_rt::AsanRuntime::poison_stack`]
+    StackRedzone = 0xf1,
+}
+
+impl PoisonKind {
+    /// The shadow magic value of upstream ASAN for this kind
+    #[must_use]
+    pub fn magic(self) -> u8 {
+        self as u8
+    }
+
+    /// The kind with the given shadow magic value, if any
+    #[must_use]
+    pub fn from_magic(magic: u8) -> Option<Self> {
+        match magic {
+            0xfa => Some(Self::HeapRedzone),
+            0xfd => Some(Self::Freed),
+            0xf1 => Some(Self::StackRedzone),
+            _ => None,
+        }
+    }
+}
+
 /// Metadata for an allocation
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AllocationMetadata {
@@ -85,6 +153,19 @@ pub struct AllocationMetadata {
     pub is_malloc_zero: bool,
 }
 
+/// Statistics about the allocator activity, for example to be shown in a monitor
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsanStats {
+    /// The number of successful allocations
+    pub allocations: usize,
+    /// The number of successful frees
+    pub frees: usize,
+    /// The number of bytes currently allocated and not yet freed
+    pub live_bytes: usize,
+    /// The highest number of live bytes so far
+    pub peak_live_bytes: usize,
+}
+
 impl Allocator {
     /// Creates a new [`Allocator`] (not supported on this platform!)
     #[cfg(not(any(
@@ -120,6 +201,7 @@ impl Allocator {
 
         let mut occupied_ranges: Vec<(usize, usize)> = vec![];
         // max(userspace address) this is usually 0x8_0000_0000_0000 - 1 on x64 linux.
+        #[cfg(target_pointer_width = "64")]
         let mut userspace_max: usize = 0;
 
         // Enumerate memory ranges that are already occupied.
@@ -133,6 +215,7 @@ impl Allocator {
                 let end = start + details.memory_range().size();
                 occupied_ranges.push((start, end));
                 // log::trace!("{:x} {:x}", start, end);
+                #[cfg(target_pointer_width = "64")]
                 let base: usize = 2;
                 // On x64, if end > 2**48, then that's in vsyscall or something.
                 #[cfg(target_arch = "x86_64")]
@@ -150,17 +233,24 @@ impl Allocator {
             });
         }
 
-        let mut maxbit = 0;
-        for power in 1..64 {
-            let base: usize = 2;
-            if base.pow(power) > userspace_max {
-                maxbit = power;
-                break;
+        #[cfg(target_pointer_width = "64")]
+        let shadow_bits = {
+            let mut maxbit = 0;
+            for power in 1..64 {
+                let base: usize = 2;
+                if base.pow(power) > userspace_max {
+                    maxbit = power;
+                    break;
+                }
             }
-        }
+            [maxbit - 4, maxbit - 3, maxbit - 2]
+        };
+        // the 32-bit address space is small enough to shadow entirely
+        #[cfg(target_pointer_width = "32")]
+        let shadow_bits = SHADOW_BITS_32;
 
         {
-            for try_shadow_bit in &[maxbit - 4, maxbit - 3, maxbit - 2] {
+            for try_shadow_bit in &shadow_bits {
                 let addr: usize = 1 << try_shadow_bit;
                 let shadow_start = addr;
                 let shadow_end = addr + addr + addr;
@@ -223,9 +313,12 @@ impl Allocator {
             shadow_bit,
             allocations: HashMap::new(),
             shadow_pages: RangeSet::new(),
+            poison_kinds: RangeMap::new(),
+            shadow_page_refs: HashMap::new(),
             allocation_queue: BTreeMap::new(),
             largest_allocation: 0,
             total_allocation_size: 0,
+            stats: AsanStats::default(),
             base_mapping_addr: addr + addr + addr,
             current_mapping_addr: addr + addr + addr,
         }
@@ -280,6 +373,17 @@ impl Allocator {
 
             return std::ptr::null_mut();
         }
+        if let Some(budget) = self.options.max_live_bytes {
+            if self.stats.live_bytes + size > budget {
+                AsanErrors::get_mut().report_error(AsanError::AllocationBudgetExceeded((
+                    size,
+                    self.stats.live_bytes,
+                    budget,
+                    Backtrace::new(),
+                )));
+                return std::ptr::null_mut();
+            }
+        }
         let rounded_up_size = self.round_up_to_page(size) + 2 * self.page_size;
 
         if self.total_allocation_size + rounded_up_size > self.options.max_total_allocation {
@@ -294,6 +398,12 @@ impl Allocator {
             if self.options.allocation_backtraces {
                 metadata.allocation_site_backtrace = Some(Backtrace::new_unresolved());
             }
+            // the shadow may have been released along with the last allocation sharing it
+            self.map_shadow_for_region(
+                metadata.address,
+                metadata.address + metadata.actual_size,
+                false,
+            );
             metadata
         } else {
             // log::trace!("{:x}, {:x}", self.current_mapping_addr, rounded_up_size);
@@ -332,12 +442,29 @@ impl Allocator {
         };
 
         self.largest_allocation = std::cmp::max(self.largest_allocation, metadata.actual_size);
+        self.stats.allocations += 1;
+        self.stats.live_bytes += size;
+        self.stats.peak_live_bytes =
+            std::cmp::max(self.stats.peak_live_bytes, self.stats.live_bytes);
         // unpoison the shadow memory for the allocation itself
         Self::unpoison(
             map_to_shadow!(self, metadata.address + self.page_size),
             size,
         );
         let address = (metadata.address + self.page_size) as *mut c_void;
+        // fill the redzones around the allocation with guard bytes, checked again on release
+        Self::write_guards(address as usize, size);
+        self.set_poison_kind(
+            metadata.address,
+            self.page_size,
+            Some(PoisonKind::HeapRedzone),
+        );
+        self.set_poison_kind(address as usize, size, None);
+        self.set_poison_kind(
+            address as usize + size,
+            metadata.address + metadata.actual_size - (address as usize + size),
+            Some(PoisonKind::HeapRedzone),
+        );
 
         self.allocations
             .insert(metadata.address + self.page_size, metadata);
@@ -345,25 +472,58 @@ impl Allocator {
         address
     }
 
+    /// The number of bytes currently allocated and not yet freed
+    #[must_use]
+    pub fn live_bytes(&self) -> usize {
+        self.stats.live_bytes
+    }
+
+    /// A snapshot of the allocation statistics
+    #[must_use]
+    pub fn stats(&self) -> AsanStats {
+        self.stats
+    }
+
     /// Releases the allocation at the given address.
     #[allow(clippy::missing_safety_doc)]
     pub unsafe fn release(&mut self, ptr: *mut c_void) {
         //log::trace!("freeing address: {:?}", ptr);
         let Some(metadata) = self.allocations.get_mut(&(ptr as usize)) else {
             if !ptr.is_null() {
-                 AsanErrors::get_mut()
-                    .report_error(AsanError::UnallocatedFree((ptr as usize, Backtrace::new())));
-          }
-             return;
-       };
+                if let Some((base, metadata)) = self.find_live_allocation_containing(ptr as usize) {
+                    AsanErrors::get_mut().report_error(AsanError::InteriorFree((
+                        ptr as usize,
+                        ptr as usize - base,
+                        metadata.clone(),
+                        Backtrace::new(),
+                    )));
+                } else {
+                    AsanErrors::get_mut()
+                        .report_error(AsanError::UnallocatedFree((ptr as usize, Backtrace::new())));
+                }
+            }
+            return;
+        };
 
         if metadata.freed {
+            // already poisoned, and its shadow may be released already
             AsanErrors::get_mut().report_error(AsanError::DoubleFree((
                 ptr as usize,
                 metadata.clone(),
                 Backtrace::new(),
             )));
+            return;
+        }
+        if let Some(corrupted) = Self::find_corrupted_guard(ptr as usize, metadata.size) {
+            AsanErrors::get_mut().report_error(AsanError::GuardCorruption((
+                ptr as usize,
+                corrupted,
+                metadata.clone(),
+                Backtrace::new(),
+            )));
         }
+        self.stats.frees += 1;
+        self.stats.live_bytes -= metadata.size;
         let shadow_mapping_start = map_to_shadow!(self, ptr as usize);
 
         metadata.freed = true;
@@ -373,6 +533,68 @@ impl Allocator {
 
         // poison the shadow memory for the allocation
         Self::poison(shadow_mapping_start, metadata.size);
+        if metadata.size > 0 {
+            self.poison_kinds.insert(
+                ptr as usize..ptr as usize + metadata.size,
+                PoisonKind::Freed,
+            );
+        }
+        let region = metadata.address..metadata.address + metadata.actual_size;
+        // drop the reference of the allocation to its shadow, once it is poisoned
+        self.unmap_shadow_for_region(region.start, region.end);
+    }
+
+    /// Records the kind of the poisoned region of `size` bytes at `start`, or forgets it with `None`.
+    /// This does not change the shadow memory.
+    pub fn set_poison_kind(&mut self, start: usize, size: usize, kind: Option<PoisonKind>) {
+        if size == 0 {
+            return;
+        }
+        match kind {
+            Some(kind) => self.poison_kinds.insert(start..start + size, kind),
+            None => self.poison_kinds.remove(start..start + size),
+        }
+    }
+
+    /// The kind of the poisoned region containing the given address, if recorded, to tell apart
+    /// accesses to freed memory from accesses to memory that was never valid
+    #[must_use]
+    pub fn poison_kind(&self, address: usize) -> Option<PoisonKind> {
+        self.poison_kinds.get(&address).copied()
+    }
+
+    /// Writes the guard bytes right before and after the allocation at the given address.
+    /// These catch corrupting writes the shadow checks missed, for example from uninstrumented code.
+    unsafe fn write_guards(address: usize, size: usize) {
+        memset(
+            (address - GUARD_SIZE) as *mut c_void,
+            GUARD_MAGIC.into(),
+            GUARD_SIZE,
+        );
+        memset(
+            (address + size) as *mut c_void,
+            GUARD_MAGIC.into(),
+            GUARD_SIZE,
+        );
+    }
+
+    /// Returns the address of the first guard byte of the allocation at the given address that
+    /// does not hold [`GUARD_MAGIC`] anymore, if any
+    unsafe fn find_corrupted_guard(address: usize, size: usize) -> Option<usize> {
+        (address - GUARD_SIZE..address)
+            .chain(address + size..address + size + GUARD_SIZE)
+            .find(|addr| *(*addr as *const u8) != GUARD_MAGIC)
+    }
+
+    /// Finds the live allocation whose user range contains the given address,
+    /// returning the base address of the allocation along with its metadata.
+    fn find_live_allocation_containing(&self, ptr: usize) -> Option<(usize, &AllocationMetadata)> {
+        self.allocations
+            .iter()
+            .find(|(base, metadata)| {
+                !metadata.freed && **base < ptr && ptr < **base + metadata.size
+            })
+            .map(|(base, metadata)| (*base, metadata))
     }
 
     /// Finds the metadata for the allocation at the given address.
@@ -405,13 +627,13 @@ impl Allocator {
     /// Resets the allocator contents
     pub fn reset(&mut self) {
         let mut tmp_allocations = Vec::new();
-        for (address, mut allocation) in self.allocations.drain() {
+        for (_, mut allocation) in self.allocations.drain() {
             if !allocation.freed {
                 tmp_allocations.push(allocation);
                 continue;
             }
-            // First poison the memory.
-            Self::poison(map_to_shadow!(self, address), allocation.size);
+            // The memory was already poisoned on release. Its shadow may have been released since,
+            // see [`Allocator::unmap_shadow_for_region`], and still reads as poisoned.
 
             // Reset the allocaiton metadata object
             allocation.size = 0;
@@ -479,6 +701,16 @@ impl Allocator {
         }
     }
 
+    /// Checks if any byte of the given region is poisoned in the shadow memory.
+    /// The shadow memory for the region must already be mapped.
+    #[must_use]
+    pub fn is_poisoned(&self, address: usize, size: usize) -> bool {
+        (address..address + size).any(|addr| {
+            let shadow = unsafe { *(map_to_shadow!(self, addr) as *const u8) };
+            shadow & (0x80 >> (addr & 7)) == 0
+        })
+    }
+
     /// Map shadow memory for a region, and optionally unpoison it
     pub fn map_shadow_for_region(
         &mut self,
@@ -491,9 +723,7 @@ impl Allocator {
         let shadow_mapping_start = map_to_shadow!(self, start);
 
         if !self.pre_allocated_shadow {
-            let shadow_start = self.round_down_to_page(shadow_mapping_start);
-            let shadow_end =
-                self.round_up_to_page((end - start) / 8) + self.page_size + shadow_start;
+            let (shadow_start, shadow_end) = self.shadow_page_range(start, end);
             for range in self.shadow_pages.gaps(&(shadow_start..shadow_end)) {
                 /*
                 log::trace!(
@@ -515,6 +745,9 @@ impl Allocator {
             }
 
             self.shadow_pages.insert(shadow_start..shadow_end);
+            for page in (shadow_start..shadow_end).step_by(self.page_size) {
+                *self.shadow_page_refs.entry(page).or_default() += 1;
+            }
         }
 
         //log::trace!("shadow_mapping_start: {:x}, shadow_size: {:x}", shadow_mapping_start, (end - start) / 8);
@@ -525,6 +758,51 @@ impl Allocator {
         (shadow_mapping_start, (end - start) / 8)
     }
 
+    /// Releases the shadow memory of a region mapped with [`Allocator::map_shadow_for_region`].
+    /// A shadow page is only unmapped once no other region or live allocation references it
+    /// anymore. Its memory is then given back, and a read-only zero page takes its place, so that
+    /// dangling accesses into the region still read it as poisoned.
+    pub fn unmap_shadow_for_region(&mut self, start: usize, end: usize) {
+        if self.pre_allocated_shadow {
+            return;
+        }
+
+        let (shadow_start, shadow_end) = self.shadow_page_range(start, end);
+        for page in (shadow_start..shadow_end).step_by(self.page_size) {
+            let Some(refs) = self.shadow_page_refs.get_mut(&page) else {
+                continue;
+            };
+            *refs -= 1;
+            if *refs == 0 {
+                self.shadow_page_refs.remove(&page);
+                unsafe {
+                    mmap(
+                        NonZeroUsize::new(page),
+                        NonZeroUsize::new(self.page_size).unwrap(),
+                        ProtFlags::PROT_READ,
+                        ANONYMOUS_FLAG
+                            | MapFlags::MAP_FIXED
+                            | MapFlags::MAP_PRIVATE
+                            | MapFlags::MAP_NORESERVE,
+                        -1,
+                        0,
+                    )
+                    .expect("An error occurred while unmapping shadow memory");
+                }
+                self.shadow_pages.remove(page..page + self.page_size);
+            }
+        }
+    }
+
+    /// The page-aligned range of shadow pages backing the region `start..end`
+    #[inline]
+    #[must_use]
+    fn shadow_page_range(&self, start: usize, end: usize) -> (usize, usize) {
+        let shadow_start = self.round_down_to_page(map_to_shadow!(self, start));
+        let shadow_end = self.round_up_to_page((end - start) / 8) + self.page_size + shadow_start;
+        (shadow_start, shadow_end)
+    }
+
     /// Maps the address to a shadow address
     #[inline]
     #[must_use]
@@ -564,3 +842,224 @@ impl Allocator {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use libafl::bolts::cli::FuzzerOptions;
+    use serial_test::serial;
+
+    use super::{shadow_fits_32, Allocator, AsanStats, PoisonKind, SHADOW_BITS_32};
+    use crate::asan::errors::{AsanErrors, ASAN_ERRORS};
+
+    #[test]
+    fn test_shadow_fits_32() {
+        for shadow_bit in SHADOW_BITS_32 {
+            assert!(shadow_fits_32(shadow_bit));
+        }
+        // too low to cover the address space, and too high to fit into it
+        assert!(!shadow_fits_32(27));
+        assert!(!shadow_fits_32(31));
+        // the 64-bit shadow bits are way out of the 32-bit address space
+        assert!(!shadow_fits_32(44));
+    }
+
+    #[test]
+    #[serial]
+    fn test_shared_shadow_page_refcount() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        let mut allocator = Allocator::new(options);
+        // exercise the on-demand shadow mapping
+        allocator.pre_allocated_shadow = false;
+
+        let ptrs: Vec<_> = (0..8).map(|_| unsafe { allocator.alloc(16, 8) }).collect();
+        let first = allocator.allocations[&(ptrs[0] as usize)].address;
+        let shadow_page = allocator.round_down_to_page(allocator.map_to_shadow(first));
+        let sharing: Vec<_> = ptrs
+            .iter()
+            .copied()
+            .filter(|ptr| {
+                let metadata = &allocator.allocations[&(*ptr as usize)];
+                let (start, end) = allocator
+                    .shadow_page_range(metadata.address, metadata.address + metadata.actual_size);
+                (start..end).contains(&shadow_page)
+            })
+            .collect();
+        assert!(
+            sharing.len() > 1,
+            "small allocations should share a shadow page"
+        );
+        assert_eq!(allocator.shadow_page_refs[&shadow_page], sharing.len());
+
+        // the page stays mapped until the last allocation sharing it is freed, reset or not
+        let (last, others) = sharing.split_last().unwrap();
+        for ptr in others {
+            unsafe { allocator.release(*ptr) };
+            assert!(allocator.shadow_pages.contains(&shadow_page));
+        }
+        allocator.reset();
+        assert_eq!(allocator.shadow_page_refs[&shadow_page], 1);
+        assert!(allocator.shadow_pages.contains(&shadow_page));
+        unsafe { allocator.release(*last) };
+        assert!(!allocator.shadow_page_refs.contains_key(&shadow_page));
+        assert!(!allocator.shadow_pages.contains(&shadow_page));
+        // the released page still reads as poisoned
+        assert!(allocator.is_poisoned(*last as usize, 16));
+
+        // reusing a freed allocation maps its shadow again
+        let reused = unsafe { allocator.alloc(16, 8) } as usize;
+        let reused_page = allocator.round_down_to_page(allocator.map_to_shadow(reused));
+        assert!(allocator.shadow_pages.contains(&reused_page));
+        assert!(!allocator.is_poisoned(reused, 16));
+
+        // a page is unmapped once the last region referencing it is released
+        let region = vec![0_u8; 1 << 20];
+        let start = region.as_ptr() as usize;
+        let end = start + region.len();
+        let region_page = allocator.round_down_to_page(allocator.map_to_shadow(start));
+        assert!(!allocator.shadow_page_refs.contains_key(&region_page));
+        allocator.map_shadow_for_region(start, end, true);
+        allocator.map_shadow_for_region(start, end, true);
+        assert_eq!(allocator.shadow_page_refs[&region_page], 2);
+        allocator.unmap_shadow_for_region(start, end);
+        assert!(allocator.shadow_pages.contains(&region_page));
+        allocator.unmap_shadow_for_region(start, end);
+        assert!(!allocator.shadow_page_refs.contains_key(&region_page));
+        assert!(!allocator.shadow_pages.contains(&region_page));
+    }
+
+    #[test]
+    #[serial]
+    fn test_use_after_free_across_reset() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        let mut allocator = Allocator::new(options);
+        allocator.pre_allocated_shadow = false;
+
+        let ptr = unsafe { allocator.alloc(16, 8) } as usize;
+        unsafe { allocator.release(ptr as *mut _) };
+        allocator.reset();
+        // the shadow of the freed allocation is released, but still reads as poisoned, so the
+        // inline check catches a dangling access, and the runtime reports it as a use-after-free
+        assert!(allocator.is_poisoned(ptr, 16));
+        assert_eq!(allocator.poison_kind(ptr), Some(PoisonKind::Freed));
+    }
+
+    #[test]
+    #[serial]
+    fn test_interior_pointer_free() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus", "--continue-on-error"]);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut allocator = Allocator::new(options);
+
+        let ptr = unsafe { allocator.alloc(32, 8) };
+        unsafe { allocator.release(ptr.cast::<u8>().add(4).cast()) };
+        assert_eq!(AsanErrors::get_mut().len(), 1);
+        assert!(!allocator.allocations[&(ptr as usize)].freed);
+
+        // freeing the base pointer is still fine
+        unsafe { allocator.release(ptr) };
+        assert_eq!(AsanErrors::get_mut().len(), 1);
+        unsafe { ASAN_ERRORS = None };
+    }
+
+    #[test]
+    #[serial]
+    fn test_guard_corruption() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus", "--continue-on-error"]);
+        let path = std::env::temp_dir().join(format!("asan_guard_{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        AsanErrors::get_mut().set_report_path(Some(path.clone()));
+        let mut allocator = Allocator::new(options);
+
+        // untouched guards are fine
+        let clean = unsafe { allocator.alloc(32, 8) };
+        unsafe { allocator.release(clean) };
+        assert_eq!(AsanErrors::get_mut().len(), 0);
+
+        // scribble right past the end, unnoticed until the release
+        let ptr = unsafe { allocator.alloc(32, 8) };
+        unsafe { *ptr.cast::<u8>().add(32) = 0 };
+        unsafe { allocator.release(ptr) };
+        assert_eq!(AsanErrors::get_mut().len(), 1);
+        unsafe { ASAN_ERRORS = None };
+
+        let report = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(report.contains("heap guard corruption"));
+        assert!(report.contains(&format!("of allocation at {:#016x}", ptr as usize)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_live_bytes_budget() {
+        let mut options =
+            FuzzerOptions::parse_from(["test", "-i", "corpus", "--continue-on-error"]);
+        options.max_live_bytes = Some(0x100);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut allocator = Allocator::new(options);
+
+        let first = unsafe { allocator.alloc(0x80, 8) };
+        let second = unsafe { allocator.alloc(0x60, 8) };
+        assert!(!first.is_null() && !second.is_null());
+        assert_eq!(allocator.live_bytes(), 0xe0);
+
+        // crossing the budget fails and reports an error
+        let third = unsafe { allocator.alloc(0x40, 8) };
+        assert!(third.is_null());
+        assert_eq!(AsanErrors::get_mut().len(), 1);
+        assert_eq!(allocator.live_bytes(), 0xe0);
+        unsafe { ASAN_ERRORS = None };
+    }
+
+    #[test]
+    #[serial]
+    fn test_live_bytes_release() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        let mut allocator = Allocator::new(options);
+
+        let first = unsafe { allocator.alloc(0x80, 8) };
+        let second = unsafe { allocator.alloc(0x20, 8) };
+        assert_eq!(allocator.live_bytes(), 0xa0);
+
+        unsafe { allocator.release(first) };
+        assert_eq!(allocator.live_bytes(), 0x20);
+
+        // live allocations survive a reset
+        allocator.reset();
+        assert_eq!(allocator.live_bytes(), 0x20);
+
+        unsafe { allocator.release(second) };
+        assert_eq!(allocator.live_bytes(), 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_allocator_stats() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        let mut allocator = Allocator::new(options);
+        assert_eq!(allocator.stats(), AsanStats::default());
+
+        let first = unsafe { allocator.alloc(0x100, 8) };
+        let second = unsafe { allocator.alloc(0x40, 8) };
+        unsafe { allocator.release(first) };
+        let third = unsafe { allocator.alloc(0x20, 8) };
+        unsafe { allocator.release(second) };
+
+        assert_eq!(
+            allocator.stats(),
+            AsanStats {
+                allocations: 3,
+                frees: 2,
+                live_bytes: 0x20,
+                peak_live_bytes: 0x140,
+            }
+        );
+
+        unsafe { allocator.release(third) };
+        let stats = allocator.stats();
+        assert_eq!(stats.frees, 3);
+        assert_eq!(stats.live_bytes, 0);
+        assert_eq!(stats.peak_live_bytes, 0x140);
+    }
+}
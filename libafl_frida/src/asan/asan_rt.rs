@@ -8,9 +8,16 @@ this helps finding mem errors early.
 
 use core::{
     fmt::{self, Debug, Formatter},
+    ops::Range,
     ptr::addr_of_mut,
 };
-use std::{ffi::c_void, num::NonZeroUsize, ptr::write_volatile};
+use std::{
+    ffi::c_void,
+    num::NonZeroUsize,
+    path::PathBuf,
+    ptr::write_volatile,
+    thread::{self, ThreadId},
+};
 
 use backtrace::Backtrace;
 #[cfg(target_arch = "x86_64")]
@@ -36,7 +43,7 @@ use frida_gum::{
     instruction_writer::InstructionWriter, interceptor::Interceptor, stalker::StalkerOutput, Gum,
     Module, ModuleDetails, ModuleMap, NativePointer, RangeDetails,
 };
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use libafl::bolts::{cli::FuzzerOptions, AsSlice};
 #[cfg(unix)]
 use libc::RLIMIT_STACK;
@@ -51,8 +58,10 @@ use rangemap::RangeMap;
 #[cfg(target_arch = "aarch64")]
 use crate::utils::instruction_width;
 use crate::{
-    alloc::Allocator,
-    asan::errors::{AsanError, AsanErrors, AsanReadWriteError, ASAN_ERRORS},
+    alloc::{AllocationMetadata, Allocator, AsanStats, PoisonKind},
+    asan::errors::{
+        AsanError, AsanErrorClass, AsanErrorPolicy, AsanErrors, AsanReadWriteError, ASAN_ERRORS,
+    },
     helper::FridaRuntime,
     utils::writer_register,
 };
@@ -111,6 +120,35 @@ const ASAN_EH_FRAME_FDE_OFFSET: u32 = 20;
 #[cfg(target_arch = "aarch64")]
 const ASAN_EH_FRAME_FDE_ADDRESS_OFFSET: u32 = 28;
 
+/// The size of the largest memory access checked for its alignment, see
+/// [`AsanRuntime::set_check_alignment`]
+const ASAN_MAX_ALIGNMENT_CHECKED_SIZE: usize = 16;
+
+/// The standard allocator function whose semantics apply to a custom allocator function, see
+/// [`AsanRuntime::hook_custom`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomAllocatorKind {
+    /// Like `void *malloc(size_t size)`
+    Malloc,
+    /// Like `void free(void *ptr)`
+    Free,
+    /// Like `void *calloc(size_t nmemb, size_t size)`
+    Calloc,
+    /// Like `void *realloc(void *ptr, size_t size)`
+    Realloc,
+}
+
+/// A custom allocator function registered with [`AsanRuntime::hook_custom`]
+#[derive(Debug)]
+struct CustomAllocator {
+    name: String,
+    /// The address of the original function
+    address: usize,
+    kind: CustomAllocatorKind,
+    /// The runtime the calls are redirected to, set once the function is hooked
+    runtime: *mut AsanRuntime,
+}
+
 /// The frida address sanitizer runtime, providing address sanitization.
 /// When executing in `ASAN`, each memory access will get checked, using frida stalker under the hood.
 /// The runtime can report memory errors that occurred during execution,
@@ -138,7 +176,22 @@ pub struct AsanRuntime {
     options: FuzzerOptions,
     module_map: Option<ModuleMap>,
     suppressed_addresses: Vec<usize>,
+    /// The mapped range of the main executable, if hooked with [`AsanRuntime::hook_main_executable`]
+    main_executable: Option<Range<usize>>,
     shadow_check_func: Option<extern "C" fn(*const c_void, usize) -> bool>,
+    /// The file reports are appended to, set with [`AsanRuntime::set_report_path`]
+    report_path: Option<PathBuf>,
+    /// If misaligned accesses are reported, set with [`AsanRuntime::set_check_alignment`]
+    check_alignment: bool,
+    /// The custom allocator functions, registered with [`AsanRuntime::hook_custom`]
+    custom_allocators: Vec<Box<CustomAllocator>>,
+    /// The stack and tls ranges of the threads registered with [`AsanRuntime::register_thread`]
+    registered_threads: HashMap<ThreadId, Vec<Range<usize>>>,
+    /// The policies per error class, set with [`AsanRuntime::set_error_policy`]
+    error_policies: HashMap<AsanErrorClass, AsanErrorPolicy>,
+    /// The pc and the faulting address of the errors reported by the instrumentation in the
+    /// current run, see [`AsanRuntime::is_reported_fault`]
+    reported_faults: HashSet<(usize, usize)>,
 
     #[cfg(target_arch = "aarch64")]
     eh_frame: [u32; ASAN_EH_FRAME_DWORD_COUNT],
@@ -166,7 +219,12 @@ impl FridaRuntime for AsanRuntime {
         modules_to_instrument: &[&str],
     ) {
         unsafe {
-            ASAN_ERRORS = Some(AsanErrors::new(self.options.clone()));
+            let mut errors = AsanErrors::new(self.options.clone());
+            errors.set_report_path(self.report_path.clone());
+            for (class, policy) in &self.error_policies {
+                errors.set_policy(*class, *policy);
+            }
+            ASAN_ERRORS = Some(errors);
         }
 
         self.generate_instrumentation_blobs();
@@ -283,6 +341,7 @@ impl FridaRuntime for AsanRuntime {
         let slice = target_bytes.as_slice();
         self.poison(slice.as_ptr() as usize, slice.len());
         self.reset_allocations();
+        self.reported_faults.clear();
 
         Ok(())
     }
@@ -290,6 +349,11 @@ impl FridaRuntime for AsanRuntime {
 
 impl AsanRuntime {
     /// Create a new `AsanRuntime`
+    ///
+    /// This installs no signal handlers, so it never alters the process's signal dispositions:
+    /// memory errors are detected by the instrumentation and reported through [`AsanErrors`],
+    /// while crashes are caught by the handlers of the executor wrapped in a
+    /// [`crate::executor::FridaInProcessExecutor`], or by the embedder's own handlers.
     #[must_use]
     pub fn new(options: FuzzerOptions) -> AsanRuntime {
         Self {
@@ -314,19 +378,133 @@ impl AsanRuntime {
             options,
             module_map: None,
             suppressed_addresses: Vec::new(),
+            main_executable: None,
             shadow_check_func: None,
+            report_path: None,
+            check_alignment: false,
+            custom_allocators: Vec::new(),
+            registered_threads: HashMap::new(),
+            error_policies: HashMap::new(),
+            reported_faults: HashSet::new(),
 
             #[cfg(target_arch = "aarch64")]
             eh_frame: [0; ASAN_EH_FRAME_DWORD_COUNT],
         }
     }
 
+    /// Track the allocations done by the main executable too, not just the instrumented modules.
+    /// This resolves the mappings of the main program from `/proc/self/maps`, so that its calls to
+    /// the allocator functions are redirected to the `ASan` allocator, and maps the shadow for them.
+    /// The mapped addresses are used as-is, so this works for both PIE executables (loaded at a random base)
+    /// and non-PIE executables (loaded at their link address).
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn hook_main_executable(&mut self) -> Result<(), libafl::Error> {
+        let exe = std::fs::read_link("/proc/self/exe")?;
+        let maps = std::fs::read_to_string("/proc/self/maps")?;
+        let range = main_executable_range(&maps, &exe.to_string_lossy()).ok_or_else(|| {
+            libafl::Error::illegal_state(format!(
+                "Main executable {} not found in /proc/self/maps",
+                exe.display()
+            ))
+        })?;
+        log::info!(
+            "Hooking main executable {} at {:#x}-{:#x}",
+            exe.display(),
+            range.start,
+            range.end
+        );
+        self.allocator
+            .map_shadow_for_region(range.start, range.end, true);
+        self.main_executable = Some(range);
+        Ok(())
+    }
+
+    /// Hooks custom allocator functions exported by the module at `path`, such as bespoke wrappers
+    /// around the standard allocator, applying the semantics of the given standard allocator
+    /// function to each of them. Like the standard ones, they are redirected to the `ASan`
+    /// allocator when called from an instrumented module.
+    /// The functions are hooked in [`FridaRuntime::init`], so this has to be called before.
+    /// Fails if a function is not exported by the module, in which case none are registered.
+    pub fn hook_custom(
+        &mut self,
+        path: &str,
+        functions: &[(&str, CustomAllocatorKind)],
+    ) -> Result<(), libafl::Error> {
+        let mut custom_allocators = Vec::with_capacity(functions.len());
+        for (name, kind) in functions {
+            let address = Module::find_export_by_name(Some(path), name).ok_or_else(|| {
+                libafl::Error::key_not_found(format!(
+                    "Custom allocator function {name} is not exported by {path}"
+                ))
+            })?;
+            custom_allocators.push(Box::new(CustomAllocator {
+                name: (*name).to_string(),
+                address: address.0 as usize,
+                kind: *kind,
+                runtime: std::ptr::null_mut(),
+            }));
+        }
+        self.custom_allocators.extend(custom_allocators);
+        Ok(())
+    }
+
+    /// Replaces the custom allocator functions registered with [`AsanRuntime::hook_custom`]
+    fn hook_custom_allocators(&mut self, interceptor: &mut Interceptor) {
+        let runtime = self as *mut AsanRuntime;
+        for custom in &mut self.custom_allocators {
+            custom.runtime = runtime;
+            let replacement = match custom.kind {
+                CustomAllocatorKind::Malloc => replacement_custom_malloc as *mut c_void,
+                CustomAllocatorKind::Free => replacement_custom_free as *mut c_void,
+                CustomAllocatorKind::Calloc => replacement_custom_calloc as *mut c_void,
+                CustomAllocatorKind::Realloc => replacement_custom_realloc as *mut c_void,
+            };
+            let data = addr_of_mut!(**custom).cast::<c_void>();
+            if interceptor
+                .replace(
+                    NativePointer(custom.address as *mut c_void),
+                    NativePointer(replacement),
+                    NativePointer(data),
+                )
+                .is_err()
+            {
+                log::warn!("Failed to hook custom allocator function {}", custom.name);
+            }
+        }
+    }
+
+    /// A snapshot of the current mappings of the process, read from `/proc/self/maps`, e.g., to
+    /// resolve addresses to modules in crash reports
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn mappings() -> Result<Vec<MapEntry>, libafl::Error> {
+        Ok(walk_self_maps(&std::fs::read_to_string("/proc/self/maps")?))
+    }
+
+    /// Returns `true` if allocator calls from `address` are handled by the `ASan` allocator
+    fn is_hooked_caller(&self, address: usize) -> bool {
+        self.module_map
+            .as_ref()
+            .unwrap()
+            .find(address as u64)
+            .is_some()
+            || self
+                .main_executable
+                .as_ref()
+                .map_or(false, |range| range.contains(&address))
+    }
+
     /// Reset all allocations so that they can be reused for new allocation requests.
     #[allow(clippy::unused_self)]
     pub fn reset_allocations(&mut self) {
         self.allocator.reset();
     }
 
+    /// A snapshot of the allocation statistics, for example to be shown in a monitor
+    #[must_use]
+    pub fn stats(&self) -> AsanStats {
+        self.allocator.stats()
+    }
+
     /// Gets the allocator
     #[must_use]
     pub fn allocator(&self) -> &Allocator {
@@ -355,6 +533,97 @@ impl AsanRuntime {
         unsafe { &ASAN_ERRORS }
     }
 
+    /// Appends the report of each memory error to the file at the given path, instead of printing
+    /// it to stdout. Each report is flushed before the target is crashed.
+    pub fn set_report_path(&mut self, report_path: PathBuf) {
+        if let Some(errors) = unsafe { ASAN_ERRORS.as_mut() } {
+            errors.set_report_path(Some(report_path.clone()));
+        }
+        self.report_path = Some(report_path);
+    }
+
+    /// Returns `true` if the target keeps running after a memory error is reported, see
+    /// [`AsanRuntime::set_continue_on_error`]
+    #[must_use]
+    pub fn continue_on_error(&self) -> bool {
+        self.options.continue_on_error
+    }
+
+    /// Sets if the target keeps running after a memory error is reported, so that a single run can
+    /// find multiple issues, instead of crashing the target on the first one.
+    /// In this mode, an instruction is reported once per run and faulting address, so repeated
+    /// executions of it, e.g. in a loop, do not report the same error again.
+    ///
+    /// Continuing is best-effort: the target performs the invalid access anyway, which may corrupt
+    /// its memory (or the allocator metadata) or crash it later, in unrelated code.
+    /// Only use this mode if the target can tolerate such corruption, e.g., when it is reset
+    /// after each run.
+    pub fn set_continue_on_error(&mut self, continue_on_error: bool) {
+        if let Some(errors) = unsafe { ASAN_ERRORS.as_mut() } {
+            errors.set_continue_on_error(continue_on_error);
+        }
+        self.options.continue_on_error = continue_on_error;
+    }
+
+    /// Sets what happens on memory errors of the given class, e.g., to crash the target on heap
+    /// overflows, but only log use-after-scope. The policy is consulted once the error is
+    /// classified, and overrides [`AsanRuntime::continue_on_error`] for the class.
+    /// Without a policy, all errors crash the target, as with [`AsanErrorPolicy::Abort`].
+    ///
+    /// As with [`AsanRuntime::set_continue_on_error`], a target kept running after an invalid
+    /// access may corrupt its memory, or crash later.
+    pub fn set_error_policy(&mut self, class: AsanErrorClass, policy: AsanErrorPolicy) {
+        if let Some(errors) = unsafe { ASAN_ERRORS.as_mut() } {
+            errors.set_policy(class, policy);
+        }
+        self.error_policies.insert(class, policy);
+    }
+
+    /// What happens on memory errors of the given class, see [`AsanRuntime::set_error_policy`]
+    #[must_use]
+    pub fn error_policy(&self, class: AsanErrorClass) -> AsanErrorPolicy {
+        match self.error_policies.get(&class) {
+            Some(policy) => *policy,
+            None if self.options.continue_on_error => AsanErrorPolicy::Report,
+            None => AsanErrorPolicy::Abort,
+        }
+    }
+
+    /// Returns `true` if misaligned accesses are reported, see [`AsanRuntime::set_check_alignment`]
+    #[must_use]
+    pub fn check_alignment(&self) -> bool {
+        self.check_alignment
+    }
+
+    /// Sets if the instrumentation additionally reports accesses of 2, 4, 8 or 16 bytes that are
+    /// in bounds, but not naturally aligned, as targets may assume the natural alignment of atomics.
+    /// The check is part of the instrumentation blobs, so it must be set before [`FridaRuntime::init`].
+    pub fn set_check_alignment(&mut self, check_alignment: bool) {
+        self.check_alignment = check_alignment;
+    }
+
+    /// The mask of the low address bits that must be clear for an access of `width` bytes, if the
+    /// instrumentation checks its alignment
+    fn alignment_check_mask(&self, width: usize) -> Option<u64> {
+        (self.check_alignment && is_alignment_checked(width)).then_some(width as u64 - 1)
+    }
+
+    /// Returns `true` if a trapped access of `width` bytes at `address` was only reported for its
+    /// alignment: a misaligned access to poisoned memory is reported as out-of-bounds instead
+    fn is_misaligned_in_bounds(&self, address: usize, width: usize) -> bool {
+        self.check_alignment
+            && is_misaligned_access(address, width)
+            && !self.is_poisoned(address, width)
+    }
+
+    /// Returns `true` if the fault of the instruction at `pc` accessing `fault_address` was already
+    /// reported in the current run, recording it otherwise. When the target keeps running after an
+    /// error, the faulting instruction may run again, e.g. in a loop, and is only reported once.
+    /// The shadow is left untouched, so other accesses to the same memory are still reported.
+    fn is_reported_fault(&mut self, pc: usize, fault_address: usize) -> bool {
+        !self.reported_faults.insert((pc, fault_address))
+    }
+
     /// Make sure the specified memory is unpoisoned
     #[allow(clippy::unused_self)]
     pub fn unpoison(&mut self, address: usize, size: usize) {
@@ -367,6 +636,35 @@ impl AsanRuntime {
         Allocator::poison(self.allocator.map_to_shadow(address), size);
     }
 
+    /// Poison a region of the current thread's stack, e.g., when a local variable goes out of
+    /// scope, so that later accesses are reported by the inline shadow check as use-after-scope.
+    /// Like ASAN's `__asan_poison_stack_memory`, the region should be 8-byte aligned.
+    ///
+    /// The caller must ensure the region lies within a stack registered through
+    /// [`AsanRuntime::register_thread`], whose shadow memory is already mapped.
+    pub fn poison_stack(&mut self, address: usize, size: usize) {
+        self.poison(address, size);
+        self.allocator
+            .set_poison_kind(address, size, Some(PoisonKind::StackRedzone));
+    }
+
+    /// Unpoison a region of the current thread's stack previously poisoned with
+    /// [`AsanRuntime::poison_stack`], e.g., when its scope is entered again.
+    ///
+    /// The caller must ensure the region lies within a stack registered through
+    /// [`AsanRuntime::register_thread`], whose shadow memory is already mapped.
+    pub fn unpoison_stack(&mut self, address: usize, size: usize) {
+        self.unpoison(address, size);
+        self.allocator.set_poison_kind(address, size, None);
+    }
+
+    /// Checks if any byte of the specified memory is poisoned.
+    /// The shadow memory for the region must already be mapped.
+    #[must_use]
+    pub fn is_poisoned(&self, address: usize, size: usize) -> bool {
+        self.allocator.is_poisoned(address, size)
+    }
+
     /// Add a stalked address to real address mapping.
     #[inline]
     pub fn add_stalked_address(&mut self, stalked: usize, real: usize) {
@@ -390,30 +688,54 @@ impl AsanRuntime {
 
     /// Register the current thread with the runtime, implementing shadow memory for its stack and
     /// tls mappings.
-    #[allow(clippy::unused_self)]
+    /// Registering a thread again only maps the shadow for its ranges that changed since, releasing
+    /// the shadow of the previous ones. Returns `false` if nothing changed.
     #[cfg(not(target_os = "ios"))]
-    pub fn register_thread(&mut self) {
+    pub fn register_thread(&mut self) -> bool {
         let (stack_start, stack_end) = Self::current_stack();
-        self.allocator
-            .map_shadow_for_region(stack_start, stack_end, true);
-
         let (tls_start, tls_end) = Self::current_tls();
-        self.allocator
-            .map_shadow_for_region(tls_start, tls_end, true);
+        if !self.register_thread_ranges(vec![stack_start..stack_end, tls_start..tls_end]) {
+            return false;
+        }
         log::info!(
             "registering thread with stack {stack_start:x}:{stack_end:x} and tls {tls_start:x}:{tls_end:x}"
         );
+        true
     }
 
     /// Register the current thread with the runtime, implementing shadow memory for its stack mapping.
-    #[allow(clippy::unused_self)]
+    /// Registering a thread again only maps the shadow for its stack if it changed since, releasing
+    /// the shadow of the previous one. Returns `false` if nothing changed.
     #[cfg(target_os = "ios")]
-    pub fn register_thread(&mut self) {
+    pub fn register_thread(&mut self) -> bool {
         let (stack_start, stack_end) = Self::current_stack();
-        self.allocator
-            .map_shadow_for_region(stack_start, stack_end, true);
-
+        if !self.register_thread_ranges(vec![stack_start..stack_end]) {
+            return false;
+        }
         log::info!("registering thread with stack {stack_start:x}:{stack_end:x}");
+        true
+    }
+
+    /// Records the ranges of the current thread, mapping the shadow for the ones it did not have
+    /// before, and unmapping the shadow of the ones it does not have anymore.
+    /// Returns `false` if the ranges did not change.
+    fn register_thread_ranges(&mut self, ranges: Vec<Range<usize>>) -> bool {
+        let previous = self
+            .registered_threads
+            .insert(thread::current().id(), ranges.clone())
+            .unwrap_or_default();
+        if previous == ranges {
+            return false;
+        }
+        for range in previous.iter().filter(|range| !ranges.contains(range)) {
+            self.allocator
+                .unmap_shadow_for_region(range.start, range.end);
+        }
+        for range in ranges.iter().filter(|range| !previous.contains(range)) {
+            self.allocator
+                .map_shadow_for_region(range.start, range.end, true);
+        }
+        true
     }
 
     /// Get the maximum stack size for the current stack
@@ -531,7 +853,7 @@ impl AsanRuntime {
                         let mut invocation = Interceptor::current_invocation();
                         let this = &mut *(invocation.replacement_data().unwrap().0 as *mut AsanRuntime);
                         let real_address = this.real_address_for_stalked(invocation.return_addr());
-                        if !this.suppressed_addresses.contains(&real_address) && this.module_map.as_ref().unwrap().find(real_address as u64).is_some() {
+                        if !this.suppressed_addresses.contains(&real_address) && this.is_hooked_caller(real_address) {
                             this.[<hook_ $name>]($($param),*)
                         } else {
                             $name($($param),*)
@@ -577,6 +899,14 @@ impl AsanRuntime {
         hook_func!(None, realloc, (ptr: *mut c_void, size: usize), *mut c_void);
         hook_func_with_check!(None, free, (ptr: *mut c_void), ());
         #[cfg(not(target_vendor = "apple"))]
+        hook_func!(
+            None,
+            reallocarray,
+            (ptr: *mut c_void, nmemb: usize, size: usize),
+            *mut c_void
+        );
+        hook_func!(None, aligned_alloc, (alignment: usize, size: usize), *mut c_void);
+        #[cfg(not(target_vendor = "apple"))]
         hook_func!(None, memalign, (size: usize, alignment: usize), *mut c_void);
         hook_func!(
             None,
@@ -586,6 +916,7 @@ impl AsanRuntime {
         );
         #[cfg(not(target_vendor = "apple"))]
         hook_func!(None, malloc_usable_size, (ptr: *mut c_void), usize);
+        self.hook_custom_allocators(&mut interceptor);
 
         for libname in ["libc++.so", "libc++.so.1", "libc++_shared.so"] {
             for export in Module::enumerate_exports(libname) {
@@ -922,6 +1253,9 @@ impl AsanRuntime {
 
         let fault_address = self.regs[17];
         let actual_pc = self.regs[18];
+        if self.is_reported_fault(actual_pc, fault_address) {
+            return;
+        }
 
         let cs = Capstone::new()
             .x86()
@@ -943,11 +1277,13 @@ impl AsanRuntime {
         let operands = cs.insn_detail(insn).unwrap().arch_detail().operands();
 
         let mut access_type: Option<RegAccessType> = None;
+        let mut access_size = 0;
         let mut regs: Option<(RegId, RegId, i64)> = None;
         for operand in operands {
             if let X86Operand(x86operand) = operand {
                 if let X86OperandType::Mem(mem) = x86operand.op_type {
                     access_type = x86operand.access;
+                    access_size = usize::from(x86operand.size);
                     regs = Some((mem.base(), mem.index(), mem.disp()));
                 }
             }
@@ -977,8 +1313,19 @@ impl AsanRuntime {
             };
 
             // log::trace!("{:x}", base_value);
+            let poison_kind = self.allocator.poison_kind(fault_address);
             #[allow(clippy::option_if_let_else)]
-            let error = if fault_address >= stack_start && fault_address < stack_end {
+            let error = if self.is_misaligned_in_bounds(fault_address, access_size) {
+                AsanError::MisalignedAccess((
+                    access_size,
+                    self.regs,
+                    actual_pc,
+                    (base_idx, index_idx, disp as usize, fault_address),
+                    backtrace,
+                ))
+            } else if (fault_address >= stack_start && fault_address < stack_end)
+                || poison_kind == Some(PoisonKind::StackRedzone)
+            {
                 match access_type {
                     Some(typ) => match typ {
                         RegAccessType::ReadOnly => AsanError::StackOobRead((
@@ -1015,16 +1362,17 @@ impl AsanRuntime {
                                 metadata: metadata.clone(),
                                 backtrace,
                             };
+                            let use_after_free = is_use_after_free(poison_kind, metadata);
                             match typ {
                                 RegAccessType::ReadOnly => {
-                                    if metadata.freed {
+                                    if use_after_free {
                                         AsanError::ReadAfterFree(asan_readwrite_error)
                                     } else {
                                         AsanError::OobRead(asan_readwrite_error)
                                     }
                                 }
                                 _ => {
-                                    if metadata.freed {
+                                    if use_after_free {
                                         AsanError::WriteAfterFree(asan_readwrite_error)
                                     } else {
                                         AsanError::OobWrite(asan_readwrite_error)
@@ -1103,6 +1451,7 @@ impl AsanRuntime {
 
         let detail = cs.insn_detail(insn).unwrap();
         let arch_detail = detail.arch_detail();
+        let access_size = instruction_width(insn, &arch_detail.operands()) as usize;
         let (mut base_reg, mut index_reg, displacement) =
             if let Arm64Operand(arm64operand) = arch_detail.operands().last().unwrap() {
                 if let Arm64OperandType::Mem(opmem) = arm64operand.op_type {
@@ -1170,12 +1519,31 @@ impl AsanRuntime {
             }
             fault_address += self.regs[index_reg as usize];
         }
+        if self.is_reported_fault(actual_pc, fault_address) {
+            return;
+        }
 
         let backtrace = Backtrace::new();
 
         let (stack_start, stack_end) = Self::current_stack();
+        let poison_kind = self.allocator.poison_kind(fault_address);
         #[allow(clippy::option_if_let_else)]
-        let error = if fault_address >= stack_start && fault_address < stack_end {
+        let error = if self.is_misaligned_in_bounds(fault_address, access_size) {
+            AsanError::MisalignedAccess((
+                access_size,
+                self.regs,
+                actual_pc,
+                (
+                    Some(base_reg),
+                    Some(index_reg),
+                    displacement as usize,
+                    fault_address,
+                ),
+                backtrace,
+            ))
+        } else if (fault_address >= stack_start && fault_address < stack_end)
+            || poison_kind == Some(PoisonKind::StackRedzone)
+        {
             if insn.mnemonic().unwrap().starts_with('l') {
                 AsanError::StackOobRead((
                     self.regs,
@@ -1217,13 +1585,14 @@ impl AsanRuntime {
                 metadata: metadata.clone(),
                 backtrace,
             };
+            let use_after_free = is_use_after_free(poison_kind, metadata);
             if insn.mnemonic().unwrap().starts_with('l') {
-                if metadata.freed {
+                if use_after_free {
                     AsanError::ReadAfterFree(asan_readwrite_error)
                 } else {
                     AsanError::OobRead(asan_readwrite_error)
                 }
-            } else if metadata.freed {
+            } else if use_after_free {
                 AsanError::WriteAfterFree(asan_readwrite_error)
             } else {
                 AsanError::OobWrite(asan_readwrite_error)
@@ -1787,6 +2156,7 @@ impl AsanRuntime {
                 ;   xor     eax, eax
                 ;   cmp     edx, ecx
                 ;   je      >done
+                ;fail:
                 ;   lea     rsi, [>done] // leap 10 bytes forward
                 ;   nop // jmp takes 10 bytes at most so we want to allocate 10 bytes buffer (?)
                 ;   nop
@@ -1802,6 +2172,13 @@ impl AsanRuntime {
             );};
         }
         let mut ops = dynasmrt::VecAssembler::<dynasmrt::x64::X64Relocation>::new(0);
+        if let Some(mask) = self.alignment_check_mask(1 << (bit - 1)) {
+            dynasm!(ops
+                ; .arch x64
+                ; test rdi, mask as i32
+                ; jnz >fail
+            );
+        }
         shadow_check!(ops, bit);
         let ops_vec = ops.finalize().unwrap();
         ops_vec[..ops_vec.len() - 10].to_vec().into_boxed_slice() //????
@@ -1831,6 +2208,7 @@ impl AsanRuntime {
                 ; ldp x2, x3, [sp], 0x10
                 ; tbnz x1, #$bit, >done
 
+                ; fail:
                 ; adr x1, >done
                 ; nop // will be replaced by b to report
                 ; done:
@@ -1838,6 +2216,14 @@ impl AsanRuntime {
         }
 
         let mut ops = dynasmrt::VecAssembler::<dynasmrt::aarch64::Aarch64Relocation>::new(0);
+        if let Some(mask) = self.alignment_check_mask(1 << bit) {
+            // x1 is scratch, and the flags must be left untouched
+            dynasm!(ops
+                ; .arch aarch64
+                ; and x1, x0, #mask
+                ; cbnz x1, >fail
+            );
+        }
         shadow_check!(ops, bit);
         let ops_vec = ops.finalize().unwrap();
         ops_vec[..ops_vec.len() - 4].to_vec().into_boxed_slice()
@@ -2706,3 +3092,795 @@ impl AsanRuntime {
         ));
     }
 }
+
+/// Returns `true` if accesses of `width` bytes are checked for their alignment, i.e., if `width` is
+/// the size of an atomic
+fn is_alignment_checked(width: usize) -> bool {
+    width.is_power_of_two() && (2..=ASAN_MAX_ALIGNMENT_CHECKED_SIZE).contains(&width)
+}
+
+/// Returns `true` if an access of `width` bytes at `address` is checked for its alignment, and is
+/// not naturally aligned
+fn is_misaligned_access(address: usize, width: usize) -> bool {
+    is_alignment_checked(width) && address & (width - 1) != 0
+}
+
+/// Returns `true` if a faulting access to a heap allocation is a use-after-free, decoding the
+/// [`PoisonKind`] of the faulting address: an access to the redzone of a live allocation is
+/// out-of-bounds, even if the closest allocation found for it was freed. Without a recorded kind,
+/// this falls back to the state of the closest allocation.
+fn is_use_after_free(poison_kind: Option<PoisonKind>, metadata: &AllocationMetadata) -> bool {
+    match poison_kind {
+        Some(PoisonKind::Freed) => true,
+        Some(PoisonKind::HeapRedzone | PoisonKind::StackRedzone) => false,
+        None => metadata.freed,
+    }
+}
+
+/// Returns the runtime of the custom allocator function being invoked, the address of the original
+/// function, and if the call is to be handled by the `ASan` allocator
+unsafe fn custom_allocator_invocation<'a>() -> (&'a mut AsanRuntime, usize, bool) {
+    let mut invocation = Interceptor::current_invocation();
+    let custom = &*(invocation.replacement_data().unwrap().0 as *const CustomAllocator);
+    let this = &mut *custom.runtime;
+    let real_address = this.real_address_for_stalked(invocation.return_addr());
+    let hooked =
+        !this.suppressed_addresses.contains(&real_address) && this.is_hooked_caller(real_address);
+    (this, custom.address, hooked)
+}
+
+unsafe extern "C" fn replacement_custom_malloc(size: usize) -> *mut c_void {
+    let (this, original, hooked) = custom_allocator_invocation();
+    if hooked {
+        this.hook_malloc(size)
+    } else {
+        let original: extern "C" fn(usize) -> *mut c_void = std::mem::transmute(original);
+        original(size)
+    }
+}
+
+unsafe extern "C" fn replacement_custom_free(ptr: *mut c_void) {
+    let (this, original, _hooked) = custom_allocator_invocation();
+    // like `free`, everything allocated by the `ASan` allocator is released by it
+    if this.hook_check_free(ptr) {
+        this.hook_free(ptr);
+    } else {
+        let original: extern "C" fn(*mut c_void) = std::mem::transmute(original);
+        original(ptr);
+    }
+}
+
+unsafe extern "C" fn replacement_custom_calloc(nmemb: usize, size: usize) -> *mut c_void {
+    let (this, original, hooked) = custom_allocator_invocation();
+    if hooked {
+        this.hook_calloc(nmemb, size)
+    } else {
+        let original: extern "C" fn(usize, usize) -> *mut c_void = std::mem::transmute(original);
+        original(nmemb, size)
+    }
+}
+
+unsafe extern "C" fn replacement_custom_realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+    let (this, original, hooked) = custom_allocator_invocation();
+    if hooked {
+        this.hook_realloc(ptr, size)
+    } else {
+        let original: extern "C" fn(*mut c_void, usize) -> *mut c_void =
+            std::mem::transmute(original);
+        original(ptr, size)
+    }
+}
+
+/// A mapping of the process, as listed in `/proc/self/maps`, see [`AsanRuntime::mappings`]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapEntry {
+    /// The start address of the mapping
+    pub start: usize,
+    /// The end address of the mapping, exclusive
+    pub end: usize,
+    /// The permissions of the mapping, e.g. `r-xp`
+    pub perms: String,
+    /// The offset of the mapping in the mapped file
+    pub offset: usize,
+    /// The path of the mapped file, or the name of a special mapping, such as `[stack]`, if any
+    pub path: Option<String>,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl MapEntry {
+    /// Returns `true` if `address` lies in this mapping
+    #[must_use]
+    pub fn contains(&self, address: usize) -> bool {
+        (self.start..self.end).contains(&address)
+    }
+}
+
+/// Parses the contents of `/proc/self/maps`, skipping malformed lines
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn walk_self_maps(maps: &str) -> Vec<MapEntry> {
+    maps.lines()
+        .filter_map(|line| {
+            // address perms offset dev inode [pathname]
+            let mut fields = line.split_whitespace();
+            let (start, end) = fields.next()?.split_once('-')?;
+            let perms = fields.next()?;
+            let offset = fields.next()?;
+            let (_dev, _inode) = (fields.next()?, fields.next()?);
+            let path = fields.collect::<Vec<_>>().join(" ");
+            Some(MapEntry {
+                start: usize::from_str_radix(start, 16).ok()?,
+                end: usize::from_str_radix(end, 16).ok()?,
+                perms: perms.to_string(),
+                offset: usize::from_str_radix(offset, 16).ok()?,
+                path: (!path.is_empty()).then_some(path),
+            })
+        })
+        .collect()
+}
+
+/// Finds the range spanned by all the mappings of the executable at `path` in the contents of `/proc/self/maps`.
+/// The range starts at the mapping at file offset `0`, i.e., the load base of the executable.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn main_executable_range(maps: &str, path: &str) -> Option<Range<usize>> {
+    let mut base = None;
+    let mut end = 0;
+    for entry in walk_self_maps(maps) {
+        if entry.path.as_deref() != Some(path) {
+            continue;
+        }
+        if entry.offset == 0 && base.map_or(true, |base| entry.start < base) {
+            base = Some(entry.start);
+        }
+        end = end.max(entry.end);
+    }
+    base.map(|base| base..end)
+}
+
+#[cfg(test)]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod tests {
+    use core::{
+        mem::{self, MaybeUninit},
+        ptr,
+    };
+    use std::ffi::{c_void, CString};
+
+    use clap::Parser;
+    use libafl::{bolts::cli::FuzzerOptions, inputs::BytesInput};
+    use serial_test::serial;
+
+    use backtrace::Backtrace;
+    use frida_gum::{interceptor::Interceptor, Gum, ModuleMap, NativePointer};
+
+    use super::{
+        is_misaligned_access, is_use_after_free, main_executable_range, walk_self_maps,
+        AsanRuntime, CustomAllocatorKind, MapEntry, ASAN_SAVE_REGISTER_COUNT,
+    };
+    use crate::{
+        alloc::{AllocationMetadata, PoisonKind},
+        asan::{
+            errors::{AsanError, AsanErrorClass, AsanErrorPolicy, AsanErrors, ASAN_ERRORS},
+            hook_funcs::is_overlapping_copy,
+        },
+        helper::FridaRuntime,
+    };
+
+    /// The currently installed `SIGSEGV` handler
+    fn segv_handler() -> libc::sighandler_t {
+        let mut action = MaybeUninit::<libc::sigaction>::zeroed();
+        assert_eq!(
+            unsafe { libc::sigaction(libc::SIGSEGV, ptr::null(), action.as_mut_ptr()) },
+            0
+        );
+        unsafe { action.assume_init() }.sa_sigaction
+    }
+
+    #[test]
+    #[serial]
+    fn test_new_keeps_signal_disposition() {
+        let before = segv_handler();
+        let runtime = AsanRuntime::new(FuzzerOptions::parse_from(["test", "-i", "corpus"]));
+        assert_eq!(segv_handler(), before);
+        drop(runtime);
+    }
+
+    #[test]
+    #[serial]
+    fn test_register_thread_idempotent() {
+        let _gum = Gum::obtain();
+        let mut runtime = AsanRuntime::new(FuzzerOptions::parse_from(["test", "-i", "corpus"]));
+        assert!(runtime.register_thread());
+        let ranges = runtime.registered_threads[&std::thread::current().id()].clone();
+        // the ranges are unchanged, so nothing is mapped again
+        assert!(!runtime.register_thread());
+        assert_eq!(
+            runtime.registered_threads[&std::thread::current().id()],
+            ranges
+        );
+
+        // a changed range is mapped, and stays unpoisoned
+        let frame = [0u64; 8];
+        let start = frame.as_ptr() as usize;
+        let mut changed = ranges.clone();
+        changed.push(start..start + core::mem::size_of_val(&frame));
+        assert!(runtime.register_thread_ranges(changed.clone()));
+        assert!(!runtime.register_thread_ranges(changed));
+        assert!(!runtime.is_poisoned(start, core::mem::size_of_val(&frame)));
+        assert!(runtime.register_thread_ranges(ranges));
+        assert!(!runtime.register_thread());
+    }
+
+    #[test]
+    #[serial]
+    fn test_poison_stack_sub_range() {
+        let mut runtime = AsanRuntime::new(FuzzerOptions::parse_from(["test", "-i", "corpus"]));
+        let frame = [0u64; 8];
+        let start = frame.as_ptr() as usize;
+        let size = core::mem::size_of_val(&frame);
+        runtime.unpoison_stack(start, size);
+        assert!(!runtime.is_poisoned(start, size));
+
+        runtime.poison_stack(start + 16, 24);
+        assert!(!runtime.is_poisoned(start, 16));
+        assert!(runtime.is_poisoned(start + 16, 1));
+        assert!(runtime.is_poisoned(start + 39, 1));
+        assert!(runtime.is_poisoned(start, size));
+        assert!(!runtime.is_poisoned(start + 40, 24));
+
+        runtime.unpoison_stack(start + 16, 24);
+        assert!(!runtime.is_poisoned(start, size));
+    }
+
+    #[test]
+    #[serial]
+    fn test_report_path() {
+        let _gum = Gum::obtain();
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus", "--continue-on-error"]);
+        let path = std::env::temp_dir().join(format!("asan_report_{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut runtime = AsanRuntime::new(options);
+        runtime.set_report_path(path.clone());
+
+        let pc = test_report_path as usize;
+        for _ in 0..2 {
+            AsanErrors::get_mut().report_error(AsanError::Unknown((
+                [0; ASAN_SAVE_REGISTER_COUNT],
+                pc,
+                (None, None, 0, 0),
+                Backtrace::new(),
+            )));
+        }
+        unsafe { ASAN_ERRORS = None };
+
+        let report = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(report.matches(" Memory error detected! ").count(), 2);
+        assert_eq!(report.matches(" REGISTERS ").count(), 2);
+        #[cfg(target_arch = "x86_64")]
+        assert!(report.contains(&format!("Rip: 0x{pc:016x}")));
+        #[cfg(target_arch = "aarch64")]
+        assert!(report.contains(&format!("pc : 0x{pc:016x}")));
+    }
+
+    #[test]
+    #[serial]
+    fn test_overlapping_memcpy() {
+        let _gum = Gum::obtain();
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus", "--continue-on-error"]);
+        let path = std::env::temp_dir().join(format!("asan_memcpy_{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut runtime = AsanRuntime::new(options);
+        runtime.set_report_path(path.clone());
+
+        let buf = [0_u8; 32];
+        let start = buf.as_ptr() as usize;
+        let pc = test_overlapping_memcpy as usize;
+        // disjoint, or only adjacent, ranges
+        assert!(!runtime.check_overlapping_copy("memcpy", pc, start, start + 16, 16));
+        assert!(!runtime.check_overlapping_copy("memcpy", pc, start + 16, start, 16));
+        assert!(!runtime.check_overlapping_copy("memcpy", pc, start, start + 8, 0));
+        assert!(AsanErrors::get_mut().is_empty());
+        // overlapping ranges, in both directions
+        assert!(runtime.check_overlapping_copy("memcpy", pc, start, start + 8, 16));
+        assert!(runtime.check_overlapping_copy("memcpy", pc, start + 8, start, 16));
+        assert!(runtime.check_overlapping_copy("memcpy", pc, start, start, 1));
+        assert_eq!(AsanErrors::get_mut().len(), 3);
+        unsafe { ASAN_ERRORS = None };
+
+        let report = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(report.matches("overlapping memcpy").count(), 3);
+        assert!(report.contains(&format!(
+            "destination {start:#016x}, source {:#016x}, size: 0x10",
+            start + 8
+        )));
+
+        assert!(!is_overlapping_copy(usize::MAX - 7, 0, 8));
+        assert!(is_overlapping_copy(usize::MAX - 7, usize::MAX - 3, 8));
+    }
+
+    #[test]
+    #[serial]
+    fn test_continue_on_error() {
+        let _gum = Gum::obtain();
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut runtime = AsanRuntime::new(options);
+        assert!(!runtime.continue_on_error());
+
+        let report = || {
+            AsanErrors::get_mut().report_error(AsanError::Unknown((
+                [0; ASAN_SAVE_REGISTER_COUNT],
+                test_continue_on_error as usize,
+                (None, None, 0, 0),
+                Backtrace::new(),
+            )));
+        };
+        assert!(std::panic::catch_unwind(report).is_err());
+
+        runtime.set_continue_on_error(true);
+        assert!(runtime.continue_on_error());
+        assert!(std::panic::catch_unwind(report).is_ok());
+        assert!(std::panic::catch_unwind(report).is_ok());
+
+        runtime.set_continue_on_error(false);
+        assert!(std::panic::catch_unwind(report).is_err());
+        assert_eq!(AsanErrors::get_mut().len(), 4);
+        unsafe { ASAN_ERRORS = None };
+    }
+
+    #[test]
+    #[serial]
+    fn test_reported_faults() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus", "--continue-on-error"]);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut runtime = AsanRuntime::new(options);
+        let ptr = runtime.hook_malloc(16) as usize;
+        let pc = test_reported_faults as usize;
+
+        // an overflow into the redzone is reported once per instruction and address
+        assert!(!runtime.is_reported_fault(pc, ptr + 16));
+        assert!(runtime.is_reported_fault(pc, ptr + 16));
+        assert!(!runtime.is_reported_fault(pc, ptr + 17));
+        assert!(!runtime.is_reported_fault(pc + 4, ptr + 16));
+        // the redzone stays poisoned, so other accesses to it are still caught
+        assert!(runtime.is_poisoned(ptr + 16, 1));
+
+        // the next run reports it again
+        runtime.post_exec(&BytesInput::new(vec![0; 4])).unwrap();
+        assert!(!runtime.is_reported_fault(pc, ptr + 16));
+        unsafe { ASAN_ERRORS = None };
+    }
+
+    #[test]
+    #[serial]
+    fn test_error_policy() {
+        let _gum = Gum::obtain();
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut runtime = AsanRuntime::new(options);
+        assert_eq!(
+            runtime.error_policy(AsanErrorClass::UnallocatedFree),
+            AsanErrorPolicy::Abort
+        );
+
+        runtime.set_error_policy(AsanErrorClass::UnallocatedFree, AsanErrorPolicy::Report);
+        runtime.set_error_policy(AsanErrorClass::Leak, AsanErrorPolicy::Ignore);
+        let unallocated_free = || {
+            AsanErrors::get_mut()
+                .report_error(AsanError::UnallocatedFree((0x1000, Backtrace::new())));
+        };
+        let leak = || {
+            AsanErrors::get_mut()
+                .report_error(AsanError::Leak((0x1000, AllocationMetadata::default())));
+        };
+        let unknown = || {
+            AsanErrors::get_mut().report_error(AsanError::Unknown((
+                [0; ASAN_SAVE_REGISTER_COUNT],
+                test_error_policy as usize,
+                (None, None, 0, 0),
+                Backtrace::new(),
+            )));
+        };
+
+        // reported, but the target keeps running
+        assert!(std::panic::catch_unwind(unallocated_free).is_ok());
+        assert_eq!(AsanErrors::get_mut().len(), 1);
+        // neither reported, nor crashing
+        assert!(std::panic::catch_unwind(leak).is_ok());
+        assert_eq!(AsanErrors::get_mut().len(), 1);
+        // classes without a policy still crash
+        assert!(std::panic::catch_unwind(unknown).is_err());
+        assert_eq!(AsanErrors::get_mut().len(), 2);
+
+        // an explicit policy overrides continue-on-error
+        runtime.set_continue_on_error(true);
+        runtime.set_error_policy(AsanErrorClass::UnallocatedFree, AsanErrorPolicy::Abort);
+        assert!(std::panic::catch_unwind(unallocated_free).is_err());
+        assert!(std::panic::catch_unwind(unknown).is_ok());
+        assert_eq!(
+            runtime.error_policy(AsanErrorClass::Unknown),
+            AsanErrorPolicy::Report
+        );
+        assert_eq!(AsanErrors::get_mut().len(), 4);
+        unsafe { ASAN_ERRORS = None };
+    }
+
+    #[cfg(not(target_vendor = "apple"))]
+    #[test]
+    #[serial]
+    fn test_reallocarray_overflow() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut runtime = AsanRuntime::new(options);
+
+        let ptr = runtime.hook_malloc(16);
+        unsafe { ptr.cast::<u8>().write(0x42) };
+        assert!(runtime.hook_reallocarray(ptr, usize::MAX / 2, 4).is_null());
+        assert_eq!(
+            std::io::Error::last_os_error().raw_os_error(),
+            Some(libc::ENOMEM)
+        );
+        // the original allocation is left untouched
+        assert_eq!(unsafe { ptr.cast::<u8>().read() }, 0x42);
+
+        let ptr = runtime.hook_reallocarray(ptr, 4, 8);
+        assert!(!ptr.is_null());
+        assert_eq!(unsafe { ptr.cast::<u8>().read() }, 0x42);
+        assert_eq!(runtime.allocator().get_usable_size(ptr), 32);
+        runtime.hook_free(ptr);
+        assert!(AsanErrors::get_mut().is_empty());
+        unsafe { ASAN_ERRORS = None };
+    }
+
+    #[test]
+    #[serial]
+    fn test_poison_kinds() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut runtime = AsanRuntime::new(options);
+
+        let freed = runtime.hook_malloc(32) as usize;
+        let live = runtime.hook_malloc(32) as usize;
+        runtime.hook_free(freed as *mut _);
+        let allocator = runtime.allocator();
+        assert_eq!(allocator.poison_kind(freed + 8), Some(PoisonKind::Freed));
+        assert_eq!(allocator.poison_kind(live + 8), None);
+        assert_eq!(
+            allocator.poison_kind(live + 32),
+            Some(PoisonKind::HeapRedzone)
+        );
+        assert_eq!(
+            allocator.poison_kind(live - 1),
+            Some(PoisonKind::HeapRedzone)
+        );
+        assert_eq!(PoisonKind::from_magic(0xfd), Some(PoisonKind::Freed));
+        assert_eq!(PoisonKind::HeapRedzone.magic(), 0xfa);
+
+        // the freed region is a use-after-free, the redzone an overflow, whatever the closest
+        // allocation found by the handler
+        let freed_metadata = AllocationMetadata {
+            freed: true,
+            ..AllocationMetadata::default()
+        };
+        for metadata in [&freed_metadata, &AllocationMetadata::default()] {
+            assert!(is_use_after_free(
+                allocator.poison_kind(freed + 8),
+                metadata
+            ));
+            assert!(!is_use_after_free(
+                allocator.poison_kind(live + 32),
+                metadata
+            ));
+        }
+
+        let frame = [0u64; 4];
+        let start = frame.as_ptr() as usize;
+        runtime.unpoison_stack(start, 32);
+        runtime.poison_stack(start + 8, 16);
+        assert_eq!(
+            runtime.allocator().poison_kind(start + 8),
+            Some(PoisonKind::StackRedzone)
+        );
+        assert_eq!(runtime.allocator().poison_kind(start), None);
+        runtime.unpoison_stack(start + 8, 16);
+        assert_eq!(runtime.allocator().poison_kind(start + 8), None);
+
+        runtime.hook_free(live as *mut _);
+        unsafe { ASAN_ERRORS = None };
+    }
+
+    #[test]
+    #[serial]
+    fn test_aligned_alloc() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut runtime = AsanRuntime::new(options);
+
+        let ptr = runtime.hook_aligned_alloc(64, 128);
+        assert!(!ptr.is_null());
+        assert_eq!(ptr as usize % 64, 0);
+        assert_eq!(runtime.allocator().get_usable_size(ptr), 128);
+        runtime.hook_free(ptr);
+
+        // like glibc, the size does not have to be a multiple of the alignment
+        let ptr = runtime.hook_aligned_alloc(64, 100);
+        assert!(!ptr.is_null());
+        assert_eq!(ptr as usize % 64, 0);
+        assert_eq!(runtime.allocator().get_usable_size(ptr), 100);
+        runtime.hook_free(ptr);
+
+        // the alignment must be a power of two
+        assert!(runtime.hook_aligned_alloc(48, 96).is_null());
+        assert_eq!(
+            std::io::Error::last_os_error().raw_os_error(),
+            Some(libc::EINVAL)
+        );
+        assert!(AsanErrors::get_mut().is_empty());
+        unsafe { ASAN_ERRORS = None };
+    }
+
+    #[test]
+    #[serial]
+    fn test_allocations_survive_fork() {
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut runtime = AsanRuntime::new(options);
+
+        let ptr = runtime.hook_malloc(16);
+        unsafe { ptr.cast::<u8>().write_bytes(0x42, 16) };
+
+        // the child inherits the allocations and the shadow of the parent, copy-on-write
+        let pid = unsafe { libc::fork() };
+        assert!(pid >= 0);
+        if pid == 0 {
+            // only async-signal-safe operations, no allocations, in the child
+            let ok = !runtime.is_poisoned(ptr as usize, 16)
+                && runtime.is_poisoned(ptr as usize + 16, 1)
+                && runtime.allocator().get_usable_size(ptr) == 16
+                && unsafe { ptr.cast::<u8>().add(15).read() } == 0x42;
+            unsafe {
+                ptr.cast::<u8>().write(0x43);
+                libc::_exit(i32::from(!ok));
+            }
+        }
+
+        let mut status = 0;
+        assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+        assert!(libc::WIFEXITED(status));
+        assert_eq!(libc::WEXITSTATUS(status), 0);
+        // writes of the child are not visible in the parent
+        assert_eq!(unsafe { ptr.cast::<u8>().read() }, 0x42);
+
+        runtime.hook_free(ptr);
+        assert!(AsanErrors::get_mut().is_empty());
+        unsafe { ASAN_ERRORS = None };
+    }
+
+    #[test]
+    fn test_misaligned_access() {
+        for width in [2, 4, 8, 16] {
+            assert!(!is_misaligned_access(0x1000, width));
+            assert!(!is_misaligned_access(0x1000 + width, width));
+            assert!(is_misaligned_access(0x1001, width));
+            assert!(is_misaligned_access(0x1000 + width / 2, width));
+        }
+        // single bytes, and sizes other than the ones of atomics, are never misaligned
+        assert!(!is_misaligned_access(0x1001, 1));
+        assert!(!is_misaligned_access(0x1001, 6));
+        assert!(!is_misaligned_access(0x1001, 32));
+    }
+
+    #[test]
+    #[serial]
+    fn test_alignment_check_blobs() {
+        let mut runtime = AsanRuntime::new(FuzzerOptions::parse_from(["test", "-i", "corpus"]));
+        assert!(!runtime.check_alignment());
+        runtime.generate_instrumentation_blobs();
+        let blobs = |runtime: &AsanRuntime| {
+            [
+                runtime.blob_check_mem_byte().to_vec(),
+                runtime.blob_check_mem_halfword().to_vec(),
+                runtime.blob_check_mem_dword().to_vec(),
+                runtime.blob_check_mem_qword().to_vec(),
+                runtime.blob_check_mem_16bytes().to_vec(),
+            ]
+        };
+        let unchecked = blobs(&runtime);
+
+        runtime.set_check_alignment(true);
+        runtime.generate_instrumentation_blobs();
+        let checked = blobs(&runtime);
+
+        // single bytes are always aligned
+        assert_eq!(checked[0], unchecked[0]);
+        // the alignment check is prepended to the unchanged shadow check
+        for (checked, unchecked) in checked.iter().zip(&unchecked).skip(1) {
+            assert!(checked.len() > unchecked.len());
+            assert!(checked.ends_with(unchecked));
+        }
+        // test rdi, width - 1
+        #[cfg(target_arch = "x86_64")]
+        for (checked, width) in checked.iter().zip([1_u32, 2, 4, 8, 16]).skip(1) {
+            assert_eq!(checked[..3], [0x48, 0xf7, 0xc7]);
+            assert_eq!(checked[3..7], (width - 1).to_le_bytes());
+        }
+    }
+
+    #[test]
+    fn test_main_executable_range_pie() {
+        let maps = "\
+55d0c0a00000-55d0c0a02000 r--p 00000000 fd:01 1234 /usr/bin/target
+55d0c0a02000-55d0c0a08000 r-xp 00002000 fd:01 1234 /usr/bin/target
+55d0c0a08000-55d0c0a0a000 rw-p 00008000 fd:01 1234 /usr/bin/target
+55d0c1000000-55d0c1021000 rw-p 00000000 00:00 0 [heap]
+7f1e2a000000-7f1e2a028000 r--p 00000000 fd:01 5678 /usr/lib/libc.so.6
+";
+        assert_eq!(
+            main_executable_range(maps, "/usr/bin/target"),
+            Some(0x55d0_c0a0_0000..0x55d0_c0a0_a000)
+        );
+        assert_eq!(main_executable_range(maps, "/usr/bin/other"), None);
+    }
+
+    #[test]
+    fn test_main_executable_range_non_pie() {
+        let maps = "\
+00400000-00401000 r--p 00000000 fd:01 1234 /usr/bin/target
+00401000-00480000 r-xp 00001000 fd:01 1234 /usr/bin/target
+004a0000-004a8000 rw-p 000a0000 fd:01 1234 /usr/bin/target
+004a8000-004b0000 rw-p 00000000 00:00 0
+";
+        assert_eq!(
+            main_executable_range(maps, "/usr/bin/target"),
+            Some(0x40_0000..0x4a_8000)
+        );
+    }
+
+    #[test]
+    fn test_walk_self_maps() {
+        let maps = "\
+55d0c0a00000-55d0c0a02000 r--p 00000000 fd:01 1234 /usr/bin/my target
+7ffd3c4e0000-7ffd3c501000 rw-p 00000000 00:00 0 [stack]
+7f1e2c000000-7f1e2c021000 rw-p 00000000 00:00 0
+not a mapping
+";
+        assert_eq!(
+            walk_self_maps(maps),
+            vec![
+                MapEntry {
+                    start: 0x55d0_c0a0_0000,
+                    end: 0x55d0_c0a0_2000,
+                    perms: "r--p".to_string(),
+                    offset: 0,
+                    path: Some("/usr/bin/my target".to_string()),
+                },
+                MapEntry {
+                    start: 0x7ffd_3c4e_0000,
+                    end: 0x7ffd_3c50_1000,
+                    perms: "rw-p".to_string(),
+                    offset: 0,
+                    path: Some("[stack]".to_string()),
+                },
+                MapEntry {
+                    start: 0x7f1e_2c00_0000,
+                    end: 0x7f1e_2c02_1000,
+                    perms: "rw-p".to_string(),
+                    offset: 0,
+                    path: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mappings() {
+        let exe = std::fs::read_link("/proc/self/exe").unwrap();
+        let exe = exe.to_string_lossy();
+        let mappings = AsanRuntime::mappings().unwrap();
+        // the code of the test binary itself lies in an executable mapping of the executable
+        let code = mappings
+            .iter()
+            .find(|entry| entry.contains(test_mappings as usize))
+            .unwrap();
+        assert_eq!(code.path.as_deref(), Some(&*exe));
+        assert!(code.perms.contains('x'));
+    }
+
+    #[test]
+    fn test_main_executable_range_self() {
+        let exe = std::fs::read_link("/proc/self/exe").unwrap();
+        let maps = std::fs::read_to_string("/proc/self/maps").unwrap();
+        let range = main_executable_range(&maps, &exe.to_string_lossy()).unwrap();
+        // code of the test binary itself lies in the main executable
+        assert!(range.contains(&(test_main_executable_range_self as usize)));
+    }
+
+    /// Builds the sample library with a bespoke allocator into a temporary directory, with the C
+    /// compiler of `$CC`, or `cc`, returning its path
+    #[cfg(target_os = "linux")]
+    fn build_test_allocator() -> std::path::PathBuf {
+        let lib = std::env::temp_dir().join(format!("libtest_allocator_{}.so", std::process::id()));
+        let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+        let status = std::process::Command::new(cc)
+            .args(["-shared", "-fPIC", "-o"])
+            .arg(&lib)
+            .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/src/test_allocator.c"))
+            .status()
+            .unwrap();
+        assert!(
+            status.success(),
+            "Failed to build the test allocator library"
+        );
+        lib
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(target_os = "linux")]
+    fn test_hook_custom() {
+        let gum = Gum::obtain();
+        let options = FuzzerOptions::parse_from(["test", "-i", "corpus"]);
+        unsafe { ASAN_ERRORS = Some(AsanErrors::new(options.clone())) };
+        let mut runtime = Box::new(AsanRuntime::new(options));
+
+        let lib_path = build_test_allocator();
+        let path = lib_path.to_str().unwrap();
+        let c_path = CString::new(path).unwrap();
+        let lib = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW) };
+        assert!(!lib.is_null());
+        let symbol = |name: &str| {
+            let name = CString::new(name).unwrap();
+            let symbol = unsafe { libc::dlsym(lib, name.as_ptr()) };
+            assert!(!symbol.is_null());
+            symbol
+        };
+        let my_malloc: extern "C" fn(usize) -> *mut c_void =
+            unsafe { mem::transmute(symbol("my_malloc")) };
+        let my_free: extern "C" fn(*mut c_void) = unsafe { mem::transmute(symbol("my_free")) };
+
+        // unknown functions are rejected
+        assert!(runtime
+            .hook_custom(
+                path,
+                &[
+                    ("my_malloc", CustomAllocatorKind::Malloc),
+                    ("my_unknown_alloc", CustomAllocatorKind::Malloc),
+                ],
+            )
+            .is_err());
+        assert!(runtime.custom_allocators.is_empty());
+        runtime
+            .hook_custom(
+                path,
+                &[
+                    ("my_malloc", CustomAllocatorKind::Malloc),
+                    ("my_free", CustomAllocatorKind::Free),
+                ],
+            )
+            .unwrap();
+
+        // redirect the calls of this test executable, as if it was instrumented
+        runtime.module_map = Some(ModuleMap::new_from_names(&gum, &[]));
+        runtime.hook_main_executable().unwrap();
+        let mut interceptor = Interceptor::obtain(&gum);
+        runtime.hook_custom_allocators(&mut interceptor);
+
+        let ptr = my_malloc(24);
+        assert!(runtime.allocator().is_managed(ptr));
+        assert_eq!(runtime.stats().allocations, 1);
+        my_free(ptr);
+        assert_eq!(runtime.stats().frees, 1);
+
+        for custom in &runtime.custom_allocators {
+            interceptor.revert(NativePointer(custom.address as *mut c_void));
+        }
+        std::fs::remove_file(&lib_path).unwrap();
+        unsafe { ASAN_ERRORS = None };
+    }
+}
@@ -1,5 +1,12 @@
 //! Errors that can be caught by the `libafl_frida` address sanitizer.
-use std::{fmt::Debug, io::Write, marker::PhantomData};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs::OpenOptions,
+    io::Write,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
 
 use backtrace::Backtrace;
 use capstone::{arch::BuildsCapstone, Capstone};
@@ -19,7 +26,7 @@ use libafl::{
     Error, SerdeAny,
 };
 use serde::{Deserialize, Serialize};
-use termcolor::{Color, ColorSpec, WriteColor};
+use termcolor::{Color, ColorSpec, NoColor, WriteColor};
 
 #[cfg(target_arch = "x86_64")]
 use crate::asan::asan_rt::ASAN_SAVE_REGISTER_NAMES;
@@ -43,6 +50,9 @@ pub(crate) enum AsanError {
     WriteAfterFree(AsanReadWriteError),
     DoubleFree((usize, AllocationMetadata, Backtrace)),
     UnallocatedFree((usize, Backtrace)),
+    InteriorFree((usize, usize, AllocationMetadata, Backtrace)),
+    AllocationBudgetExceeded((usize, usize, usize, Backtrace)),
+    GuardCorruption((usize, usize, AllocationMetadata, Backtrace)),
     Unknown(
         (
             [usize; ASAN_SAVE_REGISTER_COUNT],
@@ -70,15 +80,106 @@ pub(crate) enum AsanError {
     ),
     BadFuncArgRead((String, usize, usize, usize, Backtrace)),
     BadFuncArgWrite((String, usize, usize, usize, Backtrace)),
+    OverlappingMemcpy((String, usize, usize, usize, usize, Backtrace)),
+    MisalignedAccess(
+        (
+            usize,
+            [usize; ASAN_SAVE_REGISTER_COUNT],
+            usize,
+            (Option<u16>, Option<u16>, usize, usize),
+            Backtrace,
+        ),
+    ),
+}
+
+/// The class of a memory error detected by the `libafl_frida` address sanitizer, to choose what
+/// happens on errors of the class, see [`AsanErrorPolicy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AsanErrorClass {
+    /// A heap out-of-bounds read
+    HeapOobRead,
+    /// A heap out-of-bounds write
+    HeapOobWrite,
+    /// A read of a released heap allocation
+    HeapUseAfterFreeRead,
+    /// A write to a released heap allocation
+    HeapUseAfterFreeWrite,
+    /// A release of an already released allocation
+    DoubleFree,
+    /// A release of an address that was never allocated
+    UnallocatedFree,
+    /// A release of a pointer into an allocation
+    InteriorFree,
+    /// An allocation exceeding the allocation budget
+    AllocationBudgetExceeded,
+    /// A corrupted heap guard, detected on release
+    GuardCorruption,
+    /// A stack out-of-bounds read, including reads of scopes poisoned with
+    /// [`crate::asan::asan_rt::AsanRuntime::poison_stack`]
+    StackOobRead,
+    /// A stack out-of-bounds write, including writes to scopes poisoned with
+    /// [`crate::asan::asan_rt::AsanRuntime::poison_stack`]
+    StackOobWrite,
+    /// A bad read by a hooked function, caused by its arguments
+    BadFuncArgRead,
+    /// A bad write by a hooked function, caused by its arguments
+    BadFuncArgWrite,
+    /// A `memcpy` with overlapping source and destination ranges
+    OverlappingMemcpy,
+    /// An in-bounds, but misaligned access
+    MisalignedAccess,
+    /// A leaked allocation
+    Leak,
+    /// An invalid access that could not be classified
+    Unknown,
+}
+
+/// What the address sanitizer does on a memory error, per [`AsanErrorClass`], see
+/// [`AsanErrors::set_policy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AsanErrorPolicy {
+    /// Report the error, then crash the target
+    #[default]
+    Abort,
+    /// Report the error, and let the target keep running
+    Report,
+    /// Neither report the error, nor record it, and let the target keep running
+    Ignore,
 }
 
 impl AsanError {
+    /// The class of this error
+    fn class(&self) -> AsanErrorClass {
+        match self {
+            AsanError::OobRead(_) => AsanErrorClass::HeapOobRead,
+            AsanError::OobWrite(_) => AsanErrorClass::HeapOobWrite,
+            AsanError::ReadAfterFree(_) => AsanErrorClass::HeapUseAfterFreeRead,
+            AsanError::WriteAfterFree(_) => AsanErrorClass::HeapUseAfterFreeWrite,
+            AsanError::DoubleFree(_) => AsanErrorClass::DoubleFree,
+            AsanError::UnallocatedFree(_) => AsanErrorClass::UnallocatedFree,
+            AsanError::InteriorFree(_) => AsanErrorClass::InteriorFree,
+            AsanError::AllocationBudgetExceeded(_) => AsanErrorClass::AllocationBudgetExceeded,
+            AsanError::GuardCorruption(_) => AsanErrorClass::GuardCorruption,
+            AsanError::Unknown(_) => AsanErrorClass::Unknown,
+            AsanError::Leak(_) => AsanErrorClass::Leak,
+            AsanError::StackOobRead(_) => AsanErrorClass::StackOobRead,
+            AsanError::StackOobWrite(_) => AsanErrorClass::StackOobWrite,
+            AsanError::BadFuncArgRead(_) => AsanErrorClass::BadFuncArgRead,
+            AsanError::BadFuncArgWrite(_) => AsanErrorClass::BadFuncArgWrite,
+            AsanError::OverlappingMemcpy(_) => AsanErrorClass::OverlappingMemcpy,
+            AsanError::MisalignedAccess(_) => AsanErrorClass::MisalignedAccess,
+        }
+    }
+
     fn description(&self) -> &str {
         match self {
             AsanError::OobRead(_) => "heap out-of-bounds read",
             AsanError::OobWrite(_) => "heap out-of-bounds write",
             AsanError::DoubleFree(_) => "double-free",
             AsanError::UnallocatedFree(_) => "unallocated-free",
+            AsanError::InteriorFree(_) => "free of interior pointer",
+            AsanError::AllocationBudgetExceeded(_) => "allocation budget exceeded",
+            AsanError::GuardCorruption(_) => "heap guard corruption",
             AsanError::WriteAfterFree(_) => "heap use-after-free write",
             AsanError::ReadAfterFree(_) => "heap use-after-free read",
             AsanError::Unknown(_) => "heap unknown",
@@ -87,6 +188,8 @@ impl AsanError {
             AsanError::StackOobWrite(_) => "stack out-of-bounds write",
             AsanError::BadFuncArgRead(_) => "function arg resulting in bad read",
             AsanError::BadFuncArgWrite(_) => "function arg resulting in bad write",
+            AsanError::OverlappingMemcpy(_) => "overlapping memcpy",
+            AsanError::MisalignedAccess(_) => "misaligned access",
         }
     }
 }
@@ -97,6 +200,10 @@ impl AsanError {
 pub struct AsanErrors {
     options: FuzzerOptions,
     errors: Vec<AsanError>,
+    /// The file reports are appended to, instead of printing them to stdout
+    report_path: Option<PathBuf>,
+    /// The policies set per error class, see [`AsanErrors::set_policy`]
+    policies: HashMap<AsanErrorClass, AsanErrorPolicy>,
 }
 
 impl AsanErrors {
@@ -106,6 +213,49 @@ impl AsanErrors {
         Self {
             options,
             errors: Vec::new(),
+            report_path: None,
+            policies: HashMap::new(),
+        }
+    }
+
+    /// Appends the reports of all further errors to the file at the given path, instead of
+    /// printing them to stdout. With `None`, reports are printed to stdout again.
+    pub fn set_report_path(&mut self, report_path: Option<PathBuf>) {
+        self.report_path = report_path;
+    }
+
+    /// The file reports are appended to, if set
+    #[must_use]
+    pub fn report_path(&self) -> Option<&Path> {
+        self.report_path.as_deref()
+    }
+
+    /// Sets if the target keeps running after an error is reported, instead of being crashed
+    pub fn set_continue_on_error(&mut self, continue_on_error: bool) {
+        self.options.continue_on_error = continue_on_error;
+    }
+
+    /// Returns `true` if the target keeps running after an error is reported
+    #[must_use]
+    pub fn continue_on_error(&self) -> bool {
+        self.options.continue_on_error
+    }
+
+    /// Sets what happens on errors of the given class, overriding [`AsanErrors::continue_on_error`]
+    /// for the class
+    pub fn set_policy(&mut self, class: AsanErrorClass, policy: AsanErrorPolicy) {
+        self.policies.insert(class, policy);
+    }
+
+    /// What happens on errors of the given class: the policy set with [`AsanErrors::set_policy`],
+    /// if any, else [`AsanErrorPolicy::Report`] if [`AsanErrors::continue_on_error`], else
+    /// [`AsanErrorPolicy::Abort`]
+    #[must_use]
+    pub fn policy(&self, class: AsanErrorClass) -> AsanErrorPolicy {
+        match self.policies.get(&class) {
+            Some(policy) => *policy,
+            None if self.options.continue_on_error => AsanErrorPolicy::Report,
+            None => AsanErrorPolicy::Abort,
         }
     }
 
@@ -135,9 +285,22 @@ impl AsanErrors {
     /// Report an error
     #[allow(clippy::too_many_lines)]
     pub(crate) fn report_error(&mut self, error: AsanError) {
+        let policy = self.policy(error.class());
+        if policy == AsanErrorPolicy::Ignore {
+            return;
+        }
         self.errors.push(error.clone());
 
-        let mut out_stream = default_output_stream();
+        let mut out_stream: Box<dyn WriteColor> = match &self.report_path {
+            Some(path) => match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => Box::new(NoColor::new(file)),
+                Err(err) => {
+                    log::error!("Failed to open ASAN report file {}: {err}", path.display());
+                    default_output_stream()
+                }
+            },
+            None => default_output_stream(),
+        };
         let output = out_stream.as_mut();
 
         let backtrace_printer = BacktracePrinter::new()
@@ -155,7 +318,11 @@ impl AsanErrors {
         output
             .set_color(ColorSpec::new().set_fg(Some(Color::Red)))
             .unwrap();
-        write!(output, "{}", error.description()).unwrap();
+        if let AsanError::MisalignedAccess((width, ..)) = &error {
+            write!(output, "misaligned {width}-byte access").unwrap();
+        } else {
+            write!(output, "{}", error.description()).unwrap();
+        }
         match error {
             AsanError::OobRead(mut error)
             | AsanError::OobWrite(mut error)
@@ -356,6 +523,15 @@ impl AsanErrors {
 
                 backtrace_printer.print_trace(&backtrace, output).unwrap();
             }
+            AsanError::OverlappingMemcpy((name, pc, dest, src, size, backtrace)) => {
+                writeln!(
+                    output,
+                    " in call to {name} at {pc:#x}, destination {dest:#016x}, source {src:#016x}, size: {size:#x}"
+                )
+                .unwrap();
+                output.reset().unwrap();
+                backtrace_printer.print_trace(&backtrace, output).unwrap();
+            }
             AsanError::DoubleFree((ptr, mut metadata, backtrace)) => {
                 writeln!(output, " of {ptr:?}").unwrap();
                 output.reset().unwrap();
@@ -392,6 +568,68 @@ impl AsanErrors {
                 output.reset().unwrap();
                 backtrace_printer.print_trace(&backtrace, output).unwrap();
             }
+            AsanError::InteriorFree((ptr, offset, mut metadata, backtrace)) => {
+                writeln!(output, " of {ptr:#016x}").unwrap();
+                output.reset().unwrap();
+                backtrace_printer.print_trace(&backtrace, output).unwrap();
+
+                #[allow(clippy::non_ascii_literal)]
+                writeln!(output, "{:━^100}", " ALLOCATION INFO ").unwrap();
+                writeln!(
+                    output,
+                    "pointer is at offset 0x{:x} into allocation at 0x{:x}, with size 0x{:x}",
+                    offset,
+                    metadata.address + 0x1000,
+                    metadata.size
+                )
+                .unwrap();
+                if metadata.is_malloc_zero {
+                    writeln!(output, "allocation was zero-sized").unwrap();
+                }
+
+                if let Some(backtrace) = metadata.allocation_site_backtrace.as_mut() {
+                    writeln!(output, "allocation site backtrace:").unwrap();
+                    backtrace.resolve();
+                    backtrace_printer.print_trace(backtrace, output).unwrap();
+                }
+            }
+            AsanError::AllocationBudgetExceeded((size, live_bytes, budget, backtrace)) => {
+                writeln!(
+                    output,
+                    " allocating 0x{size:x} bytes, with 0x{live_bytes:x} bytes live and a budget of 0x{budget:x}"
+                )
+                .unwrap();
+                output.reset().unwrap();
+                backtrace_printer.print_trace(&backtrace, output).unwrap();
+            }
+            AsanError::GuardCorruption((ptr, corrupted, mut metadata, backtrace)) => {
+                writeln!(output, " of allocation at {ptr:#016x}, detected on release").unwrap();
+                output.reset().unwrap();
+                backtrace_printer.print_trace(&backtrace, output).unwrap();
+
+                #[allow(clippy::non_ascii_literal)]
+                writeln!(output, "{:━^100}", " ALLOCATION INFO ").unwrap();
+                let offset: i64 = corrupted as i64 - ptr as i64;
+                let direction = if offset > 0 { "right" } else { "left" };
+                writeln!(
+                    output,
+                    "corrupted byte at {:#x} is {:#x} to the {} of the {:#x} byte allocation",
+                    corrupted,
+                    offset.unsigned_abs(),
+                    direction,
+                    metadata.size
+                )
+                .unwrap();
+                if metadata.is_malloc_zero {
+                    writeln!(output, "allocation was zero-sized").unwrap();
+                }
+
+                if let Some(backtrace) = metadata.allocation_site_backtrace.as_mut() {
+                    writeln!(output, "allocation site backtrace:").unwrap();
+                    backtrace.resolve();
+                    backtrace_printer.print_trace(backtrace, output).unwrap();
+                }
+            }
             AsanError::Leak((ptr, mut metadata)) => {
                 writeln!(output, " of {ptr:#016x}").unwrap();
                 output.reset().unwrap();
@@ -417,7 +655,8 @@ impl AsanErrors {
             }
             AsanError::Unknown((registers, pc, fault, backtrace))
             | AsanError::StackOobRead((registers, pc, fault, backtrace))
-            | AsanError::StackOobWrite((registers, pc, fault, backtrace)) => {
+            | AsanError::StackOobWrite((registers, pc, fault, backtrace))
+            | AsanError::MisalignedAccess((_, registers, pc, fault, backtrace)) => {
                 let (basereg, indexreg, _displacement, fault_address) = fault;
 
                 if let Some(module_details) = ModuleDetails::with_address(pc as u64) {
@@ -527,9 +766,11 @@ impl AsanErrors {
                 backtrace_printer.print_trace(&backtrace, output).unwrap();
             }
         };
+        // make sure the report is written before the target crashes
+        output.flush().unwrap();
 
         #[allow(clippy::manual_assert)]
-        if !self.options.continue_on_error {
+        if policy == AsanErrorPolicy::Abort {
             panic!("ASAN: Crashing target!");
         }
     }
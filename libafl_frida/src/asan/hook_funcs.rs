@@ -13,8 +13,54 @@ use crate::{
     },
 };
 
+/// Returns `true` if the `n` byte ranges at `dest` and `src` overlap
+pub(crate) fn is_overlapping_copy(dest: usize, src: usize, n: usize) -> bool {
+    n != 0 && dest < src.saturating_add(n) && src < dest.saturating_add(n)
+}
+
+/// Sets `errno` for the current thread, as allocator functions do on failure
+fn set_errno(value: i32) {
+    #[cfg(target_vendor = "apple")]
+    unsafe {
+        *libc::__error() = value;
+    }
+    #[cfg(target_os = "android")]
+    unsafe {
+        *libc::__errno() = value;
+    }
+    #[cfg(not(any(target_vendor = "apple", target_os = "android")))]
+    unsafe {
+        *libc::__errno_location() = value;
+    }
+}
+
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 impl AsanRuntime {
+    /// Reports an overlapping memcpy if the `n` byte ranges at `dest` and `src`, passed to `name`
+    /// called at `pc`, overlap. Returns `true` if they do.
+    #[allow(clippy::unused_self)]
+    pub(crate) fn check_overlapping_copy(
+        &self,
+        name: &str,
+        pc: usize,
+        dest: usize,
+        src: usize,
+        n: usize,
+    ) -> bool {
+        if !is_overlapping_copy(dest, src, n) {
+            return false;
+        }
+        AsanErrors::get_mut().report_error(AsanError::OverlappingMemcpy((
+            name.to_string(),
+            pc,
+            dest,
+            src,
+            n,
+            Backtrace::new(),
+        )));
+        true
+    }
+
     #[inline]
     pub fn hook_malloc(&mut self, size: usize) -> *mut c_void {
         unsafe { self.allocator_mut().alloc(size, 8) }
@@ -132,6 +178,33 @@ impl AsanRuntime {
         }
     }
 
+    #[cfg(not(target_vendor = "apple"))]
+    #[inline]
+    pub fn hook_reallocarray(
+        &mut self,
+        ptr: *mut c_void,
+        nmemb: usize,
+        size: usize,
+    ) -> *mut c_void {
+        match nmemb.checked_mul(size) {
+            Some(total) => self.hook_realloc(ptr, total),
+            None => {
+                // like `reallocarray`, leave the original allocation untouched
+                set_errno(libc::ENOMEM);
+                std::ptr::null_mut()
+            }
+        }
+    }
+
+    #[inline]
+    pub fn hook_aligned_alloc(&mut self, alignment: usize, size: usize) -> *mut c_void {
+        if !alignment.is_power_of_two() {
+            set_errno(libc::EINVAL);
+            return std::ptr::null_mut();
+        }
+        unsafe { self.allocator_mut().alloc(size, alignment) }
+    }
+
     #[inline]
     pub fn hook_check_free(&mut self, ptr: *mut c_void) -> bool {
         self.allocator_mut().is_managed(ptr)
@@ -419,6 +492,7 @@ impl AsanRuntime {
     pub fn hook_memcpy(&mut self, dest: *mut c_void, src: *const c_void, n: usize) -> *mut c_void {
         extern "C" {
             fn memcpy(dest: *mut c_void, src: *const c_void, n: usize) -> *mut c_void;
+            fn memmove(dest: *mut c_void, src: *const c_void, n: usize) -> *mut c_void;
         }
         if !(self.shadow_check_func().unwrap())(dest, n) {
             AsanErrors::get_mut().report_error(AsanError::BadFuncArgWrite((
@@ -438,7 +512,19 @@ impl AsanRuntime {
                 Backtrace::new(),
             )));
         }
-        unsafe { memcpy(dest, src, n) }
+        let overlapping = self.check_overlapping_copy(
+            "memcpy",
+            self.real_address_for_stalked(AsanRuntime::pc()),
+            dest as usize,
+            src as usize,
+            n,
+        );
+        if overlapping {
+            // the result of memcpy is undefined for overlapping ranges, so copy as memmove does
+            unsafe { memmove(dest, src, n) }
+        } else {
+            unsafe { memcpy(dest, src, n) }
+        }
     }
 
     #[inline]
@@ -0,0 +1,11 @@
+// A sample library with a bespoke allocator, used by the tests of `AsanRuntime::hook_custom`
+
+#include <stdlib.h>
+
+void *my_malloc(size_t size) {
+  return malloc(size);
+}
+
+void my_free(void *ptr) {
+  free(ptr);
+}